@@ -0,0 +1,205 @@
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use tree_sitter::Node;
+
+use crate::config::ClassificationConfig;
+use crate::Param;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataClass { Pii, Financial, Credential }
+
+const DEFAULT_PII: &[&str] = &[
+    "pii", "ssn", "social_security_number", "dob", "date_of_birth", "birth_date", "birthday",
+    "first_name", "last_name", "full_name", "legal_name", "email", "phone", "phone_number", "mobile",
+    "address", "street", "address_line", "city", "zip", "zip_code", "postal_code", "passport",
+    "drivers_license", "license_number", "tax_id", "tin", "itin", "ein", "national_id", "marital_status",
+];
+const DEFAULT_FINANCIAL: &[&str] = &[
+    "income", "annual_income", "monthly_income", "salary", "wages", "account_number", "bank_account",
+    "routing_number", "aba", "iban", "swift", "credit_score", "fico", "card_number", "cvv", "net_worth",
+    "dti", "debt_to_income", "bank_statement",
+];
+const DEFAULT_CREDENTIAL: &[&str] = &[
+    "password", "passwd", "token", "secret", "api_key", "private_key", "otp", "pin", "session_id", "jwt",
+];
+// Types whose instances are borrower data as a whole, even if no single field name gives it away
+const DEFAULT_SENSITIVE_TYPES: &[&str] = &["borrower", "applicant", "loan_application", "co_borrower", "guarantor"];
+
+/// Term lists per data class, matched against the words of identifiers, properties and type names.
+/// Terms are compared on normalized words, so `date_of_birth` matches `dateOfBirth`, `DATE_OF_BIRTH`
+/// and `borrowerDateOfBirth`, but `ssn` does not match `lessons`.
+#[derive(Debug, Clone)]
+pub struct Taxonomy {
+    terms: Vec<(DataClass, Vec<String>)>,
+    sensitive_types: Vec<String>,
+}
+
+impl Default for Taxonomy {
+    fn default() -> Self {
+        Taxonomy::from_config(&ClassificationConfig::default())
+    }
+}
+
+impl Taxonomy {
+    /// Build a taxonomy from `[classification]`; configured terms extend the defaults unless `replace_defaults`.
+    pub fn from_config(cfg: &ClassificationConfig) -> Self {
+        let merge = |defaults: &[&str], extra: &[String]| -> Vec<String> {
+            let base = if cfg.replace_defaults { vec![] } else { defaults.iter().map(|t| normalize(t)).collect() };
+            base.into_iter().chain(extra.iter().map(|t| normalize(t))).collect()
+        };
+        Taxonomy {
+            terms: vec![
+                (DataClass::Pii, merge(DEFAULT_PII, &cfg.pii)),
+                (DataClass::Financial, merge(DEFAULT_FINANCIAL, &cfg.financial)),
+                (DataClass::Credential, merge(DEFAULT_CREDENTIAL, &cfg.credential)),
+            ],
+            sensitive_types: merge(DEFAULT_SENSITIVE_TYPES, &cfg.sensitive_types),
+        }
+    }
+
+    /// Classes of a single identifier or property name (`borrowerSsn` → {pii}).
+    pub fn classify_identifier(&self, ident: &str) -> BTreeSet<DataClass> {
+        let w = words(ident);
+        self.terms
+            .iter()
+            .filter(|(_, terms)| terms.iter().any(|t| matches_words(&w, t)))
+            .map(|(c, _)| *c)
+            .collect()
+    }
+
+    /// Classes of every identifier-like token in a snippet of code or a CFG label.
+    pub fn classify_text(&self, text: &str) -> BTreeSet<DataClass> {
+        identifiers(text).flat_map(|t| self.classify_identifier(t)).collect()
+    }
+
+    /// True if a type name denotes borrower data as a whole (`BorrowerDto`, `LoanApplicationInput`).
+    pub fn is_sensitive_type(&self, type_name: &str) -> bool {
+        let w = words(type_name);
+        self.sensitive_types.iter().any(|t| matches_words(&w, t))
+    }
+}
+
+/// A classified field of an interface, type literal or class (e.g. `BorrowerDto.dob` → pii)
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeField {
+    pub type_name: String,
+    pub field: String,
+    pub classes: Vec<DataClass>,
+    pub line: usize,
+}
+
+/// Collect classified fields from `interface X {..}`, `type X = {..}` and class property declarations.
+pub fn collect_type_fields(code: &str, root: Node, tax: &Taxonomy) -> Vec<TypeField> {
+    let mut out = Vec::new();
+    let mut stack = vec![root];
+    while let Some(n) = stack.pop() {
+        for i in 0..n.child_count() { stack.push(n.child(i).unwrap()); }
+        let body = match n.kind() {
+            "interface_declaration" | "class_declaration" | "abstract_class_declaration" => n.child_by_field_name("body"),
+            "type_alias_declaration" => n.child_by_field_name("value").filter(|v| v.kind() == "object_type"),
+            _ => None,
+        };
+        let (Some(body), Some(name)) = (body, n.child_by_field_name("name")) else { continue };
+        let type_name = &code[name.start_byte()..name.end_byte()];
+        for i in 0..body.named_child_count() {
+            let f = body.named_child(i).unwrap();
+            if !matches!(f.kind(), "property_signature" | "public_field_definition") { continue; }
+            let Some(fname) = f.child_by_field_name("name") else { continue };
+            let field = code[fname.start_byte()..fname.end_byte()].to_string();
+            let classes: Vec<DataClass> = tax.classify_identifier(&field).into_iter().collect();
+            if classes.is_empty() { continue; }
+            out.push(TypeField { type_name: type_name.to_string(), field, classes, line: f.start_position().row + 1 });
+        }
+    }
+    out.sort_by(|a, b| (a.line, &a.field).cmp(&(b.line, &b.field)));
+    out
+}
+
+/// Repo-wide type name → classes of data it carries (union of its classified fields)
+#[derive(Debug, Default, Clone)]
+pub struct TypeCatalogue {
+    types: HashMap<String, BTreeSet<DataClass>>,
+}
+
+impl TypeCatalogue {
+    pub fn add(&mut self, fields: &[TypeField]) {
+        for f in fields {
+            self.types.entry(f.type_name.clone()).or_default().extend(f.classes.iter().copied());
+        }
+    }
+
+    /// Classes carried by a type annotation such as `BorrowerDto`, `BorrowerDto[]` or `Partial<BorrowerDto>`.
+    pub fn classes_of(&self, ty: &str, tax: &Taxonomy) -> BTreeSet<DataClass> {
+        let mut out = BTreeSet::new();
+        for t in identifiers(ty) {
+            if let Some(c) = self.types.get(t) { out.extend(c.iter().copied()); }
+            if tax.is_sensitive_type(t) { out.insert(DataClass::Pii); }
+        }
+        out
+    }
+}
+
+/// Parameters of a function that carry classified data, by name or by declared type.
+pub fn sensitive_params(params: &[Param], tax: &Taxonomy, types: &TypeCatalogue) -> Vec<(String, BTreeSet<DataClass>)> {
+    params
+        .iter()
+        .filter_map(|p| {
+            let mut classes = tax.classify_identifier(&p.name);
+            if let Some(ty) = &p.ty { classes.extend(types.classes_of(ty, tax)); }
+            (!classes.is_empty()).then(|| (p.name.clone(), classes))
+        })
+        .collect()
+}
+
+/// Classes of a CFG label: classified identifiers in its text plus any sensitive parameter it mentions.
+pub fn classify_label(label: &str, params: &[(String, BTreeSet<DataClass>)], tax: &Taxonomy) -> BTreeSet<DataClass> {
+    let mut out = tax.classify_text(label);
+    for tok in identifiers(label) {
+        if let Some((_, c)) = params.iter().find(|(p, _)| p == tok) { out.extend(c.iter().copied()); }
+    }
+    out
+}
+
+pub(crate) fn identifiers(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$')).filter(|t| !t.is_empty())
+}
+
+fn normalize(term: &str) -> String {
+    term.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase()
+}
+
+// Split camelCase / PascalCase / snake_case / kebab-case / SCREAMING_CASE into lowercase words.
+// Acronyms stay together: `userSSNHash` → [user, ssn, hash].
+pub(crate) fn words(ident: &str) -> Vec<String> {
+    let chars: Vec<char> = ident.chars().collect();
+    let mut out = Vec::new();
+    let mut cur = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !cur.is_empty() { out.push(std::mem::take(&mut cur)); }
+            continue;
+        }
+        let prev = if i > 0 { chars[i - 1] } else { ' ' };
+        let next = chars.get(i + 1).copied().unwrap_or(' ');
+        let boundary = c.is_ascii_uppercase()
+            && (prev.is_ascii_lowercase() || prev.is_ascii_digit() || (prev.is_ascii_uppercase() && next.is_ascii_lowercase()));
+        if boundary && !cur.is_empty() { out.push(std::mem::take(&mut cur)); }
+        cur.push(c.to_ascii_lowercase());
+    }
+    if !cur.is_empty() { out.push(cur); }
+    out
+}
+
+// A (normalized) term matches if some run of consecutive words spells it exactly
+fn matches_words(words: &[String], term: &str) -> bool {
+    for i in 0..words.len() {
+        let mut acc = String::new();
+        for w in &words[i..] {
+            acc.push_str(w);
+            if acc.len() > term.len() || !term.starts_with(acc.as_str()) { break; }
+            if acc == term { return true; }
+        }
+    }
+    false
+}
@@ -0,0 +1,227 @@
+use serde::Serialize;
+use tree_sitter::Node;
+
+/// Inputs that drive one operand of a branch condition either way. Values are TypeScript expressions:
+/// literals, or relative to the other operand (`officer.limit + 1`) when that isn't a literal.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Boundary {
+    pub subject: String,
+    /// compare, length, equals, null, typeof, in, instanceof, truthy, iterable
+    pub kind: &'static str,
+    pub when_true: Vec<String>,
+    pub when_false: Vec<String>,
+}
+
+impl Boundary {
+    fn flip(self) -> Self {
+        Boundary { when_true: self.when_false, when_false: self.when_true, ..self }
+    }
+
+    /// The parameter the subject hangs off (`loan` for `loan.amount`), if it is one of `params`
+    pub fn param<'a>(&self, params: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+        let root = self.subject.split(['.', '[', '?']).next().unwrap_or_default();
+        params.into_iter().find(|p| *p == root)
+    }
+
+    /// A value is concrete when it is a literal rather than an expression over other inputs
+    pub fn is_concrete(value: &str) -> bool {
+        let v = value.trim_start_matches('-');
+        v.parse::<f64>().is_ok()
+            || ["null", "undefined", "true", "false", "[]", "{}"].contains(&v)
+            || v.starts_with(['\'', '"', '`', '[', '{'])
+            || v.starts_with("new ")
+    }
+}
+
+/// A parsed If/Loop condition: its full text (labels keep only the first line) and the inputs behind it
+#[derive(Debug, Clone, Serialize)]
+pub struct Condition {
+    pub node: usize,
+    pub text: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<Boundary>,
+}
+
+fn text(code: &str, n: Node) -> String {
+    code[n.start_byte()..n.end_byte()].to_string()
+}
+
+/// Boundary inputs for a condition expression. `&&`/`||` collect both sides; `!` swaps true and false.
+pub fn boundaries(code: &str, expr: Node) -> Vec<Boundary> {
+    match expr.kind() {
+        "parenthesized_expression" => expr.named_child(0).map(|e| boundaries(code, e)).unwrap_or_default(),
+        "unary_expression" if text(code, expr).starts_with('!') => expr
+            .child_by_field_name("argument")
+            .map(|a| boundaries(code, a).into_iter().map(Boundary::flip).collect())
+            .unwrap_or_default(),
+        "binary_expression" => {
+            let (Some(l), Some(op), Some(r)) = (
+                expr.child_by_field_name("left"),
+                expr.child_by_field_name("operator"),
+                expr.child_by_field_name("right"),
+            ) else { return vec![] };
+            let op = text(code, op);
+            match op.as_str() {
+                "&&" | "||" => [boundaries(code, l), boundaries(code, r)].concat(),
+                "in" => vec![Boundary {
+                    subject: text(code, r),
+                    kind: "in",
+                    when_true: vec![format!("{{ {}: {{}} }}", unquote(&text(code, l)))],
+                    when_false: vec!["{}".to_string()],
+                }],
+                "instanceof" => vec![Boundary {
+                    subject: text(code, l),
+                    kind: "instanceof",
+                    when_true: vec![format!("new {}()", text(code, r))],
+                    when_false: vec!["{}".to_string()],
+                }],
+                _ => comparison(code, l, &op, r).into_iter().collect(),
+            }
+        }
+        "member_expression" if text(code, expr).ends_with(".length") => vec![Boundary {
+            subject: text(code, expr).trim_end_matches(".length").to_string(),
+            kind: "length",
+            when_true: vec![sized(1)],
+            when_false: vec![sized(0)],
+        }],
+        "identifier" | "member_expression" | "subscript_expression" => vec![Boundary {
+            subject: text(code, expr),
+            kind: "truthy",
+            when_true: vec!["{}".to_string()],
+            when_false: vec!["undefined".to_string(), "null".to_string()],
+        }],
+        _ => vec![],
+    }
+}
+
+/// `for (const doc of loan.documents)`: no items skips the body, one item runs it once
+pub fn iterable_boundary(code: &str, right: Node) -> Boundary {
+    Boundary { subject: text(code, right), kind: "iterable", when_true: vec!["[{}]".to_string()], when_false: vec!["[]".to_string()] }
+}
+
+/// `[{}, {}]` for a loop over `subject` that should run `n` times
+pub fn items(n: usize) -> String {
+    format!("[{}]", vec!["{}"; n].join(", "))
+}
+
+fn unquote(s: &str) -> &str {
+    s.trim_matches(|c| c == '\'' || c == '"' || c == '`')
+}
+
+// A literal operand, normalized: numbers, strings, booleans, null/undefined
+fn literal(code: &str, n: Node) -> Option<String> {
+    let t = text(code, n);
+    match n.kind() {
+        "number" | "string" | "true" | "false" | "null" => Some(t),
+        "undefined" => Some(t),
+        "identifier" if t == "undefined" => Some(t),
+        "template_string" if !t.contains("${") => Some(t),
+        "unary_expression" if t.starts_with('-') && t[1..].trim().parse::<f64>().is_ok() => Some(t.replace(' ', "")),
+        _ => None,
+    }
+}
+
+fn number(lit: &str) -> Option<(f64, bool)> {
+    lit.parse::<f64>().ok().map(|v| (v, !lit.contains(['.', 'e', 'E'])))
+}
+
+fn fmt(v: f64, int: bool) -> String {
+    if int { format!("{}", v.round() as i64) } else { format!("{}", (v * 100.0).round() / 100.0) }
+}
+
+fn comparison(code: &str, l: Node, op: &str, r: Node) -> Option<Boundary> {
+    // keep the literal on the right: `10 < x` reads as `x > 10`
+    let (subject, op, other) = match (literal(code, l), literal(code, r)) {
+        (Some(_), None) => (r, mirror(op)?, l),
+        _ => (l, op, r),
+    };
+    // typeof x === 'string'
+    if subject.kind() == "unary_expression" && text(code, subject).starts_with("typeof")
+        && let (Some(arg), Some(lit)) = (subject.child_by_field_name("argument"), literal(code, other))
+    {
+        let ty = unquote(&lit);
+        let sample = match ty {
+            "string" => "'text'", "number" => "1", "boolean" => "true", "object" => "{}",
+            "function" => "() => {}", "undefined" => "undefined", "bigint" => "1n", _ => return None,
+        };
+        let other_sample = if ty == "string" { "1" } else { "'text'" };
+        let b = Boundary { subject: text(code, arg), kind: "typeof", when_true: vec![sample.to_string()], when_false: vec![other_sample.to_string()] };
+        return Some(if op.starts_with("!") { b.flip() } else { b });
+    }
+
+    // x.length > 0 is about x: strings/arrays of that many items
+    let (subj, kind) = match text(code, subject).strip_suffix(".length") {
+        Some(s) => (s.to_string(), "length"),
+        None => (text(code, subject), "compare"),
+    };
+    let lit = literal(code, other);
+    let b = match (op, lit.as_deref()) {
+        ("===" | "==" | "!==" | "!=", Some(v @ ("null" | "undefined"))) => {
+            // loose equality matches both
+            let mut when_true = vec![v.to_string()];
+            if op.len() == 2 { when_true.push(if v == "null" { "undefined" } else { "null" }.to_string()); }
+            Boundary { subject: subj, kind: "null", when_true, when_false: vec!["{}".to_string()] }
+        }
+        ("===" | "==" | "!==" | "!=", Some(v)) if kind == "length" => {
+            let n = v.parse::<usize>().ok()?;
+            Boundary { subject: subj, kind, when_true: vec![sized(n)], when_false: vec![sized(n + 1)] }
+        }
+        ("===" | "==" | "!==" | "!=", Some(v)) => {
+            let other = match v {
+                "true" => "false".to_string(),
+                "false" => "true".to_string(),
+                _ => match number(v) {
+                    Some((n, int)) => fmt(n + 1.0, int),
+                    None => "''".to_string(),
+                },
+            };
+            Boundary { subject: subj, kind: "equals", when_true: vec![v.to_string()], when_false: vec![other] }
+        }
+        (">" | ">=" | "<" | "<=", Some(v)) => {
+            let (n, int) = number(v)?;
+            let step = if int { 1.0 } else { 0.01 };
+            // typical values either side of the boundary
+            let above = if n > 0.0 { n * 10.0 } else { n + 100.0 };
+            let below = if n > 0.0 { 0.0 } else { n - 100.0 };
+            let val = |x: f64| if kind == "length" { sized(x.max(0.0) as usize) } else { fmt(x, int) };
+            let (t, f) = match op {
+                ">" => (vec![n + step, above], vec![n, below]),
+                ">=" => (vec![n, above], vec![n - step, below]),
+                "<" => (vec![n - step, below], vec![n, above]),
+                _ => (vec![n, below], vec![n + step, above]),
+            };
+            let mut when_true: Vec<String> = t.into_iter().map(val).collect();
+            let mut when_false: Vec<String> = f.into_iter().map(val).collect();
+            when_true.dedup();
+            when_false.dedup();
+            Boundary { subject: subj, kind, when_true, when_false }
+        }
+        // against another input: relative to it
+        (">" | ">=" | "<" | "<=" | "===" | "==" | "!==" | "!=", None) if kind == "compare" => {
+            let o = text(code, other);
+            let (t, f) = match op {
+                ">" => (format!("{} + 1", o), o),
+                ">=" => (o.clone(), format!("{} - 1", o)),
+                "<" => (format!("{} - 1", o), o),
+                "<=" => (o.clone(), format!("{} + 1", o)),
+                _ => (o.clone(), "undefined".to_string()),
+            };
+            Boundary { subject: subj, kind, when_true: vec![t], when_false: vec![f] }
+        }
+        _ => return None,
+    };
+    Some(if op.starts_with('!') { b.flip() } else { b })
+}
+
+/// `'x'.repeat(n)` stands in for something of length n; callers that know it's an array swap in `items(n)`
+pub fn sized(n: usize) -> String {
+    format!("'x'.repeat({})", n)
+}
+
+fn mirror(op: &str) -> Option<&str> {
+    Some(match op {
+        ">" => "<", "<" => ">", ">=" => "<=", "<=" => ">=",
+        "===" | "==" | "!==" | "!=" => op,
+        _ => return None,
+    })
+}
@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::findings::Severity;
+
+/// Settings read from config.toml. Unknown keys are ignored so existing configs keep working.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub classification: ClassificationConfig,
+    pub paths: PathsConfig,
+    pub queries: BTreeMap<String, QueryConfig>,
+}
+
+/// `[classification]` — extra (or replacement) terms for the data-classification taxonomy.
+///
+/// ```toml
+/// [classification]
+/// pii = ["member_number"]
+/// financial = ["apr", "payoff_amount"]
+/// credential = ["plaid_access_token"]
+/// sensitive_types = ["CoBorrower"]
+/// replace_defaults = false
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct ClassificationConfig {
+    pub replace_defaults: bool,
+    pub pii: Vec<String>,
+    pub financial: Vec<String>,
+    pub credential: Vec<String>,
+    pub sensitive_types: Vec<String>,
+}
+
+/// `[paths]` — bounds for path enumeration, so large functions don't explode.
+///
+/// ```toml
+/// [paths]
+/// max_paths = 256    # per function, per kind (simple / prime)
+/// loop_unroll = 2    # loops are taken 0, 1 and N times
+/// max_length = 64    # nodes on one path
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PathsConfig {
+    pub max_paths: usize,
+    pub loop_unroll: usize,
+    pub max_length: usize,
+}
+
+impl Default for PathsConfig {
+    fn default() -> Self {
+        PathsConfig { max_paths: 256, loop_unroll: 2, max_length: 64 }
+    }
+}
+
+/// `[queries.<name>]` — a saved query (see `query::Query`), run by `casesmith query <name>` and, as a
+/// custom check, by `generate`: every hit becomes a finding whose rule is the query's name.
+///
+/// ```toml
+/// [queries.secret-sent-in-clear]
+/// query = "functions where NET after SECRET without CRYPTO"
+/// severity = "high"
+/// message = "A secret reaches an outbound request without being encrypted"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct QueryConfig {
+    pub query: String,
+    pub severity: Severity,
+    /// Finding message; defaults to what matched
+    pub message: Option<String>,
+}
+
+impl Default for QueryConfig {
+    fn default() -> Self {
+        QueryConfig { query: String::new(), severity: Severity::Medium, message: None }
+    }
+}
+
+/// Parse config.toml contents, falling back to defaults (with a warning) if it isn't valid TOML.
+pub fn parse_config(config: &str) -> Config {
+    match toml::from_str(config) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Warning: Could not parse config.toml: {}", e);
+            Config::default()
+        }
+    }
+}
+
+/// One `KEY=value` line of a dotenv file
+#[derive(Debug, Clone)]
+pub struct DotenvEntry {
+    pub key: String,
+    pub value: String,
+    pub line: usize,
+}
+
+/// Parse a dotenv file (`.env.example`): `KEY=value` lines, ignoring comments, blanks and `export ` prefixes.
+pub fn parse_dotenv(text: &str) -> Vec<DotenvEntry> {
+    text.lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|(line, l)| {
+            let (k, v) = l.trim_start_matches("export ").split_once('=')?;
+            let v = v.split(" #").next().unwrap_or("").trim().trim_matches(|c| c == '"' || c == '\'');
+            Some(DotenvEntry { key: k.trim().to_string(), value: v.to_string(), line })
+        })
+        .collect()
+}
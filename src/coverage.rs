@@ -0,0 +1,248 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::paths::{CfgPath, FunctionPaths};
+use crate::testgen::path_name;
+use crate::SimpleCfg;
+
+type Pos = (usize, usize);
+
+/// Hit counts for one source file, from Istanbul's coverage-final.json or an lcov.info record.
+/// Istanbul gives statement ranges and `if` branches by position; lcov only gives lines.
+#[derive(Debug, Default, Clone)]
+pub struct FileCoverage {
+    statements: Vec<(Pos, Pos, u64)>,
+    functions: Vec<(Pos, Pos, u64)>,
+    // `if` branches: where the statement starts and the [true, false] counts
+    branches: Vec<(Pos, Vec<u64>)>,
+    lines: HashMap<usize, u64>,
+    // lcov BRDA: line → counts in branch order
+    line_branches: HashMap<usize, Vec<u64>>,
+}
+
+impl FileCoverage {
+    /// Executions of the code starting at `at`: the innermost statement around it, else its line.
+    /// None when nothing instrumented covers it.
+    pub fn hits(&self, at: Pos) -> Option<u64> {
+        self.statements
+            .iter()
+            .filter(|(s, e, _)| *s <= at && at <= *e)
+            .max_by_key(|(s, _, _)| *s)
+            .map(|(_, _, n)| *n)
+            .or_else(|| self.lines.get(&at.0).copied())
+    }
+
+    /// Calls of the innermost function around `at`
+    pub fn function_hits(&self, at: Pos) -> Option<u64> {
+        self.functions
+            .iter()
+            .filter(|(s, e, _)| *s <= at && at <= *e)
+            .max_by_key(|(s, _, _)| *s)
+            .map(|(_, _, n)| *n)
+    }
+
+    /// [true, false] counts for the `if` starting at `at`
+    pub fn branch(&self, at: Pos) -> Option<(u64, u64)> {
+        let counts = self.branches.iter().find(|(p, _)| *p == at).map(|(_, c)| c)
+            .or_else(|| self.line_branches.get(&at.0))?;
+        Some((counts.first().copied().unwrap_or(0), counts.get(1).copied().unwrap_or(0)))
+    }
+}
+
+/// Parse Istanbul JSON (`coverage-final.json`, as written by Jest, nyc, and Vitest's v8 and istanbul providers)
+pub fn parse_istanbul(json: &str) -> Result<HashMap<String, FileCoverage>, String> {
+    let v: serde_json::Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let files = v.as_object().ok_or("expected an object keyed by file")?;
+    let pos = |p: &serde_json::Value| -> Pos {
+        (p["line"].as_u64().unwrap_or(0) as usize, p["column"].as_u64().unwrap_or(0) as usize)
+    };
+    let mut out = HashMap::new();
+    for (file, fc) in files {
+        // older istanbul wraps each file in { data: .. }
+        let fc = fc.get("data").unwrap_or(fc);
+        let mut cov = FileCoverage::default();
+        if let (Some(map), Some(s)) = (fc["statementMap"].as_object(), fc["s"].as_object()) {
+            for (id, loc) in map {
+                let n = s.get(id).and_then(|n| n.as_u64()).unwrap_or(0);
+                cov.statements.push((pos(&loc["start"]), pos(&loc["end"]), n));
+            }
+        }
+        if let (Some(map), Some(f)) = (fc["fnMap"].as_object(), fc["f"].as_object()) {
+            for (id, func) in map {
+                let n = f.get(id).and_then(|n| n.as_u64()).unwrap_or(0);
+                cov.functions.push((pos(&func["loc"]["start"]), pos(&func["loc"]["end"]), n));
+            }
+        }
+        if let (Some(map), Some(b)) = (fc["branchMap"].as_object(), fc["b"].as_object()) {
+            for (id, br) in map.iter().filter(|(_, br)| br["type"] == "if") {
+                let counts = b.get(id).and_then(|c| c.as_array())
+                    .map(|c| c.iter().map(|n| n.as_u64().unwrap_or(0)).collect())
+                    .unwrap_or_default();
+                cov.branches.push((pos(&br["loc"]["start"]), counts));
+            }
+        }
+        out.insert(file.clone(), cov);
+    }
+    Ok(out)
+}
+
+/// Parse lcov tracefile records (`SF:`, `FN:`/`FNDA:`, `DA:line,hits`, `BRDA:line,block,branch,taken`)
+pub fn parse_lcov(text: &str) -> HashMap<String, FileCoverage> {
+    let mut out = HashMap::new();
+    let mut file: Option<String> = None;
+    let mut cov = FileCoverage::default();
+    // FN gives where a function starts, FNDA how often it ran; lcov has no end, so a function runs to the next one
+    let mut fn_lines: Vec<(usize, String)> = Vec::new();
+    let mut fn_hits: HashMap<String, u64> = HashMap::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(sf) = line.strip_prefix("SF:") {
+            file = Some(sf.to_string());
+            cov = FileCoverage::default();
+            fn_lines.clear();
+            fn_hits.clear();
+        } else if let Some((l, name)) = line.strip_prefix("FN:").and_then(|f| f.split_once(','))
+            && let Ok(l) = l.parse()
+        {
+            fn_lines.push((l, name.to_string()));
+        } else if let Some((n, name)) = line.strip_prefix("FNDA:").and_then(|f| f.split_once(',')) {
+            fn_hits.insert(name.to_string(), n.parse().unwrap_or(0));
+        } else if let Some(da) = line.strip_prefix("DA:") {
+            let mut it = da.split(',');
+            if let (Some(Ok(l)), Some(Ok(n))) = (it.next().map(str::parse), it.next().map(str::parse)) {
+                cov.lines.insert(l, n);
+            }
+        } else if let Some(brda) = line.strip_prefix("BRDA:") {
+            let parts: Vec<&str> = brda.split(',').collect();
+            if let [l, _block, _branch, taken] = parts[..]
+                && let Ok(l) = l.parse()
+            {
+                cov.line_branches.entry(l).or_default().push(taken.parse().unwrap_or(0));
+            }
+        } else if line == "end_of_record"
+            && let Some(f) = file.take()
+        {
+            fn_lines.sort();
+            for (i, (l, name)) in fn_lines.iter().enumerate() {
+                let end = fn_lines.get(i + 1).map(|n| (n.0 - 1, usize::MAX)).unwrap_or((usize::MAX, 0));
+                cov.functions.push(((*l, 0), end, fn_hits.get(name).copied().unwrap_or(0)));
+            }
+            out.insert(f, std::mem::take(&mut cov));
+        }
+    }
+    out
+}
+
+/// The coverage entry for a source file: same path, or a path that ends with the file's repo-relative path
+pub fn coverage_for<'a>(cov: &'a HashMap<String, FileCoverage>, file: &Path, rel: &Path) -> Option<&'a FileCoverage> {
+    cov.iter()
+        .find(|(k, _)| Path::new(k) == file)
+        .or_else(|| cov.iter().find(|(k, _)| Path::new(k).ends_with(rel)))
+        .map(|(_, c)| c)
+}
+
+/// A tagged node no test runs
+#[derive(Debug, Clone, Serialize)]
+pub struct UntestedNode {
+    pub node: usize,
+    pub label: String,
+    pub line: usize,
+}
+
+/// A branch outcome no test takes
+#[derive(Debug, Clone, Serialize)]
+pub struct UntestedBranch {
+    pub node: usize,
+    pub condition: String,
+    pub taken: bool,
+    pub line: usize,
+}
+
+/// An enumerated path that can't have run: some node or branch outcome on it never executed
+#[derive(Debug, Clone, Serialize)]
+pub struct UntestedPath {
+    pub name: String,
+    pub path: CfgPath,
+}
+
+/// Coverage gaps in one function
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionCoverage {
+    pub file: String,
+    pub func: String,
+    pub paths: usize,
+    pub untested_paths: Vec<UntestedPath>,
+    pub untested_nodes: Vec<UntestedNode>,
+    pub untested_branches: Vec<UntestedBranch>,
+    /// Untested AUTH/AUTHZ checks, and ownership branches: gaps that should hold a release
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blockers: Vec<String>,
+}
+
+// Node kinds whose code not running under test is worth reporting
+const SECURITY: &[&str] = &["AUTH:", "AUTHZ:", "DB:", "NET:", "SDK:", "EXEC:", "EVAL:", "STORAGE:", "QUEUE:", "CRYPTO:", "SECRET:", "VALIDATE:"];
+
+/// Map coverage onto one function's CFG and paths.
+pub fn function_coverage(file: &str, func: &str, cfg: &SimpleCfg, paths: &FunctionPaths, cov: &FileCoverage) -> FunctionCoverage {
+    // decorators (route, guards) sit before the body and run when the class loads; they count as
+    // tested when the function they annotate is
+    let body = cfg.locs.first().copied().unwrap_or_default();
+    let hits: Vec<Option<u64>> = (0..cfg.nodes.len())
+        .map(|n| match cfg.locs.get(n) {
+            _ if n < 2 => None,
+            Some(&at) if at < body => cov.function_hits(body),
+            Some(&at) => cov.hits(at),
+            None => None,
+        })
+        .collect();
+    // outcome of a decision: `if` branch counts if there are any, else whether its successor ran
+    let arc = |src: usize, dst: usize, taken: bool| -> Option<u64> {
+        let at = cfg.locs.get(src).copied()?;
+        if cfg.nodes[src].starts_with("If:")
+            && let Some((t, f)) = cov.branch(at)
+        {
+            return Some(if taken { t } else { f });
+        }
+        hits[dst]
+    };
+
+    let mut untested_nodes = Vec::new();
+    let mut blockers = Vec::new();
+    for (n, label) in cfg.nodes.iter().enumerate() {
+        if hits[n] == Some(0) && SECURITY.iter().any(|p| label.starts_with(p)) {
+            let line = cfg.locs[n].0;
+            if label.starts_with("AUTH") { blockers.push(format!("line {}: {}", line, label)); }
+            untested_nodes.push(UntestedNode { node: n, label: label.clone(), line });
+        }
+    }
+
+    let mut untested_branches = Vec::new();
+    let mut seen = BTreeMap::new();
+    for &(src, dst, taken) in &cfg.branches {
+        if arc(src, dst, taken) != Some(0) || seen.insert((src, taken), ()).is_some() { continue; }
+        let condition = cfg.conditions.iter().find(|c| c.node == src).map(|c| c.text.clone()).unwrap_or_else(|| cfg.nodes[src].clone());
+        let line = cfg.locs[src].0;
+        // an ownership check is tagged AUTHZ right before its If
+        let guarded = cfg.edges.iter().any(|&(s, d)| d == src && cfg.nodes[s].starts_with("AUTHZ:"));
+        if guarded { blockers.push(format!("line {}: {} is never {}", line, condition, taken)); }
+        untested_branches.push(UntestedBranch { node: src, condition, taken, line });
+    }
+
+    let untested_paths = paths.simple.iter()
+        .filter(|p| {
+            p.nodes.iter().any(|&n| hits[n] == Some(0))
+                || p.nodes.windows(2).zip(p.outcomes()).any(|(w, o)| o.is_some_and(|t| arc(w[0], w[1], t) == Some(0)))
+        })
+        .map(|p| UntestedPath { name: path_name(p), path: p.clone() })
+        .collect();
+
+    FunctionCoverage {
+        file: file.to_string(),
+        func: func.to_string(),
+        paths: paths.simple.len(),
+        untested_paths,
+        untested_nodes,
+        untested_branches,
+        blockers,
+    }
+}
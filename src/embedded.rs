@@ -0,0 +1,510 @@
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use tree_sitter::Node;
+
+use crate::tag::call_name;
+
+/// Tables/columns (SQL) or operation fields (GraphQL) touched by a query embedded in a tagged CFG node.
+/// SQL entries are `table.column` (`table.*` for whole rows); GraphQL entries are field paths.
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddedQuery {
+    /// Index of the CFG node that runs the query
+    pub node: usize,
+    pub language: &'static str,
+    /// `select`/`insert`/... for SQL, `query GetBorrower`/`mutation` for GraphQL
+    pub operation: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reads: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub writes: Vec<String>,
+}
+
+impl EmbeddedQuery {
+    /// Short suffix for the CFG label: "[reads loans.id; writes borrowers.ssn]"
+    pub fn label_suffix(&self) -> String {
+        let mut parts = vec![];
+        if self.language == "graphql" { parts.push(self.operation.clone()); }
+        if !self.writes.is_empty() { parts.push(format!("writes {}", self.writes.join(", "))); }
+        if !self.reads.is_empty() { parts.push(format!("reads {}", self.reads.join(", "))); }
+        format!("[{}]", parts.join("; "))
+    }
+}
+
+// ---------------------------------------------------------------------------------------------
+// Locating embedded documents in calls
+
+/// SQL passed to a DB call: a string/template argument, a tagged template (`$queryRaw\`..\``,
+/// `sql\`..\``) or a same-file `const` holding one.
+pub fn embedded_sql(code: &str, call: Node) -> Vec<EmbeddedQuery> {
+    let Some(args) = call.child_by_field_name("arguments") else { return vec![] };
+    let candidate = if args.kind() == "template_string" { Some(args) } else { args.named_child(0) };
+    let Some(text) = candidate.and_then(|c| literal_text(code, c, &["sql", "Prisma.sql"], &mut vec![])) else { return vec![] };
+    parse_sql(&text)
+        .into_iter()
+        .map(|a| EmbeddedQuery { node: 0, language: "sql", operation: a.operation, reads: a.reads, writes: a.writes })
+        .collect()
+}
+
+/// GraphQL documents used by a call: `client.query({ query: GET_BORROWER })`, `useMutation(CREATE)`,
+/// `request(url, gql\`..\`)`. Documents are `gql`/`graphql` tagged templates, inline or in a const.
+pub fn graphql_ops(code: &str, call: Node) -> Vec<EmbeddedQuery> {
+    let Some(args) = call.child_by_field_name("arguments") else { return vec![] };
+    if args.kind() == "template_string" { return vec![]; }
+    let mut docs = vec![];
+    for i in 0..args.named_child_count() {
+        let a = args.named_child(i).unwrap();
+        let mut values = vec![a];
+        if a.kind() == "object" {
+            values = (0..a.named_child_count())
+                .filter_map(|j| a.named_child(j))
+                .filter_map(|p| p.child_by_field_name("value"))
+                .collect();
+        }
+        for v in values {
+            let doc = if is_tagged(code, v, &["gql", "graphql"]) { Some(v) } else { resolve_const(code, v, &mut vec![]) };
+            if let Some(d) = doc.filter(|d| is_tagged(code, *d, &["gql", "graphql"])) {
+                docs.extend(literal_text(code, d, &["gql", "graphql"], &mut vec![]));
+            }
+        }
+    }
+    docs.iter()
+        .flat_map(|d| parse_graphql(d))
+        .map(|op| {
+            let operation = match &op.name { Some(n) => format!("{} {}", op.kind, n), None => op.kind.clone() };
+            let writes = if op.kind == "mutation" { op.roots.clone() } else { vec![] };
+            EmbeddedQuery { node: 0, language: "graphql", operation, reads: op.fields, writes }
+        })
+        .collect()
+}
+
+fn is_tagged(code: &str, n: Node, tags: &[&str]) -> bool {
+    n.kind() == "call_expression"
+        && n.child_by_field_name("arguments").is_some_and(|a| a.kind() == "template_string")
+        && call_name(code, n).is_some_and(|t| tags.contains(&t.as_str()))
+}
+
+// Text of a string / template literal (substitutions become `?`), looking through tags and consts
+fn literal_text(code: &str, n: Node, tags: &[&str], seen: &mut Vec<usize>) -> Option<String> {
+    match n.kind() {
+        "string" => {
+            let raw = &code[n.start_byte()..n.end_byte()];
+            Some(raw.get(1..raw.len() - 1).unwrap_or("").to_string())
+        }
+        "template_string" => {
+            let mut out = String::new();
+            let mut pos = n.start_byte() + 1;
+            for i in 0..n.named_child_count() {
+                let c = n.named_child(i).unwrap();
+                if c.kind() == "template_substitution" {
+                    out.push_str(&code[pos..c.start_byte()]);
+                    out.push('?');
+                    pos = c.end_byte();
+                }
+            }
+            out.push_str(&code[pos..n.end_byte().saturating_sub(1).max(pos)]);
+            Some(out)
+        }
+        "call_expression" if is_tagged(code, n, tags) => literal_text(code, n.child_by_field_name("arguments")?, tags, seen),
+        "identifier" => resolve_const(code, n, seen).and_then(|v| literal_text(code, v, tags, seen)),
+        _ => None,
+    }
+}
+
+/// Value of the `const`/`let` declaration an identifier refers to: the declarator of that name in the
+/// nearest enclosing block that has one, not looking into functions nested in it. `seen` holds the
+/// declarators already followed, so `const a = b; const b = a;` ends instead of recursing forever.
+pub(crate) fn resolve_const<'t>(code: &str, ident: Node<'t>, seen: &mut Vec<usize>) -> Option<Node<'t>> {
+    if ident.kind() != "identifier" { return None; }
+    let name = &code[ident.start_byte()..ident.end_byte()];
+    let mut scope = ident.parent();
+    while let Some(s) = scope {
+        if matches!(s.kind(), "statement_block" | "program")
+            && let Some(d) = declarator_in(code, s, name)
+        {
+            if seen.contains(&d.id()) { return None; }
+            seen.push(d.id());
+            return d.child_by_field_name("value");
+        }
+        scope = s.parent();
+    }
+    None
+}
+
+fn is_function(n: Node) -> bool {
+    matches!(n.kind(), "function_declaration" | "generator_function_declaration" | "function_expression" | "function" | "arrow_function" | "method_definition")
+}
+
+// First declarator of `name` under `scope`, skipping nested functions (their consts aren't visible here)
+fn declarator_in<'t>(code: &str, scope: Node<'t>, name: &str) -> Option<Node<'t>> {
+    let mut stack = vec![scope];
+    while let Some(n) = stack.pop() {
+        if n.kind() == "variable_declarator"
+            && n.child_by_field_name("name").is_some_and(|x| &code[x.start_byte()..x.end_byte()] == name)
+        {
+            return Some(n);
+        }
+        for i in (0..n.named_child_count()).rev() {
+            let c = n.named_child(i).unwrap();
+            if !is_function(c) { stack.push(c); }
+        }
+    }
+    None
+}
+
+// ---------------------------------------------------------------------------------------------
+// SQL
+
+/// Tables and columns one SQL statement reads and writes
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlAccess {
+    pub operation: String,
+    pub reads: Vec<String>,
+    pub writes: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok { Ident(String), Kw(String), Punct(char), Lit }
+
+const SQL_KEYWORDS: &[&str] = &[
+    "SELECT", "FROM", "WHERE", "JOIN", "INNER", "LEFT", "RIGHT", "FULL", "OUTER", "CROSS", "ON", "AND", "OR", "NOT",
+    "IN", "IS", "NULL", "AS", "INSERT", "INTO", "VALUES", "UPDATE", "SET", "DELETE", "RETURNING", "GROUP", "ORDER",
+    "BY", "HAVING", "LIMIT", "OFFSET", "DISTINCT", "WITH", "UNION", "ALL", "LIKE", "ILIKE", "BETWEEN", "CASE", "WHEN",
+    "THEN", "ELSE", "END", "ASC", "DESC", "EXISTS", "TRUE", "FALSE", "CONFLICT", "DO", "NOTHING", "DEFAULT", "USING",
+    "FOR", "INTERVAL", "ANY", "MERGE", "MATCHED", "NULLS", "FIRST", "LAST", "EXCLUDED",
+];
+
+fn sql_tokens(sql: &str) -> Vec<Tok> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() { i += 1; continue; }
+        if c == '-' && chars.get(i + 1) == Some(&'-') {
+            while i < chars.len() && chars[i] != '\n' { i += 1; }
+            continue;
+        }
+        if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') { i += 1; }
+            i += 2;
+            continue;
+        }
+        if c == '\'' {
+            i += 1;
+            while i < chars.len() && chars[i] != '\'' { i += 1; }
+            i += 1;
+            out.push(Tok::Lit);
+            continue;
+        }
+        if c.is_ascii_digit() || c == '?' || ((c == '$' || c == ':' || c == '@') && chars.get(i + 1).is_some_and(|n| n.is_ascii_alphanumeric())) {
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '.') { i += 1; }
+            out.push(Tok::Lit);
+            continue;
+        }
+        if c.is_ascii_alphabetic() || c == '_' || c == '"' || c == '`' {
+            // identifier, possibly quoted and dotted: "loans"."id", b.ssn
+            let mut ident = String::new();
+            loop {
+                if i < chars.len() && (chars[i] == '"' || chars[i] == '`') {
+                    let q = chars[i];
+                    i += 1;
+                    while i < chars.len() && chars[i] != q { ident.push(chars[i]); i += 1; }
+                    i += 1;
+                } else {
+                    while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') { ident.push(chars[i]); i += 1; }
+                }
+                if i < chars.len() && chars[i] == '.' && chars.get(i + 1).is_some_and(|n| n.is_ascii_alphabetic() || *n == '_' || *n == '"' || *n == '*') {
+                    ident.push('.');
+                    i += 1;
+                    if chars[i] == '*' { ident.push('*'); i += 1; break; }
+                } else {
+                    break;
+                }
+            }
+            let upper = ident.to_uppercase();
+            out.push(if SQL_KEYWORDS.contains(&upper.as_str()) { Tok::Kw(upper) } else { Tok::Ident(ident) });
+            continue;
+        }
+        out.push(Tok::Punct(c));
+        i += 1;
+    }
+    out
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Clause { Start, SelectList, From, Reads, InsertTable, InsertCols, Values, UpdateTable, Set, DeleteFrom }
+
+/// Parse SQL text (template substitutions already replaced by `?`) into per-statement table/column access.
+/// This is a keyword-driven scan, not a full grammar: it understands SELECT/INSERT/UPDATE/DELETE with
+/// joins, aliases, SET lists, RETURNING and WHERE/ON/GROUP/ORDER column references.
+pub fn parse_sql(sql: &str) -> Vec<SqlAccess> {
+    let first = sql.split_whitespace().next().unwrap_or("").to_uppercase();
+    if !["SELECT", "INSERT", "UPDATE", "DELETE", "WITH"].contains(&first.as_str()) { return vec![]; }
+    sql_tokens(sql)
+        .split(|t| *t == Tok::Punct(';'))
+        .filter(|s| !s.is_empty())
+        .filter_map(parse_statement)
+        .collect()
+}
+
+fn parse_statement(toks: &[Tok]) -> Option<SqlAccess> {
+    let mut operation = None;
+    let mut clause = Clause::Start;
+    let mut tables: Vec<String> = vec![];
+    let mut aliases: HashMap<String, String> = HashMap::new();
+    let mut write_table: Option<String> = None;
+    let mut reads: Vec<String> = vec![];
+    let mut writes: Vec<String> = vec![];
+    let mut expect_table = false;
+    let mut expect_set_col = false;
+    let mut depth = 0i32;
+    let mut skip_alias = false;
+
+    for (i, t) in toks.iter().enumerate() {
+        let next_is_call = matches!(toks.get(i + 1), Some(Tok::Punct('(')));
+        match t {
+            Tok::Kw(k) => {
+                skip_alias = k == "AS";
+                match k.as_str() {
+                    "SELECT" => { operation.get_or_insert("select"); clause = Clause::SelectList; }
+                    "INSERT" => { operation.get_or_insert("insert"); clause = Clause::InsertTable; }
+                    "UPDATE" if clause == Clause::Start || operation.is_none() => {
+                        operation.get_or_insert("update");
+                        clause = Clause::UpdateTable;
+                        expect_table = true;
+                    }
+                    "UPDATE" => {} // ON CONFLICT DO UPDATE SET → handled by SET
+                    "DELETE" => { operation.get_or_insert("delete"); clause = Clause::DeleteFrom; }
+                    "INTO" if clause == Clause::InsertTable => expect_table = true,
+                    "FROM" if clause == Clause::DeleteFrom => expect_table = true,
+                    "FROM" | "JOIN" | "USING" => { clause = Clause::From; expect_table = true; }
+                    "SET" => {
+                        clause = Clause::Set;
+                        expect_set_col = true;
+                        if write_table.is_none() { write_table = tables.first().cloned(); }
+                    }
+                    "VALUES" => clause = Clause::Values,
+                    "WHERE" | "ON" | "GROUP" | "ORDER" | "HAVING" | "RETURNING" => clause = Clause::Reads,
+                    _ => {}
+                }
+            }
+            Tok::Punct('(') => {
+                depth += 1;
+                if clause == Clause::InsertTable && write_table.is_some() { clause = Clause::InsertCols; }
+            }
+            Tok::Punct(')') => {
+                depth -= 1;
+                if clause == Clause::InsertCols { clause = Clause::Values; }
+            }
+            Tok::Punct(',') => {
+                if clause == Clause::From { expect_table = true; }
+                if clause == Clause::Set && depth == 0 { expect_set_col = true; }
+                skip_alias = false;
+            }
+            Tok::Punct('=') => expect_set_col = false,
+            Tok::Ident(id) => {
+                if next_is_call && !expect_table { continue; }
+                if skip_alias { skip_alias = false; if clause != Clause::From { continue; } }
+                match clause {
+                    Clause::From | Clause::UpdateTable | Clause::DeleteFrom | Clause::InsertTable if expect_table => {
+                        tables.push(id.clone());
+                        if matches!(clause, Clause::UpdateTable | Clause::DeleteFrom | Clause::InsertTable) {
+                            write_table = Some(id.clone());
+                        }
+                        if clause == Clause::DeleteFrom { writes.push(format!("{}.*", id)); }
+                        expect_table = false;
+                    }
+                    Clause::From | Clause::UpdateTable | Clause::DeleteFrom => {
+                        // alias: FROM borrowers b / UPDATE loans AS l
+                        if let Some(t) = tables.last() { aliases.insert(id.clone(), t.clone()); }
+                    }
+                    Clause::InsertCols => writes.push(id.clone()),
+                    Clause::Set if expect_set_col => { writes.push(id.clone()); expect_set_col = false; }
+                    Clause::SelectList => {
+                        // `SELECT b.ssn s` — a bare identifier right after a column is its alias
+                        if matches!(toks.get(i.wrapping_sub(1)), Some(Tok::Ident(_))) { continue; }
+                        reads.push(id.clone());
+                    }
+                    Clause::Set | Clause::Reads | Clause::Values => reads.push(id.clone()),
+                    _ => {}
+                }
+            }
+            Tok::Punct('*') if clause == Clause::SelectList => reads.push("*".to_string()),
+            _ => {}
+        }
+    }
+
+    let operation = operation?.to_string();
+    let resolve = |col: &str, default_table: Option<&String>| -> Vec<String> {
+        match col.split_once('.') {
+            // ON CONFLICT .. SET x = EXCLUDED.x reads the proposed row, not a table
+            Some((q, _)) if q.eq_ignore_ascii_case("excluded") => vec![],
+            Some((q, c)) => vec![format!("{}.{}", aliases.get(q).unwrap_or(&q.to_string()), c)],
+            None if col == "*" => {
+                if tables.is_empty() { vec!["*".to_string()] } else { tables.iter().map(|t| format!("{}.*", t)).collect() }
+            }
+            None if col.contains('*') || tables.contains(&col.to_string()) || aliases.contains_key(col) => vec![],
+            None => vec![match default_table { Some(t) => format!("{}.{}", t, col), None => col.to_string() }],
+        }
+    };
+    let read_default = tables.first();
+    let write_default = write_table.as_ref().or(tables.first());
+    let reads: BTreeSet<String> = reads.iter().flat_map(|c| resolve(c, read_default)).collect();
+    let writes: BTreeSet<String> = writes
+        .iter()
+        .flat_map(|c| if c.ends_with(".*") { vec![c.clone()] } else { resolve(c, write_default) })
+        .collect();
+    let mut writes: Vec<String> = writes.into_iter().collect();
+    if writes.is_empty() && operation == "insert" && let Some(t) = &write_table { writes.push(format!("{}.*", t)); }
+    Some(SqlAccess { operation, reads: reads.into_iter().collect(), writes })
+}
+
+// ---------------------------------------------------------------------------------------------
+// GraphQL
+
+/// One operation from a GraphQL document: its top-level fields and every selected leaf path
+#[derive(Debug, Clone, PartialEq)]
+pub struct GqlOperation {
+    pub kind: String,
+    pub name: Option<String>,
+    pub roots: Vec<String>,
+    pub fields: Vec<String>,
+}
+
+fn gql_tokens(doc: &str) -> Vec<String> {
+    let chars: Vec<char> = doc.chars().collect();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() || c == ',' { i += 1; continue; }
+        if c == '#' {
+            while i < chars.len() && chars[i] != '\n' { i += 1; }
+            continue;
+        }
+        if c == '"' {
+            i += 1;
+            while i < chars.len() && chars[i] != '"' { if chars[i] == '\\' { i += 1; } i += 1; }
+            i += 1;
+            out.push("\"\"".to_string());
+            continue;
+        }
+        if c == '.' && chars.get(i + 1) == Some(&'.') && chars.get(i + 2) == Some(&'.') {
+            out.push("...".to_string());
+            i += 3;
+            continue;
+        }
+        if c.is_ascii_alphanumeric() || c == '_' || c == '$' || c == '?' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '$' || chars[i] == '?') { i += 1; }
+            out.push(chars[start..i].iter().collect());
+            continue;
+        }
+        out.push(c.to_string());
+        i += 1;
+    }
+    out
+}
+
+/// Parse the operations of a GraphQL document. Fragments are expanded where they are spread.
+pub fn parse_graphql(doc: &str) -> Vec<GqlOperation> {
+    let toks = gql_tokens(doc);
+    let mut fragments: HashMap<String, (usize, usize)> = HashMap::new();
+    let mut ops: Vec<(String, Option<String>, usize)> = vec![];
+    let mut i = 0;
+    while i < toks.len() {
+        match toks[i].as_str() {
+            "fragment" => {
+                let name = toks.get(i + 1).cloned().unwrap_or_default();
+                let Some(open) = toks[i..].iter().position(|t| t == "{").map(|p| p + i) else { break };
+                let close = matching(&toks, open, "{", "}");
+                fragments.insert(name, (open, close));
+                i = close + 1;
+            }
+            kind @ ("query" | "mutation" | "subscription") => {
+                let name = toks.get(i + 1).filter(|t| t.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')).cloned();
+                let Some(open) = toks[i..].iter().position(|t| t == "{").map(|p| p + i) else { break };
+                ops.push((kind.to_string(), name, open));
+                i = matching(&toks, open, "{", "}") + 1;
+            }
+            "{" => {
+                ops.push(("query".to_string(), None, i));
+                i = matching(&toks, i, "{", "}") + 1;
+            }
+            _ => i += 1,
+        }
+    }
+    ops.into_iter()
+        .map(|(kind, name, open)| {
+            let mut roots = vec![];
+            let mut fields = vec![];
+            walk_selection(&toks, open, "", &fragments, &mut roots, &mut fields, 0);
+            GqlOperation { kind, name, roots, fields }
+        })
+        .collect()
+}
+
+fn matching(toks: &[String], open: usize, o: &str, c: &str) -> usize {
+    let mut depth = 0;
+    for (j, t) in toks.iter().enumerate().skip(open) {
+        if t == o { depth += 1; }
+        if t == c { depth -= 1; if depth == 0 { return j; } }
+    }
+    toks.len().saturating_sub(1)
+}
+
+// Walk a `{ ... }` selection set starting at `open`, recording root field names and leaf paths
+fn walk_selection(
+    toks: &[String],
+    open: usize,
+    prefix: &str,
+    fragments: &HashMap<String, (usize, usize)>,
+    roots: &mut Vec<String>,
+    fields: &mut Vec<String>,
+    guard: usize,
+) {
+    if guard > 16 { return; }
+    let close = matching(toks, open, "{", "}");
+    let mut i = open + 1;
+    while i < close {
+        let t = &toks[i];
+        if t == "..." {
+            // fragment spread or inline fragment (`... on Type { }`)
+            if toks.get(i + 1).is_some_and(|n| n == "on") {
+                let inner = toks[i..close].iter().position(|t| t == "{").map(|p| p + i).unwrap_or(close);
+                walk_selection(toks, inner, prefix, fragments, roots, fields, guard + 1);
+                i = matching(toks, inner, "{", "}") + 1;
+            } else {
+                if let Some((fo, _)) = toks.get(i + 1).and_then(|n| fragments.get(n)) {
+                    walk_selection(toks, *fo, prefix, fragments, roots, fields, guard + 1);
+                }
+                i += 2;
+            }
+            continue;
+        }
+        if !t.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') { i += 1; continue; }
+        // alias: realName
+        let mut name = t.clone();
+        let mut j = i + 1;
+        if toks.get(j).is_some_and(|n| n == ":") {
+            name = toks.get(j + 1).cloned().unwrap_or_default();
+            j += 2;
+        }
+        if toks.get(j).is_some_and(|n| n == "(") { j = matching(toks, j, "(", ")") + 1; }
+        while toks.get(j).is_some_and(|n| n == "@") {
+            j += 2;
+            if toks.get(j).is_some_and(|n| n == "(") { j = matching(toks, j, "(", ")") + 1; }
+        }
+        let path = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+        if prefix.is_empty() { roots.push(name.clone()); }
+        if toks.get(j).is_some_and(|n| n == "{") {
+            walk_selection(toks, j, &path, fragments, roots, fields, guard + 1);
+            i = matching(toks, j, "{", "}") + 1;
+        } else {
+            if name != "__typename" { fields.push(path); }
+            i = j;
+        }
+    }
+}
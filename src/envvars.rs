@@ -0,0 +1,232 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use tree_sitter::Node;
+
+use crate::findings::{Finding, RepoFinding, Severity};
+use crate::outbound::env_name;
+use crate::tag::{call_name, enclosing_function};
+
+// Set by the platform rather than by our deployment config, so never expected in .env.example
+const AMBIENT: &[&str] = &["NODE_ENV", "TZ", "CI", "HOME", "PATH", "PWD", "HOSTNAME", "npm_package_version"];
+
+/// One place an env var or config key is read
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvRead {
+    pub name: String,
+    pub func: String,
+    pub line: usize,
+}
+
+/// A `registerAs('database', () => ({ host: process.env.DB_HOST }))` factory: config keys and the env vars behind them
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigNamespace {
+    pub namespace: String,
+    pub keys: Vec<(String, Option<String>)>,
+}
+
+/// Env vars and config keys read in one file, plus the config namespaces it registers.
+pub fn collect_env_reads(code: &str, root: Node) -> (Vec<EnvRead>, Vec<ConfigNamespace>) {
+    let mut reads = Vec::new();
+    let mut namespaces = Vec::new();
+    let mut stack = vec![root];
+    while let Some(n) = stack.pop() {
+        for i in (0..n.named_child_count()).rev() { stack.push(n.named_child(i).unwrap()); }
+        let line = n.start_position().row + 1;
+        match n.kind() {
+            "member_expression" | "subscript_expression" => {
+                if let Some(name) = env_name(code, n) {
+                    reads.push(EnvRead { name, func: reader(code, n), line });
+                }
+            }
+            // const { DB_HOST, DB_PORT = '5432' } = process.env;
+            "variable_declarator" => {
+                let is_env = n.child_by_field_name("value").is_some_and(|v| &code[v.start_byte()..v.end_byte()] == "process.env");
+                let Some(pat) = n.child_by_field_name("name").filter(|p| is_env && p.kind() == "object_pattern") else { continue };
+                for i in 0..pat.named_child_count() {
+                    let p = pat.named_child(i).unwrap();
+                    let key = match p.kind() {
+                        "shorthand_property_identifier_pattern" => Some(p),
+                        "pair_pattern" | "object_assignment_pattern" => p.child_by_field_name("key").or_else(|| p.child_by_field_name("left")),
+                        _ => None,
+                    };
+                    if let Some(k) = key {
+                        reads.push(EnvRead { name: code[k.start_byte()..k.end_byte()].to_string(), func: reader(code, n), line });
+                    }
+                }
+            }
+            "call_expression" => {
+                let name = call_name(code, n).unwrap_or_default();
+                let lower = name.to_lowercase();
+                let first = n.child_by_field_name("arguments").and_then(|a| a.named_child(0));
+                let key = first.filter(|a| a.kind() == "string").map(|a| unquote(&code[a.start_byte()..a.end_byte()]).to_string());
+                // configService.get('STRIPE_KEY') / this.config.getOrThrow<string>('database.host')
+                if (lower.ends_with(".get") || lower.ends_with(".getorthrow")) && lower.contains("config")
+                    && let Some(k) = key.clone()
+                {
+                    reads.push(EnvRead { name: k, func: reader(code, n), line });
+                }
+                if name == "registerAs"
+                    && let Some(ns) = key
+                {
+                    namespaces.push(ConfigNamespace { namespace: ns, keys: factory_keys(code, n) });
+                }
+            }
+            _ => {}
+        }
+    }
+    (reads, namespaces)
+}
+
+// Keys of the object a registerAs factory returns, with the env var each one reads
+fn factory_keys(code: &str, call: Node) -> Vec<(String, Option<String>)> {
+    let Some(factory) = call.child_by_field_name("arguments").and_then(|a| a.named_child(1)) else { return vec![] };
+    let mut obj = factory.child_by_field_name("body");
+    // `() => ({ .. })` or `() => { return { .. }; }`
+    while let Some(o) = obj.filter(|o| o.kind() != "object") {
+        obj = match o.kind() {
+            "parenthesized_expression" | "return_statement" => o.named_child(0),
+            "statement_block" => (0..o.named_child_count()).filter_map(|i| o.named_child(i)).find(|c| c.kind() == "return_statement"),
+            _ => None,
+        };
+    }
+    let Some(obj) = obj else { return vec![] };
+    let mut out = Vec::new();
+    for i in 0..obj.named_child_count() {
+        let pair = obj.named_child(i).unwrap();
+        let (Some(k), Some(v)) = (pair.child_by_field_name("key"), pair.child_by_field_name("value")) else { continue };
+        let mut env = None;
+        let mut stack = vec![v];
+        while let Some(x) = stack.pop() {
+            if env.is_none() { env = env_name(code, x); }
+            for j in 0..x.named_child_count() { stack.push(x.named_child(j).unwrap()); }
+        }
+        out.push((unquote(&code[k.start_byte()..k.end_byte()]).to_string(), env));
+    }
+    out
+}
+
+// The function a read belongs to; reads inside a registerAs factory are attributed to it
+fn reader(code: &str, n: Node) -> String {
+    let mut cur = n.parent();
+    while let Some(p) = cur {
+        if p.kind() == "call_expression" && call_name(code, p).as_deref() == Some("registerAs") {
+            let ns = p.child_by_field_name("arguments").and_then(|a| a.named_child(0)).map(|a| &code[a.start_byte()..a.end_byte()]);
+            return format!("registerAs({})", ns.unwrap_or(""));
+        }
+        cur = p.parent();
+    }
+    enclosing_function(code, n)
+}
+
+fn unquote(s: &str) -> &str {
+    s.trim_matches(|c| c == '\'' || c == '"' || c == '`')
+}
+
+/// Where a variable is read, across the repo
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadSite {
+    pub file: String,
+    pub func: String,
+    pub line: usize,
+}
+
+/// One env var or config key and every place that reads it
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvVar {
+    pub name: String,
+    /// `env` for env vars (`process.env.X`, `configService.get('X')`), `config` for namespaced keys (`database.host`)
+    pub kind: &'static str,
+    /// For a registered config key, the env var its factory reads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
+    /// Listed in .env.example; absent when there is no .env.example to check against
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub documented: Option<bool>,
+    pub reads: Vec<ReadSite>,
+}
+
+/// Repo-wide env var / config-key inventory, cross-checked against .env.example
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvInventory {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub documented_in: Option<String>,
+    pub vars: Vec<EnvVar>,
+    pub undocumented: Vec<String>,
+    pub unread: Vec<String>,
+}
+
+/// Build the inventory from per-file reads and registered namespaces. `documented` is the .env.example
+/// path and its `(key, line)` entries, if the repo has one; gaps either way become findings.
+pub fn env_inventory(
+    reads: &[(String, Vec<EnvRead>)],
+    namespaces: &[ConfigNamespace],
+    documented: Option<(&str, &[(String, usize)])>,
+) -> (EnvInventory, Vec<RepoFinding>) {
+    let backing: BTreeMap<String, Option<String>> = namespaces
+        .iter()
+        .flat_map(|ns| ns.keys.iter().map(move |(k, env)| (format!("{}.{}", ns.namespace, k), env.clone())))
+        .collect();
+    let mut vars: BTreeMap<String, EnvVar> = BTreeMap::new();
+    for (file, rs) in reads {
+        for r in rs {
+            let v = vars.entry(r.name.clone()).or_insert_with(|| EnvVar {
+                name: r.name.clone(),
+                kind: if r.name.contains('.') { "config" } else { "env" },
+                env: backing.get(&r.name).cloned().flatten(),
+                documented: None,
+                reads: vec![],
+            });
+            v.reads.push(ReadSite { file: file.clone(), func: r.func.clone(), line: r.line });
+        }
+    }
+
+    let mut findings = Vec::new();
+    let mut undocumented = Vec::new();
+    let mut unread = Vec::new();
+    if let Some((example, keys)) = documented {
+        let known: BTreeSet<&str> = keys.iter().map(|(k, _)| k.as_str()).collect();
+        for v in vars.values_mut().filter(|v| v.kind == "env") {
+            let doc = known.contains(v.name.as_str()) || AMBIENT.contains(&v.name.as_str());
+            v.documented = Some(doc);
+            if doc { continue; }
+            undocumented.push(v.name.clone());
+            let first = &v.reads[0];
+            findings.push(RepoFinding {
+                file: first.file.clone(),
+                func: first.func.clone(),
+                finding: Finding {
+                    rule: "undocumented-env".to_string(),
+                    severity: Severity::Low,
+                    line: first.line,
+                    message: format!("{} is read but not listed in {}", v.name, example),
+                    hint: Some(format!("add {}= to {} so deployments know to set it", v.name, example)),
+                },
+            });
+        }
+        for (k, line) in keys {
+            if vars.contains_key(k) { continue; }
+            unread.push(k.clone());
+            findings.push(RepoFinding {
+                file: example.to_string(),
+                func: "<module>".to_string(),
+                finding: Finding {
+                    rule: "unread-env".to_string(),
+                    severity: Severity::Info,
+                    line: *line,
+                    message: format!("{} is documented but never read", k),
+                    hint: None,
+                },
+            });
+        }
+    }
+    for v in vars.values_mut() {
+        v.reads.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+    }
+    let inventory = EnvInventory {
+        documented_in: documented.map(|(p, _)| p.to_string()),
+        vars: vars.into_values().collect(),
+        undocumented,
+        unread,
+    };
+    (inventory, findings)
+}
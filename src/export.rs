@@ -0,0 +1,240 @@
+use serde_json::Value;
+
+use crate::negative::{NegativeCase, RouteCases};
+use crate::paths::CfgPath;
+use crate::testgen::{negate, path_name};
+use crate::SimpleCfg;
+
+/// A test case for people rather than a runner: what must hold first, what to do, what should happen
+#[derive(Debug, Clone)]
+pub struct ManualCase {
+    pub title: String,
+    /// Where the case belongs: the function or route it tests
+    pub section: String,
+    /// Gherkin tags: `path`, `negative`, the negative case's category
+    pub tags: Vec<String>,
+    pub preconditions: Vec<String>,
+    pub steps: Vec<String>,
+    pub expected: Vec<String>,
+}
+
+// What each kind of tagged node does, in words
+fn kind(prefix: &str) -> &'static str {
+    match prefix {
+        "DB" => "Database call",
+        "NET" => "HTTP request",
+        "SDK" => "Vendor SDK call",
+        "AUTH" => "Authentication check",
+        "AUTHZ" => "Authorization check",
+        "CRYPTO" => "Crypto operation",
+        "SECRET" => "Secret read",
+        "LOG" => "Log write",
+        "EXEC" => "Shell command",
+        "EVAL" => "Dynamic code evaluation",
+        "STORAGE" => "Storage access",
+        "QUEUE" => "Queue message",
+        "VALIDATE" => "Input validation",
+        "XSS" => "HTML output",
+        "REDIRECT" => "Redirect",
+        "CLIENT_STORAGE" => "Browser storage write",
+        _ => "Step",
+    }
+}
+
+// First line of a label, trimmed for a spreadsheet cell
+fn short(s: &str) -> String {
+    let line = s.lines().next().unwrap_or_default().trim();
+    if line.chars().count() > 100 { format!("{}…", line.chars().take(100).collect::<String>()) } else { line.to_string() }
+}
+
+// A tagged node as a step: `Database call: this.prisma.loan.update`, `HTTP request: POST https://…`
+fn node_step(cfg: &SimpleCfg, n: usize) -> Option<String> {
+    let label = &cfg.nodes[n];
+    let (prefix, rest) = label.split_once(": ")?;
+    if !prefix.chars().all(|c| c.is_ascii_uppercase() || c == '_') { return None; }
+    let what = match (cfg.outbound.iter().find(|h| h.node == n), cfg.calls.iter().find(|(c, _)| *c == n)) {
+        (Some(h), _) => format!("{} {}", h.method, h.url.as_deref().unwrap_or(&h.client)),
+        (None, Some((_, name))) => name.clone(),
+        (None, None) => short(rest),
+    };
+    Some(format!("{}: {}", kind(prefix), what))
+}
+
+/// A manual case for one enumerated path of `func`: its branch conditions (with example values) as
+/// preconditions, the decisions and tagged calls along it as steps, and how it ends plus which tagged
+/// calls do and don't happen as the expected result.
+pub fn path_case(func: &str, cfg: &SimpleCfg, path: &CfgPath) -> ManualCase {
+    let mut preconditions = Vec::new();
+    if let Some(r) = &cfg.route {
+        preconditions.push(format!("The route is {} {}", r.method, r.path));
+    }
+    for d in &path.decisions {
+        if let Some(l) = path.loops.iter().find(|l| l.node == d.node) {
+            if d.taken { continue; }
+            preconditions.push(format!("{} has {} item{}", d.condition, l.iterations, if l.iterations == 1 { "" } else { "s" }));
+            continue;
+        }
+        let cond = if d.taken { d.condition.clone() } else { negate(&d.condition) };
+        let examples: Vec<String> = d.inputs.iter()
+            .filter_map(|i| i.values.first().map(|v| format!("{} = {}", i.subject, v)))
+            .collect();
+        preconditions.push(if examples.is_empty() { cond } else { format!("{} (e.g. {})", cond, examples.join(", ")) });
+    }
+    preconditions.dedup();
+
+    let params: Vec<&str> = cfg.params.iter().map(|p| p.name.as_str()).collect();
+    let mut steps = vec![format!("Call {}({})", func, params.join(", "))];
+    let outcomes = path.outcomes();
+    for (i, &n) in path.nodes.iter().enumerate() {
+        let label = &cfg.nodes[n];
+        let cond = || cfg.conditions.iter().find(|c| c.node == n).map(|c| c.text.clone());
+        let step = if label.starts_with("If:") {
+            let c = cond().unwrap_or_else(|| label.trim_start_matches("If:").trim().to_string());
+            outcomes.get(i).copied().flatten().map(|t| format!("Check {}: {}", c, if t { "yes" } else { "no" }))
+        } else if label.starts_with("Loop:") {
+            // once per loop, however often it comes round
+            (!path.nodes[..i].contains(&n)).then(|| {
+                let c = cond().unwrap_or_else(|| label.trim_start_matches("Loop:").trim().to_string());
+                let times = path.loops.iter().find(|l| l.node == n).map(|l| l.iterations).unwrap_or(0);
+                format!("Repeat for {} ({} time{})", c, times, if times == 1 { "" } else { "s" })
+            })
+        } else {
+            node_step(cfg, n)
+        };
+        if let Some(s) = step.filter(|s| steps.last() != Some(s)) { steps.push(s); }
+    }
+
+    let mut expected = Vec::new();
+    let last = path.nodes.iter().rev().nth(1).map(|&n| cfg.nodes[n].as_str()).unwrap_or_default();
+    if let Some(t) = last.strip_prefix("Throw:") {
+        expected.push(format!("Fails with {}", short(t.trim().trim_start_matches("throw ").trim_end_matches(';'))));
+    } else if let Some(r) = last.strip_prefix("Return:") {
+        let value = short(r.trim().trim_start_matches("return").trim().trim_end_matches(';'));
+        expected.push(if value.is_empty() { "Returns nothing".to_string() } else { format!("Returns {}", value) });
+    } else {
+        expected.push("Completes without error".to_string());
+    }
+    let mut seen = Vec::new();
+    for (n, name) in &cfg.calls {
+        if seen.contains(&name) || cfg.nodes[*n].starts_with("LOG:") { continue; }
+        seen.push(name);
+        let hit = cfg.calls.iter().filter(|(_, c)| c == name).any(|(m, _)| path.nodes.contains(m));
+        expected.push(format!("{} is {}called", name, if hit { "" } else { "not " }));
+    }
+
+    ManualCase {
+        title: format!("{}: {}", func, path_name(path)),
+        section: func.to_string(),
+        tags: vec!["path".to_string()],
+        preconditions,
+        steps,
+        expected,
+    }
+}
+
+/// A manual case for a negative request against a route
+pub fn negative_case(route: &RouteCases, c: &NegativeCase) -> ManualCase {
+    let preconditions = vec![match c.auth {
+        "none" => "No Authorization header".to_string(),
+        "invalid" => "Authorization header holds an invalid token".to_string(),
+        "unprivileged" => "Signed in as a user with no roles".to_string(),
+        _ if route.roles.is_empty() => "Signed in as a valid user".to_string(),
+        _ => format!("Signed in as a user with role {}", route.roles.join(" or ")),
+    }];
+    let mut steps = vec![format!("Send {} {}", c.method, c.path)];
+    if !c.query.is_empty() {
+        let q: Vec<String> = c.query.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        steps.push(format!("With query {}", q.join("&")));
+    }
+    match (&c.body, &c.oversize) {
+        (Some(b), Some(o)) => steps.push(format!("With body {} plus {} padded to {} characters", compact(b), o.field, o.bytes)),
+        (Some(b), None) => steps.push(format!("With body {}", compact(b))),
+        _ => {}
+    }
+    let codes: Vec<String> = c.expect.iter().map(u16::to_string).collect();
+    let expected = vec![match codes.as_slice() {
+        [one] => format!("Response status is {}", one),
+        more => format!("Response status is one of {}", more.join(", ")),
+    }, "The request has no other effect".to_string()];
+    ManualCase {
+        title: format!("{}: {}", route.route(), c.title),
+        section: route.route(),
+        tags: vec!["negative".to_string(), c.category.to_string()],
+        preconditions,
+        steps,
+        expected,
+    }
+}
+
+fn compact(v: &Value) -> String {
+    let s = serde_json::to_string(v).unwrap_or_default();
+    if s.len() > 200 { format!("{}…", &s[..s.char_indices().nth(200).map(|(i, _)| i).unwrap_or(s.len())]) } else { s }
+}
+
+fn csv_cell(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+fn numbered(lines: &[String]) -> String {
+    lines.iter().enumerate().map(|(i, l)| format!("{}. {}", i + 1, l)).collect::<Vec<_>>().join("\n")
+}
+
+/// CSV for TestRail/Xray import: Title, Section, Preconditions, Steps, Expected Result. Multi-line cells
+/// are numbered lists.
+pub fn render_csv(cases: &[ManualCase]) -> String {
+    let mut out = vec!["Title,Section,Preconditions,Steps,Expected Result".to_string()];
+    for c in cases {
+        out.push([
+            csv_cell(&c.title),
+            csv_cell(&c.section),
+            csv_cell(&c.preconditions.join("\n")),
+            csv_cell(&numbered(&c.steps)),
+            csv_cell(&numbered(&c.expected)),
+        ].join(","));
+    }
+    out.join("\r\n") + "\r\n"
+}
+
+// Gherkin keyword for the i-th line of a block: the first gets `first`, the rest `And`
+fn keyword(i: usize, first: &str) -> &str {
+    if i == 0 { first } else { "And" }
+}
+
+// `No Authorization header` reads as `Given no Authorization header`; `POST /loans` and code stay as they are
+fn sentence(s: &str) -> String {
+    let mut c = s.chars();
+    match (c.next(), c.next()) {
+        (Some(f), Some(n)) if f.is_uppercase() && n.is_lowercase() => f.to_lowercase().chain(s.chars().skip(1)).collect(),
+        _ => s.to_string(),
+    }
+}
+
+fn tag(s: &str) -> String {
+    format!("@{}", s.chars().map(|c| if c.is_whitespace() { '_' } else { c }).collect::<String>())
+}
+
+/// A `.feature` file: one scenario per case, grouped under a Rule per function or route
+pub fn render_feature(name: &str, cases: &[ManualCase]) -> String {
+    let mut out = vec![format!("Feature: {}", name), String::new()];
+    let mut section: Option<&str> = None;
+    for c in cases {
+        if section != Some(c.section.as_str()) {
+            section = Some(&c.section);
+            out.push(format!("  Rule: {}", c.section));
+            out.push(String::new());
+        }
+        out.push(format!("    {}", c.tags.iter().map(|t| tag(t)).collect::<Vec<_>>().join(" ")));
+        out.push(format!("    Scenario: {}", c.title.strip_prefix(&format!("{}: ", c.section)).unwrap_or(&c.title)));
+        for (i, p) in c.preconditions.iter().enumerate() {
+            out.push(format!("      {} {}", keyword(i, "Given"), sentence(p)));
+        }
+        for (i, s) in c.steps.iter().enumerate() {
+            out.push(format!("      {} {}", keyword(i, "When"), sentence(s)));
+        }
+        for (i, e) in c.expected.iter().enumerate() {
+            out.push(format!("      {} {}", keyword(i, "Then"), sentence(e)));
+        }
+        out.push(String::new());
+    }
+    out.join("\n")
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity { Info, Low, Medium, High, Critical }
+
+/// A rule hit inside one function; `file`/`func` are added when findings are aggregated per repo.
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub rule: String,
+    pub severity: Severity,
+    pub line: usize,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+/// A finding located in the repo, as written to findings.json
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoFinding {
+    pub file: String,
+    pub func: String,
+    #[serde(flatten)]
+    pub finding: Finding,
+}
@@ -0,0 +1,370 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::testgen::lower_first;
+
+/// The file every generated spec imports its fixture factories from
+pub const FIXTURES_FILE: &str = "casesmith.fixtures.ts";
+
+/// A scalar, enum or composite-type field of a Prisma model; relation fields are left out, as they are
+/// from the model's generated type
+#[derive(Debug, Clone)]
+pub struct PrismaField {
+    pub name: String,
+    pub ty: String,
+    pub list: bool,
+    pub optional: bool,
+    /// `@default(...)` argument, if any: `PENDING`, `now()`, `false`
+    pub default: Option<String>,
+    /// Members of its enum type, if it has one
+    pub members: Vec<String>,
+}
+
+/// A `model` (or Mongo composite `type`) block
+#[derive(Debug, Clone)]
+pub struct PrismaModel {
+    pub name: String,
+    pub fields: Vec<PrismaField>,
+    pub composite: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PrismaSchema {
+    pub models: Vec<PrismaModel>,
+    pub enums: Vec<(String, Vec<String>)>,
+}
+
+const SCALARS: &[&str] = &["String", "Int", "BigInt", "Float", "Decimal", "Boolean", "DateTime", "Json", "Bytes"];
+
+/// Parse the model, type and enum blocks of a Prisma schema. Attributes other than `@default` are
+/// ignored.
+pub fn parse_prisma_schema(text: &str) -> PrismaSchema {
+    let mut schema = PrismaSchema::default();
+    let mut block: Option<(&str, String)> = None;
+    let mut fields = Vec::new();
+    let mut values = Vec::new();
+    for line in text.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if line.is_empty() { continue; }
+        if block.is_none() {
+            let mut words = line.split_whitespace();
+            if let (Some(kw @ ("model" | "type" | "enum")), Some(name)) = (words.next(), words.next())
+                && line.ends_with('{')
+            {
+                block = Some((kw, name.trim_end_matches('{').to_string()));
+            }
+            continue;
+        }
+        if line.starts_with('}') {
+            let (kw, name) = block.take().unwrap();
+            if kw == "enum" {
+                schema.enums.push((name, std::mem::take(&mut values)));
+            } else {
+                schema.models.push(PrismaModel { name, fields: std::mem::take(&mut fields), composite: kw == "type" });
+            }
+            continue;
+        }
+        if line.starts_with('@') { continue; }
+        let mut words = line.split_whitespace();
+        let Some(name) = words.next() else { continue };
+        if block.as_ref().is_some_and(|(kw, _)| *kw == "enum") {
+            values.push(name.to_string());
+            continue;
+        }
+        let Some(ty) = words.next() else { continue };
+        let default = line.split_once("@default(").map(|(_, d)| {
+            // up to the matching paren: `@default(dbgenerated("gen_random_uuid()"))`
+            let mut depth = 1;
+            let end = d.char_indices().find(|(_, c)| {
+                match c { '(' => depth += 1, ')' => depth -= 1, _ => {} }
+                depth == 0
+            }).map(|(i, _)| i).unwrap_or(d.len());
+            d[..end].to_string()
+        });
+        fields.push(PrismaField {
+            name: name.to_string(),
+            ty: ty.trim_end_matches(['?', ']']).trim_end_matches('[').to_string(),
+            list: ty.ends_with("[]"),
+            optional: ty.ends_with('?'),
+            default,
+            members: vec![],
+        });
+    }
+    // relation fields point at another model
+    let models: BTreeSet<String> = schema.models.iter().filter(|m| !m.composite).map(|m| m.name.clone()).collect();
+    for m in &mut schema.models {
+        m.fields.retain(|f| !models.contains(&f.ty));
+        for f in &mut m.fields {
+            if let Some((_, members)) = schema.enums.iter().find(|(n, _)| *n == f.ty) { f.members = members.clone(); }
+        }
+    }
+    schema
+}
+
+/// The project's Prisma schema: prisma/schema.prisma, schema.prisma, or the multi-file prisma/schema/*.prisma.
+/// Returns where it was found (relative to `root`) with the parsed schema.
+pub fn load_prisma_schema(root: &Path) -> Option<(String, PrismaSchema)> {
+    for rel in ["prisma/schema.prisma", "schema.prisma"] {
+        if let Ok(text) = std::fs::read_to_string(root.join(rel)) {
+            return Some((rel.to_string(), parse_prisma_schema(&text)));
+        }
+    }
+    let mut files: Vec<_> = std::fs::read_dir(root.join("prisma/schema")).ok()?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "prisma"))
+        .collect();
+    files.sort();
+    let text: Vec<String> = files.iter().filter_map(|f| std::fs::read_to_string(f).ok()).collect();
+    (!text.is_empty()).then(|| ("prisma/schema".to_string(), parse_prisma_schema(&text.join("\n"))))
+}
+
+impl PrismaSchema {
+    fn model(&self, name: &str) -> Option<&PrismaModel> {
+        self.models.iter().find(|m| m.name == name)
+    }
+
+    // The model a client delegate serves: `prisma.loanApplication` → LoanApplication
+    fn delegate(&self, delegate: &str) -> Option<&PrismaModel> {
+        self.models.iter().find(|m| !m.composite && lower_first(&m.name) == delegate)
+    }
+
+    fn is_enum(&self, ty: &str) -> bool {
+        self.enums.iter().any(|(n, _)| n == ty)
+    }
+}
+
+/// What a Prisma delegate method resolves to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Returns {
+    /// findMany
+    Many,
+    /// create, update, upsert, delete, find*OrThrow
+    One,
+    /// findUnique, findFirst
+    Nullable,
+    /// count
+    Count,
+    /// createMany, updateMany, deleteMany: `{ count }`
+    Batch,
+}
+
+/// The shape a mocked call resolves to, so a spec can hand back something the code under test can read
+#[derive(Debug, Clone)]
+pub enum Shape<'a> {
+    Model(&'a PrismaModel, Returns),
+    /// An axios call: an AxiosResponse around `data`
+    Axios,
+}
+
+/// Shape of a Prisma delegate call (`this.prisma.loan.findMany`) from the schema
+pub fn prisma_shape<'a>(callee: &str, schema: &'a PrismaSchema) -> Option<Shape<'a>> {
+    let parts: Vec<&str> = callee.split('.').collect();
+    let i = parts.iter().position(|p| p.to_lowercase().contains("prisma"))?;
+    let [delegate, op] = parts.get(i + 1..)? else { return None };
+    let model = schema.delegate(delegate)?;
+    let returns = match *op {
+        "findMany" => Returns::Many,
+        "findUnique" | "findFirst" => Returns::Nullable,
+        "findUniqueOrThrow" | "findFirstOrThrow" | "create" | "update" | "upsert" | "delete" => Returns::One,
+        "count" => Returns::Count,
+        "createMany" | "updateMany" | "deleteMany" => Returns::Batch,
+        _ => return None,
+    };
+    Some(Shape::Model(model, returns))
+}
+
+/// `loanFixture` for Loan
+pub fn factory(model: &PrismaModel) -> String {
+    format!("{}Fixture", lower_first(&model.name))
+}
+
+impl Shape<'_> {
+    /// The factory the value comes from: `loanFixture`, `axiosResponse`
+    pub fn factory(&self) -> Option<String> {
+        match self {
+            Shape::Model(m, Returns::Many | Returns::One | Returns::Nullable) => Some(factory(m)),
+            Shape::Model(..) => None,
+            Shape::Axios => Some("axiosResponse".to_string()),
+        }
+    }
+
+    /// What the mock resolves to unless a test says otherwise: one record, a response with no data
+    pub fn default_value(&self) -> String {
+        match self {
+            Shape::Model(m, Returns::Many) => format!("[{}()]", factory(m)),
+            Shape::Model(m, _) if self.factory().is_some() => format!("{}()", factory(m)),
+            Shape::Model(_, Returns::Batch) => "{ count: 1 }".to_string(),
+            Shape::Model(..) => "1".to_string(),
+            Shape::Axios => "axiosResponse({})".to_string(),
+        }
+    }
+
+    /// What the mock should resolve to so that each `(subject, value)` holds: subjects are paths into the
+    /// result (empty for the result itself). `avoid` lists literals the conditions compare against, so
+    /// an enum field that must differ from one gets another member. None when the values can't be
+    /// expressed in this shape.
+    pub fn value_for(&self, inputs: &[(Vec<&str>, String)], avoid: &[&str]) -> Option<String> {
+        let whole = inputs.iter().find(|(s, _)| s.is_empty()).map(|(_, v)| v.as_str());
+        if let Some(v) = whole.filter(|v| ["null", "undefined"].contains(v)) {
+            // Prisma finds resolve to null, not undefined
+            return Some(if matches!(self, Shape::Model(..)) { "null" } else { v }.to_string());
+        }
+        let field = |name: &str| inputs.iter().find(|(s, _)| s.len() == 1 && s[0] == name).map(|(_, v)| v.as_str());
+        match self {
+            Shape::Model(m, Returns::Many) => count(whole?).map(|n| format!("[{}]", vec![format!("{}()", factory(m)); n].join(", "))),
+            Shape::Model(m, Returns::One | Returns::Nullable) => {
+                let mut overrides: Vec<String> = Vec::new();
+                for (s, v) in inputs.iter().filter(|(s, _)| !s.is_empty()) {
+                    let Some(f) = m.fields.iter().find(|f| f.name == s[0]) else { continue };
+                    if overrides.iter().any(|o| o.starts_with(&format!("{}:", f.name))) { continue; }
+                    let v = if s.len() > 1 { nested(&s[1..], v) } else { typed(f, v, avoid) };
+                    overrides.push(format!("{}: {}", f.name, v));
+                }
+                match (overrides.is_empty(), whole) {
+                    (false, _) => Some(format!("{}({{ {} }})", factory(m), overrides.join(", "))),
+                    (true, Some("{}")) => Some(format!("{}()", factory(m))),
+                    _ => None,
+                }
+            }
+            Shape::Model(_, Returns::Count) => whole.filter(|v| v.parse::<f64>().is_ok()).map(str::to_string),
+            Shape::Model(_, Returns::Batch) => field("count").map(|v| format!("{{ count: {} }}", v)),
+            Shape::Axios => {
+                let status = field("status").filter(|v| v.parse::<u16>().is_ok());
+                let data: Vec<(Vec<&str>, String)> = inputs.iter()
+                    .filter(|(s, _)| s.len() > 1 && s[0] == "data")
+                    .map(|(s, v)| (s[1..].to_vec(), v.clone()))
+                    .collect();
+                let data = match field("data") {
+                    Some(v) => v.to_string(),
+                    None if !data.is_empty() => object(&data),
+                    None if status.is_some() || whole == Some("{}") => "{}".to_string(),
+                    None => return None,
+                };
+                Some(match status {
+                    Some(s) => format!("axiosResponse({}, {})", data, s),
+                    None => format!("axiosResponse({})", data),
+                })
+            }
+        }
+    }
+}
+
+// Items a length/iterable boundary asks for: `'x'.repeat(2)`, `[{}, {}]`, `{}` (truthy: one)
+fn count(value: &str) -> Option<usize> {
+    if value == "{}" { return Some(1); }
+    if let Some(n) = value.strip_prefix("'x'.repeat(").and_then(|n| n.strip_suffix(')')) {
+        return n.parse().ok();
+    }
+    value.strip_prefix('[').and_then(|v| v.strip_suffix(']')).map(|v| v.matches("{}").count())
+}
+
+// `{ a: { b: value } }` for a path under a field
+fn nested(path: &[&str], value: &str) -> String {
+    path.iter().rev().fold(value.to_string(), |v, p| format!("{{ {}: {} }}", p, v))
+}
+
+// A literal in the field's TypeScript type: Decimal and BigInt columns don't take plain numbers, and an
+// enum column takes a member rather than some other string (one not in `avoid`)
+fn typed(f: &PrismaField, value: &str, avoid: &[&str]) -> String {
+    let nullish = ["null", "undefined"].contains(&value);
+    let numeric = !nullish && !value.starts_with(['\'', '"', '`', '[', '{']);
+    let unquoted = value.trim_matches(['\'', '"', '`']);
+    match f.ty.as_str() {
+        "Decimal" if numeric => format!("new Prisma.Decimal({})", value),
+        "BigInt" if numeric => format!("BigInt({})", value),
+        _ if !f.members.is_empty() && !nullish && !f.members.iter().any(|m| m == unquoted) => {
+            match f.members.iter().find(|m| !avoid.contains(&m.as_str())) {
+                Some(m) => format!("'{}'", m),
+                None => value.to_string(),
+            }
+        }
+        _ => value.to_string(),
+    }
+}
+
+// `{ a: 1, b: { c: 2 } }` from (path, value) pairs; the first value for a path wins
+fn object(fields: &[(Vec<&str>, String)]) -> String {
+    let mut groups: BTreeMap<&str, Vec<(Vec<&str>, String)>> = BTreeMap::new();
+    for (path, v) in fields {
+        groups.entry(path[0]).or_default().push((path[1..].to_vec(), v.clone()));
+    }
+    let parts: Vec<String> = groups.iter().map(|(k, sub)| match sub.iter().find(|(p, _)| p.is_empty()) {
+        Some((_, v)) => format!("{}: {}", k, v),
+        None => format!("{}: {}", k, object(sub)),
+    }).collect();
+    format!("{{ {} }}", parts.join(", "))
+}
+
+// A value that fits the field: its default when that's a literal, else something plausible
+fn sample(schema: &PrismaSchema, f: &PrismaField) -> String {
+    if f.list { return "[]".to_string(); }
+    if f.optional && f.default.is_none() { return "null".to_string(); }
+    let name = f.name.to_lowercase();
+    match (f.ty.as_str(), f.default.as_deref()) {
+        (_, Some("now()")) | ("DateTime", _) => "new Date('2024-01-01T00:00:00.000Z')".to_string(),
+        ("String", Some("uuid()" | "uuid(4)" | "uuid(7)")) => "'00000000-0000-4000-8000-000000000000'".to_string(),
+        ("String", Some("cuid()" | "cuid(2)")) => "'cjld2cjxh0000qzrmn831i7rn'".to_string(),
+        ("String", Some(d)) if d.starts_with('"') => format!("'{}'", d.trim_matches('"').replace('\'', "\\'")),
+        ("String", _) if name.contains("email") => "'user@example.com'".to_string(),
+        ("String", _) if name.contains("url") => "'https://example.com'".to_string(),
+        ("String", _) if name == "id" || name.ends_with("id") => format!("'{}-1'", f.name),
+        ("String", _) => format!("'{}'", f.name),
+        ("Int" | "Float", Some(d)) if d.parse::<f64>().is_ok() => d.to_string(),
+        ("Int" | "Float", _) => "1".to_string(),
+        ("BigInt", _) => "BigInt(1)".to_string(),
+        ("Decimal", Some(d)) if d.parse::<f64>().is_ok() => format!("new Prisma.Decimal({})", d),
+        ("Decimal", _) => "new Prisma.Decimal(1)".to_string(),
+        ("Boolean", Some(d @ ("true" | "false"))) => d.to_string(),
+        ("Boolean", _) => "false".to_string(),
+        ("Json", _) => "{}".to_string(),
+        ("Bytes", _) => "Buffer.from('')".to_string(),
+        (ty, d) if schema.is_enum(ty) => {
+            let values = &schema.enums.iter().find(|(n, _)| n == ty).unwrap().1;
+            let v = d.filter(|d| values.iter().any(|v| v == d)).or(values.first().map(String::as_str)).unwrap_or_default();
+            format!("'{}'", v)
+        }
+        (ty, _) if !SCALARS.contains(&ty) => match schema.model(ty) {
+            // composite types are embedded objects
+            Some(m) => format!("{{ {} }}", m.fields.iter().map(|f| format!("{}: {}", f.name, sample(schema, f))).collect::<Vec<_>>().join(", ")),
+            None => "null as any".to_string(),
+        },
+        _ => "null as any".to_string(),
+    }
+}
+
+/// casesmith.fixtures.ts: a factory per model in `used` (by factory name) and, if used, `axiosResponse`.
+/// Overrides go through the factory argument, so the file itself can be regenerated.
+pub fn render_fixtures(schema: &PrismaSchema, source: Option<&str>, used: &BTreeSet<String>) -> String {
+    let models: Vec<&PrismaModel> = schema.models.iter().filter(|m| !m.composite && used.contains(&factory(m))).collect();
+    let mut out = vec![format!(
+        "// Generated by `casesmith tests`{}; rewritten on every run, so pass overrides from the specs instead of editing here.",
+        source.map(|s| format!(" from {}", s)).unwrap_or_default()
+    )];
+    if used.contains("axiosResponse") {
+        out.push("import type { AxiosResponse } from 'axios';".to_string());
+    }
+    if !models.is_empty() {
+        let types: Vec<&str> = models.iter().map(|m| m.name.as_str()).collect();
+        let decimal = models.iter().flat_map(|m| &m.fields).any(|f| f.ty == "Decimal" && !f.list);
+        if decimal { out.push("import { Prisma } from '@prisma/client';".to_string()); }
+        out.push(format!("import type {{ {} }} from '@prisma/client';", types.join(", ")));
+    }
+    for m in models {
+        out.push(String::new());
+        out.push(format!("export function {}(overrides: Partial<{}> = {{}}): {} {{", factory(m), m.name, m.name));
+        out.push("  return {".to_string());
+        for f in &m.fields {
+            out.push(format!("    {}: {},", f.name, sample(schema, f)));
+        }
+        out.push("    ...overrides,".to_string());
+        out.push("  };".to_string());
+        out.push("}".to_string());
+    }
+    if used.contains("axiosResponse") {
+        out.push(String::new());
+        out.push("export function axiosResponse<T>(data: T, status = 200): AxiosResponse<T> {".to_string());
+        out.push("  return { data, status, statusText: String(status), headers: {}, config: { headers: {} } as any };".to_string());
+        out.push("}".to_string());
+    }
+    out.join("\n") + "\n"
+}
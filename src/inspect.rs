@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+
+use crate::SimpleCfg;
+
+// Box-drawing prefixes for the last and other children of a tree level
+fn branch(last: bool) -> (&'static str, &'static str) {
+    if last { ("└── ", "    ") } else { ("├── ", "│   ") }
+}
+
+// Tag counts in label order of first appearance: `DB 2, NET 1`
+fn tag_counts(cfg: &SimpleCfg) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for label in &cfg.nodes {
+        let Some((prefix, _)) = label.split_once(": ") else { continue };
+        if !prefix.chars().all(|c| c.is_ascii_uppercase() || c == '_') { continue; }
+        match counts.iter_mut().find(|(p, _)| *p == prefix) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((prefix, 1)),
+        }
+    }
+    counts.iter().map(|(p, n)| format!("{} {}", p, n)).collect::<Vec<_>>().join(", ")
+}
+
+// `→ 3 (true), 4 (false)`
+fn successors(cfg: &SimpleCfg, n: usize) -> String {
+    let next: Vec<String> = cfg.edges.iter()
+        .filter(|(s, _)| *s == n)
+        .map(|&(_, d)| match cfg.branches.iter().find(|b| b.0 == n && b.1 == d) {
+            Some(b) => format!("{} ({})", d, b.2),
+            None => d.to_string(),
+        })
+        .collect();
+    if next.is_empty() { String::new() } else { format!(" → {}", next.join(", ")) }
+}
+
+/// The functions of one source as a tree: signature, route, tag counts and findings, then every CFG
+/// node with where it goes next. `verbose` adds node lines and the inputs behind each condition.
+/// Functions come in source order.
+pub fn render_tree(name: &str, grammar: &str, cfgs: &BTreeMap<String, SimpleCfg>, verbose: bool) -> String {
+    let mut funcs: Vec<(&String, &SimpleCfg)> = cfgs.iter().collect();
+    funcs.sort_by_key(|(f, c)| (c.locs.first().copied().unwrap_or_default(), *f));
+    let mut out = vec![format!("{} · {} · {} function{}", name, grammar, funcs.len(), if funcs.len() == 1 { "" } else { "s" })];
+    for (i, (func, cfg)) in funcs.iter().enumerate() {
+        let (head, indent) = branch(i + 1 == funcs.len());
+        let params: Vec<String> = cfg.params.iter()
+            .map(|p| match &p.ty { Some(t) => format!("{}: {}", p.name, t), None => p.name.clone() })
+            .collect();
+        let line = cfg.locs.first().map(|l| l.0).unwrap_or_default();
+        out.push(format!("{}{}({}) · line {}", head, func, params.join(", "), line));
+
+        let mut items: Vec<String> = Vec::new();
+        if let Some(r) = &cfg.route { items.push(format!("route: {} {}", r.method, r.path)); }
+        let tags = tag_counts(cfg);
+        if !tags.is_empty() { items.push(format!("tags: {}", tags)); }
+        for f in &cfg.findings {
+            items.push(format!("finding: {} ({}) line {}: {}", f.rule, serde_json::to_value(f.severity).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default(), f.line, f.message));
+        }
+        // the cfg comes last, so these never close the level
+        out.extend(items.iter().map(|item| format!("{}{}{}", indent, branch(false).0, item)));
+
+        out.push(format!("{}└── cfg ({} nodes, {} edges)", indent, cfg.nodes.len(), cfg.edges.len()));
+        let indent = format!("{}    ", indent);
+        for (n, label) in cfg.nodes.iter().enumerate() {
+            let (head, sub) = branch(n + 1 == cfg.nodes.len());
+            let at = if verbose { format!(" @{}", cfg.locs.get(n).map(|l| l.0).unwrap_or_default()) } else { String::new() };
+            let label = label.lines().next().unwrap_or_default();
+            out.push(format!("{}{}[{}] {}{}{}", indent, head, n, label, at, successors(cfg, n)));
+            if !verbose { continue; }
+            if let Some(c) = cfg.conditions.iter().find(|c| c.node == n) {
+                for (k, b) in c.inputs.iter().enumerate() {
+                    let (h, _) = branch(k + 1 == c.inputs.len());
+                    out.push(format!("{}{}{}{} ({}): true {} / false {}", indent, sub, h, b.subject, b.kind, b.when_true.join(" | "), b.when_false.join(" | ")));
+                }
+            }
+        }
+    }
+    out.join("\n") + "\n"
+}
@@ -1,14 +1,74 @@
+pub mod classify;
+pub mod conditions;
+pub mod config;
+pub mod coverage;
+pub mod embedded;
+pub mod envvars;
+pub mod export;
+pub mod findings;
+pub mod fixtures;
+pub mod inspect;
+mod logleak;
+pub mod negative;
+pub mod outbound;
+pub mod paths;
+pub mod query;
+pub mod repl;
+pub mod secrets;
+mod sqli;
 mod tag;
+pub mod testgen;
+pub mod validation;
+mod weakcrypto;
 
 use tree_sitter::{Parser as TreeSitterParser, Tree, Node};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::io::Write;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::tag::{classify_call, is_secretish, snippet, EdgeKind};
+use crate::classify::{classify_label, collect_type_fields, sensitive_params, DataClass, Taxonomy, TypeCatalogue, TypeField};
+use crate::conditions::{boundaries, iterable_boundary, Condition};
+use crate::config::{parse_config, parse_dotenv, QueryConfig};
+use crate::coverage::{coverage_for, function_coverage, parse_istanbul, parse_lcov, FunctionCoverage};
+use crate::embedded::{embedded_sql, graphql_ops, EmbeddedQuery};
+use crate::envvars::{collect_env_reads, env_inventory, ConfigNamespace, EnvRead};
+use crate::findings::{Finding, RepoFinding};
+use crate::logleak::{log_leaks, log_site, LogSite};
+use crate::outbound::{host_of, http_clients, outbound_call, HttpCall, HttpClient};
+use crate::export::{negative_case, path_case, render_csv, render_feature};
+use crate::fixtures::{load_prisma_schema, render_fixtures, FIXTURES_FILE};
+use crate::inspect::render_tree;
+use crate::negative::{negative_cases, render_supertest, RouteCases, SETUP, SETUP_FILE};
+use crate::paths::{enumerate_paths, FunctionPaths};
+use crate::query::Query;
+use crate::repl::{interact, Session};
+use crate::secrets::{scan_secrets, SecretHit};
+use crate::sqli::check_sql_call;
+use crate::testgen::{collect_imports, render_spec, Framework, Import};
+use crate::validation::{collect_dto_rules, entrypoint_validation, has_global_pipe, DtoCatalogue, DtoRule, EntrypointValidation};
+use crate::weakcrypto::check_crypto_call;
+use crate::tag::{
+    call_name, classify_assignment, classify_call, has_tag, is_jsx_html_sink, is_authn_decorator, is_authz_decorator, is_db_write, is_entrypoint, is_ownership_check,
+    is_queue_consumer, is_queue_handler_decorator, is_secretish, is_validation_decorator, queue_topics, sdk_call, sdk_clients, snippet,
+    storage_target, EdgeKind, SdkClient, SDK_CATALOGUE, TAG_KINDS,
+};
 
 #[derive(Serialize)]
-struct SecIndex { functions: usize, edges: usize, boundary_crossings: usize, pii_edges: usize }
+struct SecIndex {
+    functions: usize,
+    edges: usize,
+    boundary_crossings: usize,
+    pii_edges: usize,
+    financial_edges: usize,
+    credential_edges: usize,
+    authn_without_authz: usize,
+    exec_sinks: usize,
+    code_eval_sinks: usize,
+    unvalidated_entrypoints: usize,
+    external_hosts: usize,
+    vendors: usize,
+    findings: usize,
+}
 
 #[derive(Serialize)]
 struct SecEdge {
@@ -16,53 +76,547 @@ struct SecEdge {
     src: String,
     dst: String,
     kind: EdgeKind,
-    sensitive: bool,
+    classes: Vec<DataClass>,
+}
+
+/// An authenticated route that reaches a DB write with no role/permission/ownership check before it
+#[derive(Serialize)]
+struct AuthzGap {
+    file: String,
+    func: String,
+    db_write: String,
+}
+
+/// Tables/columns and GraphQL fields a function reads and writes through embedded queries
+#[derive(Serialize)]
+struct DataAccess {
+    file: String,
+    func: String,
+    entrypoint: bool,
+    reads: Vec<String>,
+    writes: Vec<String>,
+    classes: Vec<DataClass>,
 }
 
 #[derive(Serialize)]
 struct SecurityFlow {
     index: SecIndex,
     edges: Vec<SecEdge>,
+    authz_gaps: Vec<AuthzGap>,
+    data_access: Vec<DataAccess>,
+    entrypoints: Vec<EntrypointValidation>,
+    external_hosts: Vec<ExternalHost>,
+    vendors: Vec<VendorUsage>,
+}
+
+/// Calls into one vendor's SDK across the repo, and the classes of data passed to it
+#[derive(Serialize)]
+struct VendorUsage {
+    vendor: &'static str,
+    calls: usize,
+    operations: BTreeSet<String>,
+    functions: BTreeSet<String>,
+    classes: BTreeSet<DataClass>,
+}
+
+/// A third-party host the code sends requests to. Hosts only known through an env var whose value
+/// isn't in .env.example are listed as `$VAR`.
+#[derive(Serialize)]
+struct ExternalHost {
+    host: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    env: Option<String>,
+    calls: usize,
+    methods: BTreeSet<String>,
+    credential_headers: BTreeSet<String>,
+    functions: BTreeSet<String>,
+}
+
+/// The grammar for `casesmith run`: `--lang` if given, else the file's extension, else whether the
+/// snippet looks like JSX. True for TSX.
+fn run_grammar(file: Option<&str>, lang: Option<&str>, code: &str) -> Result<bool, String> {
+    match lang.map(str::to_lowercase).as_deref() {
+        Some("tsx" | "jsx") => return Ok(true),
+        Some("ts" | "typescript" | "js" | "javascript") => return Ok(false),
+        Some(other) => return Err(format!("Unknown language '{}'; expected ts, tsx, js or jsx.", other)),
+        None => {}
+    }
+    if let Some(ext) = file.and_then(|f| std::path::Path::new(f).extension()).and_then(|e| e.to_str()) {
+        return Ok(matches!(ext.to_lowercase().as_str(), "tsx" | "jsx"));
+    }
+    // `<Button onClick={..}>` or `<br />`: JSX; type arguments (`Array<string>`) don't close like that
+    Ok(code.contains("</") || code.contains("/>"))
+}
+
+/// What `casesmith run` prints for one source: its functions, CFGs and tags as a tree, or as JSON
+/// (`{ file, grammar, functions }`, functions shaped like the .cfg.json files).
+pub fn run_report(name: &str, code: &str, tsx: bool, json: bool, verbose: bool, config: &str) -> String {
+    let settings = parse_config(config);
+    let fa = analyze_ts_source(name, code, tsx, &Taxonomy::from_config(&settings.classification));
+    let cfgs: BTreeMap<String, SimpleCfg> = fa.cfgs.into_iter().collect();
+    let grammar = if tsx { "tsx" } else { "typescript" };
+    if json {
+        let report = serde_json::json!({ "file": name, "grammar": grammar, "functions": cfgs });
+        serde_json::to_string_pretty(&report).unwrap_or_default() + "\n"
+    } else {
+        render_tree(name, grammar, &cfgs, verbose)
+    }
+}
+
+/// Analyze one file, an inline `--code` snippet, or stdin (neither given, or a file of `-`) and print
+/// what was extracted. Nothing is written to disk.
+pub fn handle_run(file: Option<String>, code: Option<String>, lang: Option<String>, json: bool, verbose: bool, config: &str) {
+    let (name, source) = match (file.as_deref(), code) {
+        (_, Some(code)) => ("<code>".to_string(), code),
+        (Some(f), None) if f != "-" => match std::fs::read_to_string(f) {
+            Ok(c) => (f.to_string(), c),
+            Err(e) => {
+                eprintln!("Could not read {}: {}", f, e);
+                return;
+            }
+        },
+        _ => {
+            let mut buf = String::new();
+            if let Err(e) = std::io::Read::read_to_string(&mut std::io::stdin(), &mut buf) {
+                eprintln!("Could not read stdin: {}", e);
+                return;
+            }
+            ("<stdin>".to_string(), buf)
+        }
+    };
+    let tsx = match run_grammar(file.as_deref().filter(|f| *f != "-"), lang.as_deref(), &source) {
+        Ok(tsx) => tsx,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    print!("{}", run_report(&name, &source, tsx, json, verbose, config));
+}
+
+/// Query a `.casesmithresults` directory, or a source root analyzed on the spot, interactively: functions,
+/// CFGs, routes reaching DB writes, callers/callees and edges by kind. `path` defaults to the current dir.
+pub fn handle_repl(path: Option<String>, config: &str) {
+    let settings = parse_config(config);
+    let path = path.unwrap_or_else(|| ".".to_string());
+    let session = match Session::load(std::path::Path::new(&path), &Taxonomy::from_config(&settings.classification)) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    println!("[repl] {} functions from {}; `help` lists commands, Tab completes function IDs", session.funcs.len(), path);
+    if let Err(e) = interact(session) {
+        eprintln!("{}", e);
+    }
+}
+
+/// Run a query (or the name of one saved under `[queries]` in config) over a `.casesmithresults`
+/// directory or a source root, `path` defaulting to the current dir, and print the hits.
+pub fn handle_query(expr: String, path: Option<String>, json: bool, config: &str) {
+    let settings = parse_config(config);
+    let text = settings.queries.get(&expr).map(|q| q.query.clone()).unwrap_or(expr);
+    let query = match Query::parse(&text) {
+        Ok(q) => q,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let path = path.unwrap_or_else(|| ".".to_string());
+    let session = match Session::load(std::path::Path::new(&path), &Taxonomy::from_config(&settings.classification)) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let hits = session.query(&query);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&hits).unwrap_or_default());
+        return;
+    }
+    for h in &hits {
+        println!("{}", h);
+    }
+    let funcs: HashSet<&str> = hits.iter().map(|h| h.id.as_str()).collect();
+    println!("[query] {} hit{} in {} of {} functions", hits.len(), if hits.len() == 1 { "" } else { "s" }, funcs.len(), session.funcs.len());
+}
+
+/// Write a Jest/Vitest spec skeleton next to each source file: one `it()` per enumerated path, with the
+/// NET/DB/LOG calls mocked. Specs generated before are updated in place; see `Spec::merge`.
+pub fn handle_tests(output: Option<String>, framework: Option<String>, config: &str) {
+    let settings = parse_config(config);
+    let Some(out) = output else {
+        eprintln!("No output directory specified.");
+        return;
+    };
+    let root = std::path::Path::new(&out);
+    if !root.is_dir() {
+        eprintln!("Output path '{}' is not a directory. Create it first, then rerun.", out);
+        return;
+    }
+    // --framework wins; otherwise vitest if the package depends on it
+    let fw = match framework.as_deref() {
+        Some("vitest") => Framework::Vitest,
+        Some("jest") => Framework::Jest,
+        Some(other) => {
+            eprintln!("Unknown framework '{}'; expected jest or vitest.", other);
+            return;
+        }
+        None if std::fs::read_to_string(root.join("package.json")).is_ok_and(|p| p.contains("\"vitest\"")) => Framework::Vitest,
+        None => Framework::Jest,
+    };
+
+    let taxonomy = Taxonomy::from_config(&settings.classification);
+    let (schema_file, schema) = load_prisma_schema(root).unzip();
+    let schema = schema.unwrap_or_default();
+    let mut factories = BTreeSet::new();
+    let mut written = 0;
+    for path in collect_ts_files(root) {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+        if is_generated_or_test(&name) { continue; }
+        let Some(fa) = analyze_ts_file(&path, &taxonomy) else { continue };
+        let cfgs: BTreeMap<String, SimpleCfg> = fa.cfgs.into_iter().collect();
+        let paths = cfgs.iter().map(|(f, c)| (f.clone(), enumerate_paths(c, &settings.paths))).collect();
+        let (stem, ext) = match name.rsplit_once('.') {
+            Some((stem, ext)) => (stem.to_string(), ext.to_string()),
+            None => (name.clone(), "ts".to_string()),
+        };
+        let depth = path.strip_prefix(root).map(|r| r.components().count()).unwrap_or(1);
+        let up = if depth > 1 { "../".repeat(depth - 1) } else { "./".to_string() };
+        let fixtures = format!("{}{}", up, FIXTURES_FILE.trim_end_matches(".ts"));
+        let spec = render_spec(&name, &format!("./{}", stem), &fa.imports, &cfgs, &paths, &schema, &fixtures, fw);
+        if spec.describes.is_empty() { continue; }
+        factories.extend(spec.fixtures.iter().cloned());
+
+        let spec_path = path.with_file_name(format!("{}.casesmith.spec.{}", stem, ext));
+        let tests: usize = spec.describes.iter().map(|d| d.tests.len()).sum();
+        let (text, added) = match std::fs::read_to_string(&spec_path) {
+            Ok(old) => spec.merge(&old),
+            Err(_) => (spec.render(), tests),
+        };
+        match std::fs::write(&spec_path, text) {
+            Ok(_) => {
+                written += 1;
+                println!("Wrote {} ({} paths, {} new tests)", spec_path.display(), tests, added);
+            }
+            Err(e) => eprintln!("Failed to write {}: {}", spec_path.display(), e),
+        }
+    }
+    if !factories.is_empty() {
+        let fixtures_path = root.join(FIXTURES_FILE);
+        match std::fs::write(&fixtures_path, render_fixtures(&schema, schema_file.as_deref(), &factories)) {
+            Ok(_) => println!("Wrote {} ({} factories)", fixtures_path.display(), factories.len()),
+            Err(e) => eprintln!("Failed to write {}: {}", fixtures_path.display(), e),
+        }
+    }
+    println!("[tests] {} spec files", written);
 }
 
-pub fn handle_run(name: String, verbose: bool, count: u8, config: &str) {
-    println!("[run] Using config:\n{}", config);
-    for _ in 0..count {
-        if verbose {
-            println!("Verbose mode is enabled.");
+/// Write negative test cases for every Nest route under `output`: missing/invalid tokens, missing roles,
+/// broken DTO rules, oversized bodies and injection payloads. `json` (the default) writes
+/// .casesmithresults/negative-cases.json; `supertest` writes a `<stem>.casesmith.e2e-spec.ts` next to
+/// each controller, plus casesmith.e2e-setup.ts at the root for the app and tokens they use.
+pub fn handle_negative(output: Option<String>, format: Option<String>, config: &str) {
+    let settings = parse_config(config);
+    let Some(out) = output else {
+        eprintln!("No output directory specified.");
+        return;
+    };
+    let root = std::path::Path::new(&out);
+    if !root.is_dir() {
+        eprintln!("Output path '{}' is not a directory. Create it first, then rerun.", out);
+        return;
+    }
+    let supertest = match format.as_deref() {
+        None | Some("json") => false,
+        Some("supertest") => true,
+        Some(other) => {
+            eprintln!("Unknown format '{}'; expected json or supertest.", other);
+            return;
+        }
+    };
+
+    let taxonomy = Taxonomy::from_config(&settings.classification);
+    let (analyses, dtos) = analyze_with_dtos(root, &taxonomy);
+    let mut all = Vec::new();
+    for (path, fa) in &analyses {
+        let rel = path.strip_prefix(root).unwrap_or(path).display().to_string();
+        let cfgs: BTreeMap<&String, &SimpleCfg> = fa.cfgs.iter().collect();
+        let routes: Vec<RouteCases> = cfgs.iter().filter_map(|(f, c)| negative_cases(&rel, f, c, &dtos)).collect();
+        if routes.is_empty() { continue; }
+        if supertest {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            let stem = name.rsplit_once('.').map(|(s, _)| s).unwrap_or(name);
+            let depth = path.strip_prefix(root).map(|r| r.components().count()).unwrap_or(1);
+            let up = if depth > 1 { "../".repeat(depth - 1) } else { "./".to_string() };
+            let setup = format!("{}{}", up, SETUP_FILE.trim_end_matches(".ts"));
+            let fw = if std::fs::read_to_string(root.join("package.json")).is_ok_and(|p| p.contains("\"vitest\"")) { Framework::Vitest } else { Framework::Jest };
+            let spec = render_supertest(name, &setup, &routes, fw);
+            let spec_path = path.with_file_name(format!("{}.casesmith.e2e-spec.ts", stem));
+            let tests: usize = spec.describes.iter().map(|d| d.tests.len()).sum();
+            let (text, added) = match std::fs::read_to_string(&spec_path) {
+                Ok(old) => spec.merge(&old),
+                Err(_) => (spec.render(), tests),
+            };
+            match std::fs::write(&spec_path, text) {
+                Ok(_) => println!("Wrote {} ({} cases, {} new)", spec_path.display(), tests, added),
+                Err(e) => eprintln!("Failed to write {}: {}", spec_path.display(), e),
+            }
         }
-        let mut parser = TreeSitterParser::new();
-        let language = tree_sitter_typescript::LANGUAGE_TYPESCRIPT;
-        parser.set_language(&language.into()).expect("Error setting language");
+        all.extend(routes);
+    }
 
-        let source_code = "function helloWorld(param:string):void {\n    console.log('Hello, world!');\n}";
-        let tree = parser.parse(source_code, None).unwrap();
-        let root_node = tree.root_node();
-        println!("Root node: {}", root_node.kind());
+    let cases: usize = all.iter().map(|r| r.cases.len()).sum();
+    if supertest {
+        let setup_path = root.join(SETUP_FILE);
+        if !all.is_empty() && !setup_path.exists() {
+            match std::fs::write(&setup_path, SETUP) {
+                Ok(_) => println!("Wrote {}: fill in createApp and tokenFor", setup_path.display()),
+                Err(e) => eprintln!("Failed to write {}: {}", setup_path.display(), e),
+            }
+        }
+    } else {
+        let results_root = root.join(".casesmithresults");
+        let _ = std::fs::create_dir_all(&results_root);
+        let out_path = results_root.join("negative-cases.json");
+        match std::fs::File::create(&out_path) {
+            Ok(mut f) => match serde_json::to_writer_pretty(&mut f, &all) {
+                Ok(_) => println!("Wrote {} ({} cases over {} routes)", out_path.display(), cases, all.len()),
+                Err(e) => eprintln!("Failed to write {}: {}", out_path.display(), e),
+            },
+            Err(e) => eprintln!("Failed to create {}: {}", out_path.display(), e),
+        }
     }
+    println!("[negative] {} routes, {} cases", all.len(), cases);
+}
+
+// DTO class name → its class-validator rules, across the repo
+type DtoRules = HashMap<String, Vec<DtoRule>>;
+
+// Every source file under `root` in path order, with the DTO rules of the whole repo: a controller
+// takes DTOs declared elsewhere
+fn analyze_with_dtos(root: &std::path::Path, taxonomy: &Taxonomy) -> (Vec<(std::path::PathBuf, FileAnalysis)>, DtoRules) {
+    let mut files = collect_ts_files(root);
+    files.sort();
+    let mut analyses = Vec::new();
+    let mut dtos = DtoRules::new();
+    for path in files {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if is_generated_or_test(name) { continue; }
+        let Some(fa) = analyze_ts_file(&path, taxonomy) else { continue };
+        for r in &fa.dto_rules {
+            dtos.entry(r.type_name.clone()).or_default().push(r.clone());
+        }
+        analyses.push((path, fa));
+    }
+    (analyses, dtos)
+}
+
+/// Export enumerated paths and negative route cases as manual test cases: `csv` (the default) writes
+/// .casesmithresults/test-cases.csv for TestRail/Xray import, `gherkin` writes a .feature file per
+/// source file under .casesmithresults/features/.
+pub fn handle_export(output: Option<String>, format: Option<String>, config: &str) {
+    let settings = parse_config(config);
+    let Some(out) = output else {
+        eprintln!("No output directory specified.");
+        return;
+    };
+    let root = std::path::Path::new(&out);
+    if !root.is_dir() {
+        eprintln!("Output path '{}' is not a directory. Create it first, then rerun.", out);
+        return;
+    }
+    let gherkin = match format.as_deref() {
+        None | Some("csv") => false,
+        Some("gherkin") => true,
+        Some(other) => {
+            eprintln!("Unknown format '{}'; expected csv or gherkin.", other);
+            return;
+        }
+    };
+
+    let taxonomy = Taxonomy::from_config(&settings.classification);
+    let (analyses, dtos) = analyze_with_dtos(root, &taxonomy);
+    let results_root = root.join(".casesmithresults");
+    let mut all = Vec::new();
+    let mut features = 0;
+    for (path, fa) in &analyses {
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        let cfgs: BTreeMap<&String, &SimpleCfg> = fa.cfgs.iter().collect();
+        let mut cases = Vec::new();
+        for (func, cfg) in cfgs.iter().filter(|(f, _)| !f.ends_with(".constructor")) {
+            let paths = enumerate_paths(cfg, &settings.paths);
+            cases.extend(paths.simple.iter().map(|p| path_case(func, cfg, p)));
+        }
+        for route in cfgs.iter().filter_map(|(f, c)| negative_cases(&rel.display().to_string(), f, c, &dtos)) {
+            cases.extend(route.cases.iter().map(|c| negative_case(&route, c)));
+        }
+        if cases.is_empty() { continue; }
+        if gherkin {
+            let mut feature_path = results_root.join("features").join(rel);
+            feature_path.set_extension("feature");
+            if let Some(parent) = feature_path.parent() { let _ = std::fs::create_dir_all(parent); }
+            match std::fs::write(&feature_path, render_feature(&rel.display().to_string(), &cases)) {
+                Ok(_) => features += 1,
+                Err(e) => eprintln!("Failed to write {}: {}", feature_path.display(), e),
+            }
+        }
+        all.extend(cases);
+    }
+
+    if gherkin {
+        println!("Wrote {} feature files to {} ({} scenarios)", features, results_root.join("features").display(), all.len());
+    } else {
+        let _ = std::fs::create_dir_all(&results_root);
+        let out_path = results_root.join("test-cases.csv");
+        match std::fs::write(&out_path, render_csv(&all)) {
+            Ok(_) => println!("Wrote {} ({} cases)", out_path.display(), all.len()),
+            Err(e) => eprintln!("Failed to write {}: {}", out_path.display(), e),
+        }
+    }
+}
+
+// Specs (`.spec.ts`, Nest's `.e2e-spec.ts`), declarations and casesmith's own fixtures/setup aren't code under test
+fn is_generated_or_test(name: &str) -> bool {
+    [".spec.", "-spec.", ".test.", ".d.ts"].iter().any(|s| name.contains(s)) || name.starts_with("casesmith.")
+}
+
+/// coverage-gaps.json: enumerated paths and tagged nodes that tests never execute
+#[derive(Serialize)]
+struct CoverageReport {
+    coverage: String,
+    functions: usize,
+    paths: usize,
+    untested_paths: usize,
+    untested_nodes: usize,
+    blockers: Vec<String>,
+    not_in_coverage: Vec<String>,
+    gaps: Vec<FunctionCoverage>,
+}
+
+/// Read Istanbul (`coverage-final.json`) or lcov (`lcov.info`) coverage, map it onto each function's CFG
+/// and paths, and write .casesmithresults/coverage-gaps.json. Returns the number of release blockers:
+/// AUTH/AUTHZ checks and ownership branches no test runs.
+pub fn handle_coverage(output: Option<String>, coverage: Option<String>, config: &str) -> usize {
+    let settings = parse_config(config);
+    let Some(out) = output else {
+        eprintln!("No output directory specified.");
+        return 0;
+    };
+    let root = std::path::Path::new(&out);
+    if !root.is_dir() {
+        eprintln!("Output path '{}' is not a directory. Create it first, then rerun.", out);
+        return 0;
+    }
+    // default to where Jest/Vitest put it
+    let cov_path = coverage.map(std::path::PathBuf::from).or_else(|| {
+        ["coverage/coverage-final.json", "coverage/lcov.info"].iter().map(|p| root.join(p)).find(|p| p.is_file())
+    });
+    let Some(cov_path) = cov_path else {
+        eprintln!("No coverage found; pass --coverage or run tests with coverage into {}/coverage first.", root.display());
+        return 0;
+    };
+    let text = match std::fs::read_to_string(&cov_path) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Could not read {}: {}", cov_path.display(), e);
+            return 0;
+        }
+    };
+    let cov = if cov_path.extension().is_some_and(|e| e == "json") {
+        match parse_istanbul(&text) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("Could not parse {}: {}", cov_path.display(), e);
+                return 0;
+            }
+        }
+    } else {
+        parse_lcov(&text)
+    };
+
+    let taxonomy = Taxonomy::from_config(&settings.classification);
+    let mut report = CoverageReport {
+        coverage: cov_path.display().to_string(),
+        functions: 0,
+        paths: 0,
+        untested_paths: 0,
+        untested_nodes: 0,
+        blockers: vec![],
+        not_in_coverage: vec![],
+        gaps: vec![],
+    };
+    let mut files = collect_ts_files(root);
+    files.sort();
+    for path in files {
+        let rel = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+        let Some(fa) = analyze_ts_file(&path, &taxonomy) else { continue };
+        if fa.cfgs.is_empty() { continue; }
+        let Some(fc) = coverage_for(&cov, &path, &rel) else {
+            report.not_in_coverage.push(rel.display().to_string());
+            continue;
+        };
+        let cfgs: BTreeMap<String, SimpleCfg> = fa.cfgs.into_iter().collect();
+        for (func, cfg) in &cfgs {
+            let paths = enumerate_paths(cfg, &settings.paths);
+            let gaps = function_coverage(&rel.display().to_string(), func, cfg, &paths, fc);
+            report.functions += 1;
+            report.paths += gaps.paths;
+            report.untested_paths += gaps.untested_paths.len();
+            report.untested_nodes += gaps.untested_nodes.len();
+            report.blockers.extend(gaps.blockers.iter().map(|b| format!("{} :: {} {}", gaps.file, func, b)));
+            if !gaps.untested_paths.is_empty() || !gaps.untested_nodes.is_empty() || !gaps.untested_branches.is_empty() {
+                report.gaps.push(gaps);
+            }
+        }
+    }
+
+    let results_root = root.join(".casesmithresults");
+    let _ = std::fs::create_dir_all(&results_root);
+    let out_path = results_root.join("coverage-gaps.json");
+    match std::fs::File::create(&out_path) {
+        Ok(mut f) => match serde_json::to_writer_pretty(&mut f, &report) {
+            Ok(_) => println!(
+                "Wrote {} ({} of {} paths never run, {} tagged nodes untested, {} files not in coverage)",
+                out_path.display(), report.untested_paths, report.paths, report.untested_nodes, report.not_in_coverage.len()
+            ),
+            Err(e) => eprintln!("Failed to write {}: {}", out_path.display(), e),
+        },
+        Err(e) => eprintln!("Failed to create {}: {}", out_path.display(), e),
+    }
+    for g in &report.gaps {
+        for n in &g.untested_nodes {
+            println!("  untested {}:{} {} :: {}", g.file, n.line, g.func, n.label);
+        }
+    }
+    for b in &report.blockers {
+        println!("  release blocker: {}", b);
+    }
+    report.blockers.len()
 }
 
 /// Recursively collect .ts/.tsx files under `root`, skipping common directories
 fn collect_ts_files(root: &std::path::Path) -> Vec<std::path::PathBuf> {
     fn walk(dir: &std::path::Path, out: &mut Vec<std::path::PathBuf>) {
         let rd = match std::fs::read_dir(dir) { Ok(r) => r, Err(_) => return };
-        for entry in rd {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.is_dir() {
-                    let skip = path.file_name()
-                        .and_then(|s| s.to_str())
-                        .map(|n| {
-                            let n = n.to_lowercase();
-                            n == "node_modules" || n == ".git" || n == ".casesmithresults" || n == "dist" || n == "build" || n == "target"
-                        })
-                        .unwrap_or(false);
-                    if !skip { walk(&path, out); }
-                } else if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                    if ext.eq_ignore_ascii_case("ts") || ext.eq_ignore_ascii_case("tsx") {
-                        out.push(path);
-                    }
-                }
+        for entry in rd.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                let skip = path.file_name()
+                    .and_then(|s| s.to_str())
+                    .map(|n| {
+                        let n = n.to_lowercase();
+                        n == "node_modules" || n == ".git" || n == ".casesmithresults" || n == "dist" || n == "build" || n == "target"
+                    })
+                    .unwrap_or(false);
+                if !skip { walk(&path, out); }
+            } else if let Some(ext) = path.extension().and_then(|e| e.to_str())
+                && (ext.eq_ignore_ascii_case("ts") || ext.eq_ignore_ascii_case("tsx"))
+            {
+                out.push(path);
             }
         }
     }
@@ -71,68 +625,215 @@ fn collect_ts_files(root: &std::path::Path) -> Vec<std::path::PathBuf> {
     outv
 }
 
+/// Classified type fields found in one file, for data-classification.json
+#[derive(Serialize)]
+struct FileTypeFields<'a> {
+    file: &'a str,
+    fields: &'a [TypeField],
+}
+
+/// Validated DTO fields found in one file, for validation.json
+#[derive(Serialize)]
+struct FileDtoRules<'a> {
+    file: &'a str,
+    rules: &'a [DtoRule],
+}
+
+fn secret_finding(file: &str, hit: &SecretHit) -> RepoFinding {
+    RepoFinding {
+        file: file.to_string(),
+        func: hit.func.clone(),
+        finding: Finding {
+            rule: "hardcoded-secret".to_string(),
+            severity: hit.severity,
+            line: hit.line,
+            message: format!("{} in string literal: {}", hit.kind, hit.value),
+            hint: Some("rotate it, then load it from the environment or a secret manager (ConfigService)".to_string()),
+        },
+    }
+}
+
 /// Convert all per-file CFGs → repo-level security-flow.json
-fn to_security_flow(all: &HashMap<String, HashMap<String, SimpleCfg>>) -> SecurityFlow {
+fn to_security_flow(
+    all: &HashMap<String, HashMap<String, SimpleCfg>>,
+    taxonomy: &Taxonomy,
+    types: &TypeCatalogue,
+    dtos: &DtoCatalogue,
+    env: &HashMap<String, String>,
+    findings: usize,
+) -> SecurityFlow {
     let mut edges_out: Vec<SecEdge> = Vec::new();
     let mut seen: HashSet<String> = HashSet::new();
 
     let mut boundary = 0usize;
     let mut pii = 0usize;
+    let mut financial = 0usize;
+    let mut credential = 0usize;
+    let mut exec = 0usize;
+    let mut eval = 0usize;
+    let mut authz_gaps: Vec<AuthzGap> = Vec::new();
+    let mut data_access: Vec<DataAccess> = Vec::new();
+    let mut entrypoints: Vec<EntrypointValidation> = Vec::new();
+    let mut hosts: HashMap<String, ExternalHost> = HashMap::new();
+    let mut vendors: HashMap<&'static str, VendorUsage> = HashMap::new();
+    // app.useGlobalPipes(new ValidationPipe()) anywhere validates every class-validator DTO
+    let global_pipe = all.values().flat_map(|m| m.values()).any(has_global_pipe);
 
-    for (_file, funcs) in all {
+    for (file, funcs) in all {
         for (func, cfg) in funcs {
+            let params = sensitive_params(&cfg.params, taxonomy, types);
+            if !cfg.queries.is_empty() {
+                let reads: BTreeSet<String> = cfg.queries.iter().flat_map(|q| q.reads.iter().cloned()).collect();
+                let writes: BTreeSet<String> = cfg.queries.iter().flat_map(|q| q.writes.iter().cloned()).collect();
+                let classes = reads.iter().chain(&writes).flat_map(|c| taxonomy.classify_text(c)).collect::<BTreeSet<_>>();
+                data_access.push(DataAccess {
+                    file: file.clone(),
+                    func: func.clone(),
+                    entrypoint: cfg.nodes.iter().any(|n| is_entrypoint(n)),
+                    reads: reads.into_iter().collect(),
+                    writes: writes.into_iter().collect(),
+                    classes: classes.into_iter().collect(),
+                });
+            }
+            for call in &cfg.sdk_calls {
+                let v = vendors.entry(call.vendor).or_insert_with(|| VendorUsage {
+                    vendor: call.vendor,
+                    calls: 0,
+                    operations: BTreeSet::new(),
+                    functions: BTreeSet::new(),
+                    classes: BTreeSet::new(),
+                });
+                v.calls += 1;
+                v.operations.insert(call.operation.clone());
+                v.functions.insert(format!("{}::{}", file, func));
+                v.classes.extend(classify_label(&cfg.nodes[call.node], &params, taxonomy));
+            }
+            for call in &cfg.outbound {
+                // env-driven bases resolve through the documented value in .env.example
+                let host = call.host.clone()
+                    .or_else(|| call.env.as_ref().and_then(|e| env.get(e)).and_then(|v| host_of(v)))
+                    .or_else(|| call.env.as_ref().map(|e| format!("${}", e)));
+                let Some(host) = host else { continue };
+                let h = hosts.entry(host.clone()).or_insert_with(|| ExternalHost {
+                    host,
+                    env: None,
+                    calls: 0,
+                    methods: BTreeSet::new(),
+                    credential_headers: BTreeSet::new(),
+                    functions: BTreeSet::new(),
+                });
+                h.calls += 1;
+                if h.env.is_none() { h.env = call.env.clone(); }
+                h.methods.insert(call.method.clone());
+                h.credential_headers.extend(call.credential_headers.iter().cloned());
+                h.functions.insert(format!("{}::{}", file, func));
+            }
+            if let Some(ev) = entrypoint_validation(file, func, cfg, dtos, global_pipe) {
+                entrypoints.push(ev);
+            }
+            if let Some(write) = authz_gap(cfg) {
+                authz_gaps.push(AuthzGap { file: file.clone(), func: func.clone(), db_write: write.clone() });
+            }
             for (si, di) in &cfg.edges {
                 let s = &cfg.nodes[*si];
                 let d = &cfg.nodes[*di];
 
-                let kind = if s.starts_with("NET:") || d.starts_with("NET:") { EdgeKind::Net }
-                else if s.starts_with("DB:") || d.starts_with("DB:") { EdgeKind::Db }
-                else if s.starts_with("AUTH:") || d.starts_with("AUTH:") || s.contains("USER ENTRY") { EdgeKind::Auth }
-                else if s.starts_with("CRYPTO:") || d.starts_with("CRYPTO:") { EdgeKind::Crypto }
-                else if s.starts_with("SECRET:") || d.starts_with("SECRET:") { EdgeKind::Secret }
-                else if s.starts_with("LOG:") || d.starts_with("LOG:") { EdgeKind::Log }
-                else if s.starts_with("Loop") || s == d { EdgeKind::Loop }
-                else if d.starts_with("Return") { EdgeKind::Return }
-                else if s.starts_with("If") || d.starts_with("If") { EdgeKind::Branch }
-                else { EdgeKind::Other };
+                let kind = edge_kind(s, d);
 
                 // unique signature (per func+kind+src+dst)
                 let sig = format!("{}|{:?}|{}|{}", func, kind, s, d);
                 if !seen.insert(sig) { continue; }
 
-                let sensitive = {
-                    let l = format!("{s} {d}").to_lowercase();
-                    l.contains("pii") || l.contains("ssn") || l.contains("passport")
-                        || l.contains("password") || l.contains("token") || l.contains("secret")
-                };
-                if matches!(kind, EdgeKind::Net) { boundary += 1; }
-                if sensitive { pii += 1; }
+                let classes: Vec<DataClass> = classify_label(&format!("{s} {d}"), &params, taxonomy).into_iter().collect();
+                if matches!(kind, EdgeKind::Net | EdgeKind::Queue | EdgeKind::Sdk) { boundary += 1; }
+                if matches!(kind, EdgeKind::Exec) { exec += 1; }
+                if matches!(kind, EdgeKind::CodeEval) { eval += 1; }
+                if classes.contains(&DataClass::Pii) { pii += 1; }
+                if classes.contains(&DataClass::Financial) { financial += 1; }
+                if classes.contains(&DataClass::Credential) { credential += 1; }
 
                 edges_out.push(SecEdge {
                     func: func.clone(),
                     src: s.clone(),
                     dst: d.clone(),
                     kind,
-                    sensitive,
+                    classes,
                 });
             }
         }
     }
 
+    authz_gaps.sort_by(|a, b| (&a.file, &a.func).cmp(&(&b.file, &b.func)));
+    data_access.sort_by(|a, b| (&a.file, &a.func).cmp(&(&b.file, &b.func)));
+    entrypoints.sort_by(|a, b| (&a.file, &a.func).cmp(&(&b.file, &b.func)));
+    let mut external_hosts: Vec<ExternalHost> = hosts.into_values().collect();
+    external_hosts.sort_by(|a, b| a.host.cmp(&b.host));
+    let mut vendors: Vec<VendorUsage> = vendors.into_values().collect();
+    vendors.sort_by(|a, b| a.vendor.cmp(b.vendor));
+
     SecurityFlow {
         index: SecIndex {
             functions: all.values().map(|m| m.len()).sum(),
             edges: edges_out.len(),
             boundary_crossings: boundary,
             pii_edges: pii,
+            financial_edges: financial,
+            credential_edges: credential,
+            authn_without_authz: authz_gaps.len(),
+            exec_sinks: exec,
+            code_eval_sinks: eval,
+            unvalidated_entrypoints: entrypoints.iter().filter(|e| !e.validated).count(),
+            external_hosts: external_hosts.len(),
+            vendors: vendors.len(),
+            findings,
         },
         edges: edges_out,
+        authz_gaps,
+        data_access,
+        entrypoints,
+        external_hosts,
+        vendors,
+    }
+}
+
+/// Kind of a CFG edge between the nodes labelled `s` and `d`: the highest-priority tag on either end,
+/// else its control-flow shape
+pub fn edge_kind(s: &str, d: &str) -> EdgeKind {
+    let tagged = TAG_KINDS.iter().copied().find(|k| {
+        has_tag(s, *k) || has_tag(d, *k)
+            || (matches!(k, EdgeKind::Auth) && s.contains("USER ENTRY"))
+            || (matches!(k, EdgeKind::Queue) && s.starts_with("QUEUE ENTRY"))
+    });
+    if let Some(k) = tagged { k }
+    else if s.starts_with("Loop") || s == d { EdgeKind::Loop }
+    else if d.starts_with("Return") { EdgeKind::Return }
+    else if s.starts_with("If") || d.starts_with("If") { EdgeKind::Branch }
+    else { EdgeKind::Other }
+}
+
+/// Route handlers that authenticate but can reach a DB write from Entry without passing an AUTHZ node:
+/// returns that write's label. Follows CFG edges, so it doesn't depend on how nodes are numbered.
+fn authz_gap(cfg: &SimpleCfg) -> Option<&String> {
+    if !cfg.nodes.iter().any(|n| is_entrypoint(n)) { return None; }
+    if !cfg.nodes.iter().any(|n| has_tag(n, EdgeKind::Auth)) { return None; }
+    let mut seen = vec![false; cfg.nodes.len()];
+    let mut queue = VecDeque::from([0]);
+    while let Some(n) = queue.pop_front() {
+        if n >= seen.len() || seen[n] { continue; }
+        seen[n] = true;
+        let label = &cfg.nodes[n];
+        if has_tag(label, EdgeKind::Authz) { continue; }
+        if has_tag(label, EdgeKind::Db) && is_db_write(label) { return Some(label); }
+        queue.extend(cfg.edges.iter().filter(|(s, _)| *s == n).map(|&(_, d)| d));
     }
+    None
 }
 
 pub fn handle_generate(output: Option<String>, config: &str) {
     println!("[generate] Using config:
 {}", config);
+    let settings = parse_config(config);
+    let taxonomy = Taxonomy::from_config(&settings.classification);
     let Some(out) = output else {
         eprintln!("No output directory specified.");
         return;
@@ -161,20 +862,83 @@ pub fn handle_generate(output: Option<String>, config: &str) {
     let mut handles = vec![];
     for path in files {
         let path_clone = path.clone();
+        let taxonomy = taxonomy.clone();
         handles.push(std::thread::spawn(move || {
-            extract_cfgs_from_ts_file(&path_clone)
+            analyze_ts_file(&path_clone, &taxonomy)
         }));
     }
 
     // Collect results from threads
     let mut all_cfgs: HashMap<String, HashMap<String, SimpleCfg>> = HashMap::new();
+    let mut all_type_fields: Vec<(String, Vec<TypeField>)> = Vec::new();
+    let mut all_dto_rules: Vec<(String, Vec<DtoRule>)> = Vec::new();
+    let mut types = TypeCatalogue::default();
+    let mut dtos = DtoCatalogue::default();
+    let mut all_env_reads: Vec<(String, Vec<EnvRead>)> = Vec::new();
+    let mut all_namespaces: Vec<ConfigNamespace> = Vec::new();
+    let mut all_findings: Vec<RepoFinding> = Vec::new();
     for handle in handles {
         match handle.join() {
-            Ok(Some((file, cfgs))) => { all_cfgs.insert(file, cfgs); }
+            Ok(Some(fa)) => {
+                types.add(&fa.type_fields);
+                dtos.add(&fa.dto_rules);
+                if !fa.dto_rules.is_empty() { all_dto_rules.push((fa.file.clone(), fa.dto_rules)); }
+                if !fa.env_reads.is_empty() { all_env_reads.push((fa.file.clone(), fa.env_reads)); }
+                all_namespaces.extend(fa.config_namespaces);
+                all_findings.extend(fa.secrets.iter().map(|h| secret_finding(&fa.file, h)));
+                if !fa.type_fields.is_empty() { all_type_fields.push((fa.file.clone(), fa.type_fields)); }
+                all_cfgs.insert(fa.file, fa.cfgs);
+            }
             Ok(None) => { /* already logged */ }
             Err(_) => eprintln!("A worker thread panicked while extracting CFGs."),
         }
     }
+    all_type_fields.sort_by(|a, b| a.0.cmp(&b.0));
+    all_dto_rules.sort_by(|a, b| a.0.cmp(&b.0));
+
+    // Named queries from config run as custom checks; one that doesn't parse is reported and skipped
+    let checks: Vec<(&String, &QueryConfig, Query)> = settings.queries.iter()
+        .filter_map(|(name, q)| match Query::parse(&q.query) {
+            Ok(parsed) => Some((name, q, parsed)),
+            Err(e) => {
+                eprintln!("Skipping query {}: {}", name, e);
+                None
+            }
+        })
+        .collect();
+
+    // Rules that need the repo-wide type catalogue run once every file is in
+    for (file, cfgs) in all_cfgs.iter_mut() {
+        for (func, cfg) in cfgs.iter_mut() {
+            let leaks = log_leaks(cfg, &taxonomy, &types);
+            cfg.findings.extend(leaks);
+            let id = format!("{}::{}", file, func);
+            let custom: Vec<Finding> = checks.iter()
+                .flat_map(|(name, q, parsed)| parsed.hits(&id, cfg, &taxonomy, &types).into_iter().map(move |h| Finding {
+                    rule: name.to_string(),
+                    severity: q.severity,
+                    line: h.line,
+                    message: q.message.clone().unwrap_or_else(|| if h.detail.is_empty() { format!("Matches `{}`", q.query) } else { h.detail.clone() }),
+                    hint: None,
+                }))
+                .collect();
+            cfg.findings.extend(custom);
+            all_findings.extend(cfg.findings.iter().map(|f| RepoFinding { file: file.clone(), func: func.clone(), finding: f.clone() }));
+        }
+    }
+    // .env.example documents the env vars a deployment sets; reads are cross-checked against it
+    let dotenv = [".env.example", ".env.sample"]
+        .iter()
+        .find_map(|n| std::fs::read_to_string(root.join(n)).ok().map(|t| (root.join(n).display().to_string(), parse_dotenv(&t))));
+    let documented: Option<Vec<(String, usize)>> = dotenv.as_ref().map(|(_, es)| es.iter().map(|e| (e.key.clone(), e.line)).collect());
+    all_env_reads.sort_by(|a, b| a.0.cmp(&b.0));
+    let (inventory, env_findings) = env_inventory(
+        &all_env_reads,
+        &all_namespaces,
+        dotenv.as_ref().zip(documented.as_deref()).map(|((path, _), keys)| (path.as_str(), keys)),
+    );
+    all_findings.extend(env_findings);
+    all_findings.sort_by(|a, b| (&a.file, a.finding.line, &a.func).cmp(&(&b.file, b.finding.line, &b.func)));
 
     // Write each file's CFGs as JSON mirroring the source tree under .casesmithresults
     for (file, cfgs) in &all_cfgs {
@@ -193,10 +957,26 @@ pub fn handle_generate(output: Option<String>, config: &str) {
             }
             Err(e) => eprintln!("Failed to create output file {}: {}", out_path.display(), e),
         }
+
+        // Test-planning paths per function, next to the CFGs they walk
+        let paths: BTreeMap<&String, FunctionPaths> = cfgs.iter().map(|(func, cfg)| (func, enumerate_paths(cfg, &settings.paths))).collect();
+        out_path.set_extension("");
+        out_path.set_extension("paths.json");
+        match std::fs::File::create(&out_path) {
+            Ok(mut f) => {
+                if let Err(e) = serde_json::to_writer_pretty(&mut f, &paths) {
+                    eprintln!("Failed to write {}: {}", out_path.display(), e);
+                }
+            }
+            Err(e) => eprintln!("Failed to create output file {}: {}", out_path.display(), e),
+        }
     }
 
+    // Documented env values, used to resolve env-driven base URLs to hosts
+    let env: HashMap<String, String> = dotenv.iter().flat_map(|(_, es)| es.iter().map(|e| (e.key.clone(), e.value.clone()))).collect();
+
     // Build and write repo-level security-flow.json into .casesmithresults
-    let flow = to_security_flow(&all_cfgs);
+    let flow = to_security_flow(&all_cfgs, &taxonomy, &types, &dtos, &env, all_findings.len());
     let repo_out = results_root.join("security-flow.json");
     match std::fs::File::create(&repo_out) {
         Ok(mut f) => {
@@ -204,18 +984,93 @@ pub fn handle_generate(output: Option<String>, config: &str) {
                 eprintln!("Failed to write {}: {}", repo_out.display(), e);
             } else {
                 println!(
-                    "Wrote {} (functions: {}, edges: {}, boundary_crossings: {}, pii_edges: {})",
+                    "Wrote {} (functions: {}, edges: {}, boundary_crossings: {}, pii_edges: {}, financial_edges: {}, credential_edges: {}, authn_without_authz: {}, exec_sinks: {}, code_eval_sinks: {}, unvalidated_entrypoints: {}, external_hosts: {}, vendors: {})",
                     repo_out.display(),
                     flow.index.functions,
                     flow.index.edges,
                     flow.index.boundary_crossings,
-                    flow.index.pii_edges
+                    flow.index.pii_edges,
+                    flow.index.financial_edges,
+                    flow.index.credential_edges,
+                    flow.index.authn_without_authz,
+                    flow.index.exec_sinks,
+                    flow.index.code_eval_sinks,
+                    flow.index.unvalidated_entrypoints,
+                    flow.index.external_hosts,
+                    flow.index.vendors
                 );
+                for gap in &flow.authz_gaps {
+                    println!("  authn without authz: {} :: {} → {}", gap.file, gap.func, gap.db_write);
+                }
+                for v in &flow.vendors {
+                    println!("  vendor {}: {} calls ({})", v.vendor, v.calls, v.operations.iter().cloned().collect::<Vec<_>>().join(", "));
+                }
+                for h in &flow.external_hosts {
+                    let creds = if h.credential_headers.is_empty() { String::new() }
+                        else { format!("; credentials: {}", h.credential_headers.iter().cloned().collect::<Vec<_>>().join(", ")) };
+                    println!("  external host: {} ({} calls{})", h.host, h.calls, creds);
+                }
+                for ev in flow.entrypoints.iter().filter(|e| !e.validated) {
+                    println!("  unvalidated input: {} :: {} ({})", ev.file, ev.func, ev.unvalidated.join(", "));
+                }
+                for da in flow.data_access.iter().filter(|d| d.entrypoint && !d.writes.is_empty()) {
+                    println!("  route {} :: {} writes {}", da.file, da.func, da.writes.join(", "));
+                }
             }
         }
         Err(e) => eprintln!("Failed to create {}: {}", repo_out.display(), e),
     }
 
+    // Rule findings across the repo (PII in logs, ...)
+    let findings_path = results_root.join("findings.json");
+    match std::fs::File::create(&findings_path) {
+        Ok(mut f) => match serde_json::to_writer_pretty(&mut f, &all_findings) {
+            Ok(_) => {
+                println!("Wrote {} ({} findings)", findings_path.display(), all_findings.len());
+                for rf in &all_findings {
+                    println!("  [{:?}] {}:{} {} :: {}", rf.finding.severity, rf.file, rf.finding.line, rf.func, rf.finding.message);
+                }
+            }
+            Err(e) => eprintln!("Failed to write {}: {}", findings_path.display(), e),
+        },
+        Err(e) => eprintln!("Failed to create {}: {}", findings_path.display(), e),
+    }
+
+    // Classified DTO/interface fields (e.g. BorrowerDto.dob → pii)
+    let dc_path = results_root.join("data-classification.json");
+    let dc: Vec<FileTypeFields> = all_type_fields.iter().map(|(file, fields)| FileTypeFields { file, fields }).collect();
+    match std::fs::File::create(&dc_path) {
+        Ok(mut f) => match serde_json::to_writer_pretty(&mut f, &dc) {
+            Ok(_) => println!("Wrote {} ({} classified fields)", dc_path.display(), dc.iter().map(|d| d.fields.len()).sum::<usize>()),
+            Err(e) => eprintln!("Failed to write {}: {}", dc_path.display(), e),
+        },
+        Err(e) => eprintln!("Failed to create {}: {}", dc_path.display(), e),
+    }
+
+    // class-validator rules per DTO field (e.g. CreateLoanDto.amount → @IsInt(), @Min(1000))
+    let val_path = results_root.join("validation.json");
+    let val: Vec<FileDtoRules> = all_dto_rules.iter().map(|(file, rules)| FileDtoRules { file, rules }).collect();
+    match std::fs::File::create(&val_path) {
+        Ok(mut f) => match serde_json::to_writer_pretty(&mut f, &val) {
+            Ok(_) => println!("Wrote {} ({} validated DTO fields)", val_path.display(), val.iter().map(|v| v.rules.len()).sum::<usize>()),
+            Err(e) => eprintln!("Failed to write {}: {}", val_path.display(), e),
+        },
+        Err(e) => eprintln!("Failed to create {}: {}", val_path.display(), e),
+    }
+
+    // Env vars / config keys and who reads them
+    let env_path = results_root.join("env-inventory.json");
+    match std::fs::File::create(&env_path) {
+        Ok(mut f) => match serde_json::to_writer_pretty(&mut f, &inventory) {
+            Ok(_) => println!(
+                "Wrote {} ({} keys, {} undocumented, {} documented but unread)",
+                env_path.display(), inventory.vars.len(), inventory.undocumented.len(), inventory.unread.len()
+            ),
+            Err(e) => eprintln!("Failed to write {}: {}", env_path.display(), e),
+        },
+        Err(e) => eprintln!("Failed to create {}: {}", env_path.display(), e),
+    }
+
     // Optional: index.txt for quick glance
     let idx_path = results_root.join("security-flow.index.txt");
     if let Ok(mut f) = std::fs::File::create(&idx_path) {
@@ -224,8 +1079,20 @@ pub fn handle_generate(output: Option<String>, config: &str) {
             "functions: {}
 edges: {}
 boundary_crossings: {}
-pii_edges: {}",
-            flow.index.functions, flow.index.edges, flow.index.boundary_crossings, flow.index.pii_edges
+pii_edges: {}
+financial_edges: {}
+credential_edges: {}
+authn_without_authz: {}
+exec_sinks: {}
+code_eval_sinks: {}
+unvalidated_entrypoints: {}
+external_hosts: {}
+vendors: {}
+findings: {}",
+            flow.index.functions, flow.index.edges, flow.index.boundary_crossings, flow.index.pii_edges,
+            flow.index.financial_edges, flow.index.credential_edges, flow.index.authn_without_authz, flow.index.exec_sinks, flow.index.code_eval_sinks,
+            flow.index.unvalidated_entrypoints, flow.index.external_hosts, flow.index.vendors,
+            flow.index.findings
         );
     }
 }
@@ -234,11 +1101,89 @@ pii_edges: {}",
 #[derive(Debug, Clone, Serialize)]
 pub struct SimpleCfg {
     pub nodes: Vec<String>,
+    /// Where each node's code starts: 1-based line, 0-based column (Exit: where the body ends)
+    pub locs: Vec<(usize, usize)>,
     pub edges: Vec<(usize, usize)>,
+    /// Outcome of each edge leaving an If/Loop decision: (src, dst, condition true?)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<(usize, usize, bool)>,
+    /// Parsed If/Loop conditions with the inputs that drive each way
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<Condition>,
+    /// Callee of each tagged call node as `call_name` flattens it (`this.prisma.loan.update`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<(usize, String)>,
+    /// Tagged calls whose result is kept in a local: `const loans = await prisma.loan.findMany()` → (node, loans)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub results: Vec<(usize, String)>,
+    /// Everything the body calls, tagged or not, by flattened name in first-call order; the call graph
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub invokes: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<Param>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub findings: Vec<Finding>,
+    /// SQL / GraphQL embedded in tagged calls, with the tables/columns/fields they touch
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub queries: Vec<EmbeddedQuery>,
+    /// Outbound HTTP requests made by NET nodes: method, URL, host and credential headers
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub outbound: Vec<HttpCall>,
+    /// Calls into catalogued third-party SDKs (Stripe, Plaid, AWS, ..)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sdk_calls: Vec<SdkCall>,
+    /// For a Nest route handler, its method and path
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route: Option<Route>,
+    /// Log calls and the values they write, checked against the data classification once types are known
+    #[serde(skip)]
+    pub(crate) log_sites: Vec<LogSite>,
 }
 
-/// Parse a TypeScript file and return (file, function name -> CFG) if successful.
-pub fn extract_cfgs_from_ts_file(path: &std::path::Path) -> Option<(String, HashMap<String, SimpleCfg>)> {
+/// A declared function parameter and its type annotation, if any.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Param {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ty: Option<String>,
+    /// Where a handler gets it from, per its Nest decorator: body, query, param, headers, request, payload, file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// The name the request uses when the decorator gives one: `@Param('id') loanId` → id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+/// The HTTP route a Nest handler serves: `@Controller('loans')` + `@Post(':id/approve')`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Route {
+    pub method: String,
+    pub path: String,
+}
+
+/// A call into a vendor SDK, e.g. `plaid` / `transactionsGet`
+#[derive(Debug, Clone, Serialize)]
+pub struct SdkCall {
+    /// Index of the SDK node in the CFG
+    pub node: usize,
+    pub vendor: &'static str,
+    pub operation: String,
+}
+
+/// Everything extracted from one source file.
+pub struct FileAnalysis {
+    pub file: String,
+    pub cfgs: HashMap<String, SimpleCfg>,
+    pub type_fields: Vec<TypeField>,
+    pub dto_rules: Vec<DtoRule>,
+    pub env_reads: Vec<EnvRead>,
+    pub config_namespaces: Vec<ConfigNamespace>,
+    pub secrets: Vec<SecretHit>,
+    pub imports: Vec<Import>,
+}
+
+/// Parse a .ts/.tsx file once and extract its CFGs and classified type fields.
+pub fn analyze_ts_file(path: &std::path::Path, taxonomy: &Taxonomy) -> Option<FileAnalysis> {
     let code = match std::fs::read_to_string(path) {
         Ok(c) => c,
         Err(e) => {
@@ -246,23 +1191,66 @@ pub fn extract_cfgs_from_ts_file(path: &std::path::Path) -> Option<(String, Hash
             return None;
         }
     };
-    let cfgs = extract_cfgs_from_code(&code);
-    Some((path.display().to_string(), cfgs))
+    let tsx = path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("tsx"));
+    Some(analyze_ts_source(&path.display().to_string(), &code, tsx, taxonomy))
+}
+
+/// Analyze source that isn't (necessarily) on disk; `file` is what the analysis calls it.
+pub fn analyze_ts_source(file: &str, code: &str, tsx: bool, taxonomy: &Taxonomy) -> FileAnalysis {
+    let code = code.to_string();
+    let tree = parse_ts(&code, tsx);
+    let mut cfgs = extract_cfgs_from_tree(&code, &tree);
+    let secrets = scan_secrets(&code, tree.root_node(), taxonomy);
+    let (env_reads, config_namespaces) = collect_env_reads(&code, tree.root_node());
+    // Never echo a detected secret through CFG labels either
+    for cfg in cfgs.values_mut() {
+        for label in cfg.nodes.iter_mut() {
+            for hit in &secrets {
+                if label.contains(&hit.raw) { *label = label.replace(&hit.raw, &hit.value); }
+            }
+        }
+    }
+    FileAnalysis {
+        file: file.to_string(),
+        cfgs,
+        type_fields: collect_type_fields(&code, tree.root_node(), taxonomy),
+        dto_rules: collect_dto_rules(&code, tree.root_node()),
+        env_reads,
+        config_namespaces,
+        secrets,
+        imports: collect_imports(&code, tree.root_node()),
+    }
+}
+
+/// Parse a TypeScript file and return (file, function name -> CFG) if successful.
+pub fn extract_cfgs_from_ts_file(path: &std::path::Path) -> Option<(String, HashMap<String, SimpleCfg>)> {
+    analyze_ts_file(path, &Taxonomy::default()).map(|fa| (fa.file, fa.cfgs))
 }
 
 /// Parse TypeScript code and extract all function CFGs.
 pub fn extract_cfgs_from_code(code: &str) -> HashMap<String, SimpleCfg> {
+    let tree = parse_ts(code, false);
+    extract_cfgs_from_tree(code, &tree)
+}
+
+/// Parse TSX (TypeScript + JSX) code and extract all function CFGs.
+pub fn extract_cfgs_from_tsx_code(code: &str) -> HashMap<String, SimpleCfg> {
+    let tree = parse_ts(code, true);
+    extract_cfgs_from_tree(code, &tree)
+}
+
+fn parse_ts(code: &str, tsx: bool) -> Tree {
     let mut parser = TreeSitterParser::new();
-    let language = tree_sitter_typescript::LANGUAGE_TYPESCRIPT;
+    let language = if tsx { tree_sitter_typescript::LANGUAGE_TSX } else { tree_sitter_typescript::LANGUAGE_TYPESCRIPT };
     parser.set_language(&language.into()).expect("Error setting language");
-    let tree = parser.parse(code, None).unwrap();
-    extract_cfgs_from_tree(code, &tree)
+    parser.parse(code, None).unwrap()
 }
 
 /// Given code and a tree, extract all function CFGs.
 pub fn extract_cfgs_from_tree(code: &str, tree: &Tree) -> HashMap<String, SimpleCfg> {
     let mut result = HashMap::new();
     let root = tree.root_node();
+    let clients = &FileClients::scan(code, root);
     let mut stack = vec![root];
     while let Some(node) = stack.pop() {
         for i in 0..node.child_count() {
@@ -277,21 +1265,22 @@ pub fn extract_cfgs_from_tree(code: &str, tree: &Tree) -> HashMap<String, Simple
                         .map(|n| code[n.start_byte()..n.end_byte()].to_string())
                         .unwrap_or_else(|| "<anon>".to_string());
                     let body = ch.child_by_field_name("body").unwrap_or(ch);
-                    let mut cfg = build_structured_cfg(code, body);
+                    let mut cfg = build_cfg(code, clients, &[], body);
                     dedupe_cfg_edges(&mut cfg);
+                    cfg.params = function_params(code, ch);
                     result.insert(name, cfg);
                 }
                 // Class declarations (include methods & constructor)
                 "class_declaration" | "class" => {
-                    extract_from_class(code, ch, &mut result);
+                    extract_from_class(code, clients, ch, &mut result);
                 }
                 // Exported declarations, e.g. `export const foo = () => {}`
                 "export_statement" => {
-                    extract_from_export(code, ch, &mut result);
+                    extract_from_export(code, clients, ch, &mut result);
                 }
                 // Variable/lexical declarations, e.g. `const foo = () => {}` or `var bar = function() {}`
                 "lexical_declaration" | "variable_declaration" => {
-                    extract_from_var_declaration(code, ch, &mut result);
+                    extract_from_var_declaration(code, clients, ch, &mut result);
                 }
                 _ => {}
             }
@@ -300,7 +1289,7 @@ pub fn extract_cfgs_from_tree(code: &str, tree: &Tree) -> HashMap<String, Simple
     result
 }
 
-fn extract_from_export(code: &str, export_node: Node, out: &mut HashMap<String, SimpleCfg>) {
+fn extract_from_export(code: &str, clients: &FileClients, export_node: Node, out: &mut HashMap<String, SimpleCfg>) {
     // Walk entire export subtree so we catch:
     // - export function foo() {}
     // - export class Foo {}
@@ -316,25 +1305,27 @@ fn extract_from_export(code: &str, export_node: Node, out: &mut HashMap<String,
                     .map(|x| code[x.start_byte()..x.end_byte()].to_string())
                     .unwrap_or_else(|| "<anon>".to_string());
                 let body = n.child_by_field_name("body").unwrap_or(n);
-                let mut cfg = build_structured_cfg(code, body);
+                let mut cfg = build_cfg(code, clients, &[], body);
                 dedupe_cfg_edges(&mut cfg);
+                cfg.params = function_params(code, n);
                 out.insert(name, cfg);
             }
             "class_declaration" | "class" => {
-                extract_from_class(code, n, out);
+                extract_from_class(code, clients, n, out);
             }
             "lexical_declaration" | "variable_declaration" => {
-                extract_from_var_declaration(code, n, out);
+                extract_from_var_declaration(code, clients, n, out);
             }
             "assignment_expression" => {
-                extract_from_assignment(code, n, out);
+                extract_from_assignment(code, clients, n, out);
             }
             // A bare arrow/function expression directly under export default
             "arrow_function" | "function_expression" => {
                 let body = n.child_by_field_name("body").unwrap_or(n);
                 let synth = format!("default_export@b{}", n.start_byte());
-                let mut cfg = build_structured_cfg(code, body);
+                let mut cfg = build_cfg(code, clients, &[], body);
                 dedupe_cfg_edges(&mut cfg);
+                cfg.params = function_params(code, n);
                 out.insert(synth, cfg);
             }
             _ => {
@@ -344,7 +1335,7 @@ fn extract_from_export(code: &str, export_node: Node, out: &mut HashMap<String,
     }
 }
 
-fn extract_from_assignment(code: &str, assign_node: Node, out: &mut HashMap<String, SimpleCfg>) {
+fn extract_from_assignment(code: &str, clients: &FileClients, assign_node: Node, out: &mut HashMap<String, SimpleCfg>) {
     let left = assign_node.child_by_field_name("left");
     let right = assign_node.child_by_field_name("right");
     if let (Some(l), Some(r)) = (left, right) {
@@ -359,14 +1350,15 @@ fn extract_from_assignment(code: &str, assign_node: Node, out: &mut HashMap<Stri
                 } else { "<exported>".to_string() }
             } else { "<exported>".to_string() };
             let body = r.child_by_field_name("body").unwrap_or(r);
-            let mut cfg = build_structured_cfg(code, body);
+            let mut cfg = build_cfg(code, clients, &[], body);
             dedupe_cfg_edges(&mut cfg);
+            cfg.params = function_params(code, r);
             out.insert(name, cfg);
         }
     }
 }
 
-fn extract_from_var_declaration(code: &str, decl_node: Node, out: &mut HashMap<String, SimpleCfg>) {
+fn extract_from_var_declaration(code: &str, clients: &FileClients, decl_node: Node, out: &mut HashMap<String, SimpleCfg>) {
     // Find all variable_declarator nodes under this declaration
     let mut q = vec![decl_node];
     while let Some(n) = q.pop() {
@@ -382,8 +1374,9 @@ fn extract_from_var_declaration(code: &str, decl_node: Node, out: &mut HashMap<S
                         let name = code[name_node.start_byte()..name_node.end_byte()].to_string();
                         // Body may be statement_block or expression (for concise arrow bodies). We handle both.
                         let body_node = val.child_by_field_name("body").unwrap_or(val);
-                        let mut cfg = build_structured_cfg(code, body_node);
+                        let mut cfg = build_cfg(code, clients, &[], body_node);
                         dedupe_cfg_edges(&mut cfg);
+                        cfg.params = function_params(code, val);
                         out.insert(name, cfg);
                     }
                 }
@@ -394,7 +1387,7 @@ fn extract_from_var_declaration(code: &str, decl_node: Node, out: &mut HashMap<S
     }
 }
 
-fn extract_from_class(code: &str, class_node: Node, out: &mut HashMap<String, SimpleCfg>) {
+fn extract_from_class(code: &str, clients: &FileClients, class_node: Node, out: &mut HashMap<String, SimpleCfg>) {
     // Class name (may be anonymous)
     let class_name = class_node
         .child_by_field_name("name")
@@ -407,9 +1400,23 @@ fn extract_from_class(code: &str, class_node: Node, out: &mut HashMap<String, Si
         None => return,
     };
 
+    // Class-level decorators (e.g. @UseGuards on a controller) apply to every method; they sit on the
+    // class node itself or on a wrapping `export` statement.
+    let mut class_decorators = decorator_children(class_node);
+    if let Some(p) = class_node.parent().filter(|p| p.kind() == "export_statement") {
+        class_decorators.extend(decorator_children(p));
+    }
+    // Method decorators are siblings preceding the method_definition inside the class body
+    let mut pending: Vec<Node> = Vec::new();
+
     for i in 0..body.child_count() {
         let m = body.child(i).unwrap();
         let kind = m.kind();
+        if kind == "decorator" {
+            pending.push(m);
+            continue;
+        }
+        let method_decorators = std::mem::take(&mut pending);
         // method_definition or constructor with a body
         if kind == "method_definition" || kind == "constructor" {
             // name may be under field "name" or "property"
@@ -421,8 +1428,11 @@ fn extract_from_class(code: &str, class_node: Node, out: &mut HashMap<String, Si
                 .map(|n| code[n.start_byte()..n.end_byte()].to_string())
                 .unwrap_or_else(|| if kind == "constructor" { "constructor".to_string() } else { "<anon_method>".to_string() });
             let body_node = m.child_by_field_name("body").unwrap_or(m);
-            let mut cfg = build_structured_cfg(code, body_node);
+            let decorators: Vec<Node> = class_decorators.iter().chain(&method_decorators).chain(&param_pipes(code, m)).copied().collect();
+            let mut cfg = build_cfg(code, clients, &decorators, body_node);
             dedupe_cfg_edges(&mut cfg);
+            cfg.params = function_params(code, m);
+            cfg.route = nest_route(code, &class_decorators, &method_decorators);
             out.insert(format!("{}.{}", class_name, method_name), cfg);
             continue;
         }
@@ -436,8 +1446,9 @@ fn extract_from_class(code: &str, class_node: Node, out: &mut HashMap<String, Si
                 let vkind = val.kind();
                 if vkind == "arrow_function" || vkind == "function" || vkind == "function_expression" {
                     let body_node = val.child_by_field_name("body").unwrap_or(val);
-                    let mut cfg = build_structured_cfg(code, body_node);
+                    let mut cfg = build_cfg(code, clients, &[], body_node);
                     dedupe_cfg_edges(&mut cfg);
+                    cfg.params = function_params(code, val);
                     out.insert(format!("{}.{}", class_name, method_name), cfg);
                 }
             }
@@ -445,95 +1456,499 @@ fn extract_from_class(code: &str, class_node: Node, out: &mut HashMap<String, Si
     }
 }
 
+// `@Controller('loans')` + `@Post(':id/approve')` → POST /loans/:id/approve
+fn nest_route(code: &str, class_decorators: &[Node], method_decorators: &[Node]) -> Option<Route> {
+    // first string literal among the decorator's arguments (`'loans'`, `{ path: 'loans' }`)
+    let arg = |d: &Node| -> String {
+        let text = snippet_full(code, *d);
+        let args = text.split_once('(').map(|(_, a)| a).unwrap_or("");
+        args.split(['\'', '"', '`']).nth(1).filter(|_| args.contains(['\'', '"', '`'])).unwrap_or("").to_string()
+    };
+    let name = |d: &Node| snippet(code, *d).trim_start_matches('@').split('(').next().unwrap_or("").to_string();
+    let (method, path) = method_decorators.iter().find_map(|d| {
+        let n = name(d);
+        ["Get", "Post", "Put", "Delete", "Patch", "All", "Options", "Head"].contains(&n.as_str()).then(|| (n.to_uppercase(), arg(d)))
+    })?;
+    let prefix = class_decorators.iter().find(|d| name(d) == "Controller").map(arg).unwrap_or_default();
+    let parts: Vec<&str> = [prefix.as_str(), path.as_str()].iter().map(|p| p.trim_matches('/')).filter(|p| !p.is_empty()).collect();
+    Some(Route { method, path: format!("/{}", parts.join("/")) })
+}
+
+// Parameters of a function-like node: `(id: string, @Body() dto: LoanDto)` or a bare arrow `x => ..`
+fn function_params(code: &str, func: Node) -> Vec<Param> {
+    if let Some(p) = func.child_by_field_name("parameter") {
+        return vec![Param { name: code[p.start_byte()..p.end_byte()].to_string(), ty: None, source: None, key: None }];
+    }
+    let Some(list) = func.child_by_field_name("parameters") else { return vec![] };
+    (0..list.named_child_count())
+        .filter_map(|i| list.named_child(i))
+        .filter_map(|p| {
+            let pat = p.child_by_field_name("pattern")?;
+            let ty = p.child_by_field_name("type")
+                .map(|t| code[t.start_byte()..t.end_byte()].trim_start_matches(':').trim().to_string());
+            let (source, key) = param_source(code, p).unzip();
+            Some(Param { name: code[pat.start_byte()..pat.end_byte()].to_string(), ty, source, key: key.flatten() })
+        })
+        .collect()
+}
+
+// `@Body() dto` → "body", `@Param('id') id` → "param"; other parameter decorators are not request input
+fn param_source(code: &str, param: Node) -> Option<(String, Option<String>)> {
+    decorator_children(param).into_iter().find_map(|d| {
+        let text = snippet(code, d);
+        let name = text.trim_start_matches('@').split('(').next().unwrap_or("").to_lowercase();
+        let source = match name.as_str() {
+            "body" | "query" | "param" | "headers" | "payload" => name.as_str(),
+            "req" | "request" => "request",
+            "uploadedfile" | "uploadedfiles" => "file",
+            _ => return None,
+        };
+        // `@Param('id')` / `@Query("status", ParseEnumPipe)`: the name in the request
+        let key = text.split_once('(')
+            .map(|(_, args)| args.trim_start())
+            .filter(|a| a.starts_with(['\'', '"']))
+            .and_then(|a| a[1..].split(['\'', '"']).next())
+            .map(str::to_string);
+        Some((source.to_string(), key))
+    })
+}
+
+// Parameter decorators that run a pipe (`@Body(new ValidationPipe()) dto`), tagged with the handler's decorators
+fn param_pipes<'a>(code: &str, method: Node<'a>) -> Vec<Node<'a>> {
+    let Some(list) = method.child_by_field_name("parameters") else { return vec![] };
+    (0..list.named_child_count())
+        .filter_map(|i| list.named_child(i))
+        .flat_map(decorator_children)
+        .filter(|d| is_validation_decorator(&snippet(code, *d)))
+        .collect()
+}
+
+fn decorator_children(n: Node) -> Vec<Node> {
+    (0..n.child_count()).filter_map(|i| n.child(i)).filter(|c| c.kind() == "decorator").collect()
+}
+
 fn dedupe_cfg_edges(cfg: &mut SimpleCfg) {
     let mut seen = HashSet::<(usize, usize)>::new();
     cfg.edges.retain(|e| seen.insert(*e));
+    let mut seen = HashSet::<(usize, usize, bool)>::new();
+    cfg.branches.retain(|b| seen.insert(*b));
+}
+
+// NestJS decorators: route handlers (public entry points), authentication and authorization guards, validation pipes
+fn tag_decorator(b: &mut CfgBuilder, deco_node: Node, preds: &mut Preds) {
+    let code = b.code;
+    let deco_raw = snippet(code, deco_node);
+    let deco = deco_raw.to_lowercase();
+    if ["@get", "@post", "@put", "@delete", "@patch", "@all"].iter().any(|d| deco.starts_with(d)) {
+        b.tag(preds, "USER ENTRY (Nest route)".to_string());
+    }
+    if is_authn_decorator(&deco_raw) {
+        b.tag(preds, format!("AUTH: {}", deco_raw));
+    }
+    if is_authz_decorator(&deco_raw) {
+        b.tag(preds, format!("AUTHZ: {}", deco_raw));
+    }
+    if is_validation_decorator(&deco_raw) {
+        // a pipe on a parameter names it, so the entrypoint check can tell which input it covers
+        let param = deco_node.parent()
+            .filter(|p| p.kind().ends_with("_parameter"))
+            .and_then(|p| p.child_by_field_name("pattern"))
+            .map(|p| format!(" {}", snippet(code, p)))
+            .unwrap_or_default();
+        b.tag(preds, format!("VALIDATE: {}{}", deco_raw, param));
+    }
+    if is_queue_handler_decorator(&deco_raw) {
+        let topics = deco_node.named_child(0).map(|c| queue_topics(code, c)).unwrap_or_default();
+        b.tag(preds, with_topics("QUEUE ENTRY (message handler)".to_string(), &topics));
+    }
+}
+
+// The local a call's result lands in: `const loans = await prisma.loan.findMany()`, `res = await axios.get(url)`
+fn result_local(code: &str, call: Node) -> Option<String> {
+    let mut n = call;
+    while let Some(p) = n.parent()
+        && matches!(p.kind(), "await_expression" | "parenthesized_expression" | "as_expression" | "non_null_expression")
+    {
+        n = p;
+    }
+    let p = n.parent()?;
+    let target = match p.kind() {
+        "variable_declarator" if p.child_by_field_name("value") == Some(n) => p.child_by_field_name("name")?,
+        "assignment_expression" if p.child_by_field_name("right") == Some(n) => p.child_by_field_name("left")?,
+        _ => return None,
+    };
+    (target.kind() == "identifier").then(|| snippet(code, target))
+}
+
+// Istanbul-style position: 1-based line, 0-based column
+fn pos(n: Node) -> (usize, usize) {
+    (n.start_position().row + 1, n.start_position().column)
+}
+
+// A node's whole text on one line (labels use `snippet`, which keeps the first line only)
+fn snippet_full(code: &str, n: Node) -> String {
+    code[n.start_byte()..n.end_byte()].split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// "QUEUE: producer.send(..)" + ["loan.approved"] → "QUEUE: producer.send(..) [topic=loan.approved]"
+fn with_topics(label: String, topics: &[String]) -> String {
+    if topics.is_empty() { label } else { format!("{} [topic={}]", label, topics.join(",")) }
 }
 
 /// Build a simple structured CFG for a function body node.
 pub fn build_structured_cfg(code: &str, body: Node) -> SimpleCfg {
-    // helper: avoid pushing identical tag nodes back-to-back
-    fn push_tag_node(nodes: &mut Vec<String>, edges: &mut Vec<(usize, usize)>, last: &mut usize, label: String) {
-        if nodes.get(*last).map(|s| s == &label).unwrap_or(false) { return; }
-        let idx = nodes.len();
-        nodes.push(label);
-        edges.push((*last, idx));
-        *last = idx;
-    }
-
-    let mut nodes = vec!["Entry".to_string(), "Exit".to_string()];
-    let mut edges = vec![];
-    let mut last = 0;
-    let exit = 1;
-
-    let mut stack = vec![body];
-    while let Some(n) = stack.pop() {
-        for i in 0..n.child_count() {
-            let ch = n.child(i).unwrap();
-            stack.push(ch);
-            let kind = ch.kind();
-
-            // Branches / loops / returns (original behavior)
-            if kind == "if_statement" {
-                let cond = snippet(code, ch);
-                let idx = nodes.len();
-                nodes.push(format!("If: {}", cond));
-                edges.push((last, idx));
-                last = idx;
-            } else if kind == "for_statement" || kind == "while_statement" {
-                let label = snippet(code, ch);
-                let idx = nodes.len();
-                nodes.push(format!("Loop: {}", label));
-                edges.push((last, idx));
-                edges.push((idx, idx)); // self-loop
-                edges.push((idx, exit));
-                last = idx;
-            } else if kind == "return_statement" {
-                let label = snippet(code, ch);
-                let idx = nodes.len();
-                nodes.push(format!("Return: {}", label));
-                edges.push((last, idx));
-                last = idx;
-            }
-
-            // Detect calls → tag NET/DB/AUTH/CRYPTO/LOG
-            if kind == "call_expression" {
-                if let Some(k) = classify_call(code, ch) {
-                    let prefix = match k {
-                        EdgeKind::Net => "NET",
-                        EdgeKind::Db => "DB",
-                        EdgeKind::Auth => "AUTH",
-                        EdgeKind::Crypto => "CRYPTO",
-                        EdgeKind::Log => "LOG",
-                        _ => "OTHER",
-                    };
-                    let label = format!("{}: {}", prefix, snippet(code, ch));
-                    push_tag_node(&mut nodes, &mut edges, &mut last, label);
+    let mut root = body;
+    while let Some(p) = root.parent() { root = p; }
+    build_cfg(code, &FileClients::scan(code, root), &[], body)
+}
+
+// HTTP client and vendor SDK instances declared anywhere in a file. Found once per file and shared by
+// every function's CFG, so calls on them are tagged without walking the tree again per function.
+struct FileClients {
+    http: Vec<HttpClient>,
+    sdk: Vec<SdkClient>,
+}
+
+impl FileClients {
+    fn scan(code: &str, root: Node) -> Self {
+        let lower = code.to_lowercase();
+        FileClients {
+            // axios.create / got.extend instances, so calls on them count as NET
+            http: if code.contains(".create(") || code.contains(".extend(") { http_clients(code, root) } else { vec![] },
+            // Vendor SDK imports and client instances, looked for only if a catalogued vendor is mentioned
+            sdk: if SDK_CATALOGUE.iter().any(|s| lower.contains(s.vendor)) { sdk_clients(code, root) } else { vec![] },
+        }
+    }
+}
+
+/// Build a CFG for a body, tagging the decorators that annotate the function (route, guards, roles) first.
+/// Statements are laid out in source order; `if`s and loops fork into true/false successors (see `branches`),
+/// `return`/`throw` go straight to Exit.
+fn build_cfg<'a>(code: &'a str, clients: &'a FileClients, decorators: &[Node<'a>], body: Node<'a>) -> SimpleCfg {
+    let mut b = CfgBuilder {
+        code,
+        nodes: vec!["Entry".to_string(), "Exit".to_string()],
+        locs: vec![pos(body), (body.end_position().row + 1, body.end_position().column)],
+        at: pos(body),
+        edges: vec![],
+        branches: vec![],
+        conditions: vec![],
+        calls: vec![],
+        results: vec![],
+        invokes: vec![],
+        log_sites: vec![],
+        findings: vec![],
+        queries: vec![],
+        outbound: vec![],
+        sdk_calls: vec![],
+        // GraphQL documents are only looked for in files that mention gql/graphql at all
+        has_gql: code.contains("gql") || code.contains("graphql"),
+        clients: &clients.http,
+        vendors: &clients.sdk,
+        loops: vec![],
+    };
+
+    let mut preds: Preds = vec![(0, None)];
+    for d in decorators {
+        b.at = pos(*d);
+        tag_decorator(&mut b, *d, &mut preds);
+    }
+    let preds = b.stmt(body, preds);
+    for (p, outcome) in preds {
+        b.edge(p, 1, outcome);
+    }
+    SimpleCfg {
+        nodes: b.nodes,
+        locs: b.locs,
+        edges: b.edges,
+        branches: b.branches,
+        conditions: b.conditions,
+        calls: b.calls,
+        results: b.results,
+        invokes: b.invokes,
+        params: vec![],
+        route: None,
+        findings: b.findings,
+        queries: b.queries,
+        outbound: b.outbound,
+        sdk_calls: b.sdk_calls,
+        log_sites: b.log_sites,
+    }
+}
+
+// Open ends waiting for the next node: (node, outcome when the edge leaves an If/Loop decision)
+type Preds = Vec<(usize, Option<bool>)>;
+
+struct CfgBuilder<'a> {
+    code: &'a str,
+    nodes: Vec<String>,
+    locs: Vec<(usize, usize)>,
+    // where the node about to be added starts
+    at: (usize, usize),
+    edges: Vec<(usize, usize)>,
+    branches: Vec<(usize, usize, bool)>,
+    conditions: Vec<Condition>,
+    calls: Vec<(usize, String)>,
+    results: Vec<(usize, String)>,
+    invokes: Vec<String>,
+    log_sites: Vec<LogSite>,
+    findings: Vec<Finding>,
+    queries: Vec<EmbeddedQuery>,
+    outbound: Vec<HttpCall>,
+    sdk_calls: Vec<SdkCall>,
+    has_gql: bool,
+    clients: &'a [HttpClient],
+    vendors: &'a [SdkClient],
+    // enclosing loops: header node and the `break`s that leave it
+    loops: Vec<(usize, Preds)>,
+}
+
+impl<'a> CfgBuilder<'a> {
+    fn edge(&mut self, src: usize, dst: usize, outcome: Option<bool>) {
+        self.edges.push((src, dst));
+        if let Some(o) = outcome { self.branches.push((src, dst, o)); }
+    }
+
+    fn node(&mut self, preds: &Preds, label: String) -> usize {
+        let idx = self.nodes.len();
+        self.nodes.push(label);
+        self.locs.push(self.at);
+        for &(p, outcome) in preds {
+            self.edge(p, idx, outcome);
+        }
+        idx
+    }
+
+    // Append a tag node after `preds`, unless it would repeat the single node right before it
+    fn tag(&mut self, preds: &mut Preds, label: String) -> usize {
+        if let [(p, None)] = preds.as_slice()
+            && self.nodes[*p] == label
+        {
+            return *p;
+        }
+        let idx = self.node(preds, label);
+        *preds = vec![(idx, None)];
+        idx
+    }
+
+    fn condition(&mut self, node: usize, expr: Node) {
+        let text = snippet_full(self.code, expr);
+        let text = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')).unwrap_or(&text).trim().to_string();
+        self.conditions.push(Condition { node, text, inputs: boundaries(self.code, expr) });
+    }
+
+    // `while (..)` / `for (..; i < n; ..)` conditions; `for (x of xs)` runs once per item of xs
+    fn loop_condition(&mut self, node: usize, n: Node) {
+        if n.kind() == "for_in_statement" {
+            let head = n.child_by_field_name("body").map(|b| &self.code[n.start_byte()..b.start_byte()]).unwrap_or_default();
+            let text = head.trim().trim_start_matches("for").trim().trim_start_matches("await").trim();
+            let text = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')).unwrap_or(text);
+            let inputs = n.child_by_field_name("right")
+                .filter(|_| snippet_full(self.code, n).contains(" of "))
+                .map(|r| vec![iterable_boundary(self.code, r)])
+                .unwrap_or_default();
+            self.conditions.push(Condition { node, text: text.split_whitespace().collect::<Vec<_>>().join(" "), inputs });
+            return;
+        }
+        let mut cond = n.child_by_field_name("condition");
+        if let Some(c) = cond.filter(|c| c.kind() == "expression_statement") { cond = c.named_child(0); }
+        if let Some(c) = cond.filter(|c| c.kind() != "empty_statement") { self.condition(node, c); }
+    }
+
+    // Control flow at statement level; everything else is scanned for tags in evaluation order
+    fn stmt(&mut self, n: Node<'a>, preds: Preds) -> Preds {
+        let code = self.code;
+        match n.kind() {
+            "statement_block" | "else_clause" => {
+                let mut p = preds;
+                for i in 0..n.named_child_count() {
+                    p = self.stmt(n.named_child(i).unwrap(), p);
                 }
+                p
             }
-
-            // Secrets/config reads anywhere
-            if kind == "member_expression" || kind == "call_expression" || kind == "identifier" {
-                if is_secretish(code, ch) {
-                    let label = format!("SECRET: {}", snippet(code, ch));
-                    push_tag_node(&mut nodes, &mut edges, &mut last, label);
+            "if_statement" => {
+                let mut p = preds;
+                if let Some(c) = n.child_by_field_name("condition") { self.scan(c, &mut p); }
+                let cond = snippet(code, n);
+                self.at = pos(n);
+                if is_ownership_check(code, n) {
+                    self.tag(&mut p, format!("AUTHZ: ownership check {}", cond));
+                }
+                let idx = self.node(&p, format!("If: {}", cond));
+                if let Some(c) = n.child_by_field_name("condition") { self.condition(idx, c); }
+                let mut out = match n.child_by_field_name("consequence") {
+                    Some(c) => self.stmt(c, vec![(idx, Some(true))]),
+                    None => vec![(idx, Some(true))],
+                };
+                match n.child_by_field_name("alternative") {
+                    Some(alt) => out.extend(self.stmt(alt, vec![(idx, Some(false))])),
+                    None => out.push((idx, Some(false))),
                 }
+                out
             }
-
-            // NestJS route handlers via decorators (public entry points)
-            if kind == "decorator" {
-                let deco_raw = snippet(code, ch);
-                let deco = deco_raw.to_lowercase();
-                if ["@get", "@post", "@put", "@delete", "@patch", "@all"].iter().any(|d| deco.starts_with(d)) {
-                    push_tag_node(&mut nodes, &mut edges, &mut last, "USER ENTRY (Nest route)".to_string());
+            "for_statement" | "for_in_statement" | "while_statement" | "do_statement" => {
+                let mut p = preds;
+                let body = n.child_by_field_name("body");
+                for i in 0..n.named_child_count() {
+                    let c = n.named_child(i).unwrap();
+                    if Some(c) != body { self.scan(c, &mut p); }
+                }
+                self.at = pos(n);
+                let idx = self.node(&p, format!("Loop: {}", snippet(code, n)));
+                self.loop_condition(idx, n);
+                self.loops.push((idx, vec![]));
+                let out = match body {
+                    Some(b) => self.stmt(b, vec![(idx, Some(true))]),
+                    None => vec![(idx, Some(true))],
+                };
+                // back edge; an iteration with nothing tagged in it is a self-loop
+                for (o, outcome) in out {
+                    self.edge(o, idx, outcome);
+                }
+                let (_, breaks) = self.loops.pop().unwrap_or_default();
+                let mut exit = vec![(idx, Some(false))];
+                exit.extend(breaks);
+                exit
+            }
+            "return_statement" | "throw_statement" => {
+                let mut p = preds;
+                for i in 0..n.named_child_count() { self.scan(n.named_child(i).unwrap(), &mut p); }
+                let kind = if n.kind() == "return_statement" { "Return" } else { "Throw" };
+                self.at = pos(n);
+                let idx = self.node(&p, format!("{}: {}", kind, snippet(code, n)));
+                self.edge(idx, 1, None);
+                vec![]
+            }
+            "break_statement" if !self.loops.is_empty() => {
+                if let Some((_, breaks)) = self.loops.last_mut() { breaks.extend(preds); }
+                vec![]
+            }
+            "continue_statement" if !self.loops.is_empty() => {
+                let header = self.loops.last().map(|l| l.0).unwrap_or(0);
+                for (p, outcome) in preds {
+                    self.edge(p, header, outcome);
                 }
-                if deco.contains("useguards") || deco.contains("auth") {
-                    push_tag_node(&mut nodes, &mut edges, &mut last, format!("AUTH: {}", deco_raw));
+                vec![]
+            }
+            "try_statement" => {
+                // the catch block is entered from wherever the try block started
+                let mut out = match n.child_by_field_name("body") {
+                    Some(body) => self.stmt(body, preds.clone()),
+                    None => preds.clone(),
+                };
+                if let Some(h) = n.child_by_field_name("handler").and_then(|h| h.child_by_field_name("body")) {
+                    out.extend(self.stmt(h, preds));
+                }
+                if let Some(f) = n.child_by_field_name("finalizer").and_then(|f| f.child_by_field_name("body")) {
+                    out = self.stmt(f, out);
                 }
+                out
+            }
+            _ => {
+                let mut p = preds;
+                self.scan(n, &mut p);
+                p
             }
         }
     }
 
-    edges.push((last, exit));
-    SimpleCfg { nodes, edges }
+    // Tag calls, sinks, secrets and decorators in a subtree, innermost first (arguments before the call)
+    fn scan(&mut self, n: Node<'a>, preds: &mut Preds) {
+        for i in 0..n.child_count() {
+            self.scan(n.child(i).unwrap(), preds);
+        }
+        self.visit(n, preds);
+    }
+
+    fn visit(&mut self, ch: Node<'a>, preds: &mut Preds) {
+        let code = self.code;
+        self.at = pos(ch);
+        let kind = ch.kind();
+        if kind == "call_expression"
+            && let Some(name) = call_name(code, ch)
+            && !self.invokes.contains(&name)
+        {
+            self.invokes.push(name);
+        }
+
+        // Detect calls and constructions → tag NET/DB/AUTH/AUTHZ/CRYPTO/LOG/EXEC/EVAL/STORAGE/QUEUE/VALIDATE/SDK.
+        // A call carrying a GraphQL document goes over the wire whatever the client is called.
+        let gql = if self.has_gql && kind == "call_expression" { graphql_ops(code, ch) } else { vec![] };
+        let http = if kind == "call_expression" { outbound_call(code, ch, self.clients) } else { None };
+        let vendor = if kind == "call_expression" && gql.is_empty() { sdk_call(code, ch, self.vendors) } else { None };
+        let tagged = if !gql.is_empty() { Some(EdgeKind::Net) }
+            else if vendor.is_some() { Some(EdgeKind::Sdk) }
+            else if kind == "call_expression" || kind == "new_expression" {
+                classify_call(code, ch).or(http.as_ref().map(|_| EdgeKind::Net))
+            }
+            else { None };
+        if let Some(k) = tagged {
+            let mut label = format!("{}: {}", k.prefix().unwrap_or("OTHER"), snippet(code, ch));
+            if matches!(k, EdgeKind::Storage)
+                && let Some(target) = storage_target(code, ch)
+            {
+                label = format!("{} [{}]", label, target);
+            }
+            if matches!(k, EdgeKind::Queue) {
+                label = with_topics(label, &queue_topics(code, ch));
+            }
+            if matches!(k, EdgeKind::Log) {
+                self.log_sites.push(log_site(code, ch));
+            }
+            let found = if matches!(k, EdgeKind::Db) { embedded_sql(code, ch) } else { gql };
+            for q in &found {
+                label = format!("{} {}", label, q.label_suffix());
+            }
+            let http = http.filter(|_| matches!(k, EdgeKind::Net));
+            if let Some(h) = &http {
+                label = format!("{} {}", label, h.label_suffix());
+            }
+            if let Some((v, op)) = &vendor {
+                label = format!("{} [{}.{}]", label, v, op);
+            }
+            let node = self.tag(preds, label);
+            if let Some(name) = call_name(code, ch).filter(|_| kind == "call_expression") {
+                self.calls.push((node, name));
+                if let Some(local) = result_local(code, ch) { self.results.push((node, local)); }
+            }
+            self.queries.extend(found.into_iter().map(|q| EmbeddedQuery { node, ..q }));
+            self.outbound.extend(http.map(|h| HttpCall { node, ..h }));
+            self.sdk_calls.extend(vendor.map(|(vendor, operation)| SdkCall { node, vendor, operation }));
+        }
+
+        // Browser sinks written as assignments or JSX attributes (XSS/REDIRECT/CLIENT_STORAGE)
+        if (kind == "assignment_expression" || kind == "augmented_assignment_expression")
+            && let Some(k) = classify_assignment(code, ch)
+        {
+            let label = format!("{}: {}", k.prefix().unwrap_or("OTHER"), snippet(code, ch));
+            self.tag(preds, label);
+        }
+        if kind == "jsx_attribute" && is_jsx_html_sink(code, ch) {
+            self.tag(preds, format!("XSS: {}", snippet(code, ch)));
+        }
+
+        // Weak crypto / insecure randomness / unsafe JWT options / SQL built from input
+        if kind == "call_expression" {
+            self.findings.extend(check_crypto_call(code, ch));
+            self.findings.extend(check_sql_call(code, ch));
+        }
+
+        // Message consumers registered in code are entry points too
+        if (kind == "call_expression" || kind == "new_expression") && is_queue_consumer(code, ch) {
+            let label = format!("QUEUE ENTRY ({})", snippet(code, ch));
+            self.tag(preds, with_topics(label, &queue_topics(code, ch)));
+        }
+
+        // Secrets/config reads anywhere
+        if (kind == "member_expression" || kind == "call_expression" || kind == "identifier")
+            && is_secretish(code, ch)
+        {
+            self.tag(preds, format!("SECRET: {}", snippet(code, ch)));
+        }
+
+        // NestJS route handlers via decorators (public entry points)
+        if kind == "decorator" {
+            tag_decorator(self, ch, preds);
+        }
+    }
 }
@@ -0,0 +1,124 @@
+use std::collections::BTreeSet;
+use tree_sitter::Node;
+
+use crate::classify::{sensitive_params, DataClass, Taxonomy, TypeCatalogue};
+use crate::findings::{Finding, Severity};
+use crate::tag::{call_name, snippet};
+use crate::SimpleCfg;
+
+/// One argument value reaching a log call, after looking through templates, objects, spreads and
+/// `JSON.stringify`: `user.ssn`, `req.body`, or `dto` (a whole object).
+#[derive(Debug, Clone)]
+pub struct LoggedValue {
+    pub expr: String,
+    /// The value is an object as a whole (identifier, `x.body`, spread, stringified) rather than a leaf
+    pub whole: bool,
+}
+
+/// A log call and the values it writes
+#[derive(Debug, Clone)]
+pub struct LogSite {
+    pub line: usize,
+    pub call: String,
+    pub values: Vec<LoggedValue>,
+}
+
+// redact(x), maskSsn(x), sanitizeUser(x), hashEmail(x), last4(x) ... make a value safe to log
+const SANITIZERS: &[&str] = &["redact", "mask", "sanitize", "scrub", "anonymize", "obfuscate", "omit", "hash", "last4", "censor"];
+
+fn is_sanitizer(name: &str) -> bool {
+    let n = name.rsplit('.').next().unwrap_or(name).to_lowercase();
+    SANITIZERS.iter().any(|s| n.starts_with(s))
+}
+
+/// Collect the values a LOG-tagged call writes.
+pub fn log_site(code: &str, call: Node) -> LogSite {
+    let mut values = Vec::new();
+    if let Some(args) = call.child_by_field_name("arguments") {
+        for i in 0..args.named_child_count() {
+            collect_values(code, args.named_child(i).unwrap(), false, &mut values);
+        }
+    }
+    LogSite { line: call.start_position().row + 1, call: snippet(code, call), values }
+}
+
+fn collect_values(code: &str, n: Node, whole: bool, out: &mut Vec<LoggedValue>) {
+    let text = || code[n.start_byte()..n.end_byte()].to_string();
+    match n.kind() {
+        "identifier" | "shorthand_property_identifier" => out.push(LoggedValue { expr: text(), whole: true }),
+        "member_expression" | "subscript_expression" => {
+            let expr = text();
+            let whole = whole || expr.ends_with(".body") || expr.ends_with(".query") || expr.ends_with(".params");
+            out.push(LoggedValue { expr, whole });
+        }
+        "spread_element" => {
+            if let Some(inner) = n.named_child(0) { collect_values(code, inner, true, out); }
+        }
+        "call_expression" => {
+            let name = call_name(code, n).unwrap_or_default();
+            if is_sanitizer(&name) { return; }
+            let stringify = name == "JSON.stringify" || name.ends_with("inspect");
+            if let Some(args) = n.child_by_field_name("arguments") {
+                for i in 0..args.named_child_count() {
+                    collect_values(code, args.named_child(i).unwrap(), whole || stringify, out);
+                }
+            }
+        }
+        "pair" => {
+            if let Some(v) = n.child_by_field_name("value") { collect_values(code, v, whole, out); }
+        }
+        "string" | "number" | "true" | "false" | "null" | "undefined" | "comment" => {}
+        _ => {
+            for i in 0..n.named_child_count() { collect_values(code, n.named_child(i).unwrap(), whole, out); }
+        }
+    }
+}
+
+const REQUEST_OBJECTS: &[&str] = &["req", "request", "req.body", "request.body", "body", "ctx.request.body", "payload"];
+
+/// Report log calls that write classified values or whole request/DTO objects.
+pub fn log_leaks(cfg: &SimpleCfg, taxonomy: &Taxonomy, types: &TypeCatalogue) -> Vec<Finding> {
+    let params = sensitive_params(&cfg.params, taxonomy, types);
+    let mut out = Vec::new();
+    for site in &cfg.log_sites {
+        for v in &site.values {
+            let root = v.expr.split(['.', '[', '?']).next().unwrap_or("");
+            let param = cfg.params.iter().find(|p| p.name == root);
+            let mut classes: BTreeSet<DataClass> = taxonomy.classify_text(&v.expr);
+            if v.whole && let Some((_, c)) = params.iter().find(|(p, _)| p == root) {
+                classes.extend(c.iter().copied());
+            }
+            let dto_param = param.is_some_and(|p| {
+                p.name.to_lowercase().ends_with("dto")
+                    || p.ty.as_deref().is_some_and(|t| ["Dto", "Input", "Request"].iter().any(|s| t.trim_end_matches("[]").ends_with(s)))
+            });
+            let request_object = v.whole && (REQUEST_OBJECTS.contains(&v.expr.as_str()) || (dto_param && v.expr == root));
+
+            let (severity, message) = if request_object {
+                (Severity::High, format!("`{}` logs the whole request/DTO object `{}`", site.call, v.expr))
+            } else if classes.contains(&DataClass::Pii) || classes.contains(&DataClass::Credential) {
+                (Severity::High, format!("`{}` logs {} value `{}`", site.call, class_names(&classes), v.expr))
+            } else if classes.contains(&DataClass::Financial) {
+                (Severity::Medium, format!("`{}` logs {} value `{}`", site.call, class_names(&classes), v.expr))
+            } else {
+                continue;
+            };
+            out.push(Finding {
+                rule: "pii-in-log".to_string(),
+                severity,
+                line: site.line,
+                message,
+                hint: Some("log an identifier instead, or wrap the value in a redaction helper (redact/mask)".to_string()),
+            });
+        }
+    }
+    out
+}
+
+fn class_names(classes: &BTreeSet<DataClass>) -> String {
+    classes
+        .iter()
+        .map(|c| match c { DataClass::Pii => "PII", DataClass::Financial => "financial", DataClass::Credential => "credential" })
+        .collect::<Vec<_>>()
+        .join("/")
+}
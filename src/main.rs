@@ -1,4 +1,4 @@
-use casesmith::{handle_run, handle_generate};
+use casesmith::{handle_coverage, handle_export, handle_negative, handle_query, handle_repl, handle_run, handle_generate, handle_tests};
 use clap::{Parser};
 use std::fs;
 use std::path::Path;
@@ -6,20 +6,75 @@ use std::path::Path;
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 enum Cli {
-    /// Run the default command
+    /// Analyze one file, a --code snippet or stdin and print its functions, CFGs and tags
     Run {
+        /// File to analyze; `-` or nothing (without --code) reads stdin
+        file: Option<String>,
+        /// Inline source to analyze instead of a file
         #[arg(short, long)]
-        name: String,
+        code: Option<String>,
+        /// ts, tsx, js or jsx; defaults to the file extension, or JSX if the snippet has closing tags
+        #[arg(short, long)]
+        lang: Option<String>,
+        /// Print JSON instead of a tree
+        #[arg(long)]
+        json: bool,
+        /// Add line numbers and condition inputs to the tree
         #[arg(short, long)]
         verbose: bool,
-        #[arg(short, long, default_value_t = 1)]
-        count: u8,
+    },
+    /// Query functions, CFGs, routes, callers/callees and edges interactively, with Tab completion
+    Repl {
+        /// A .casesmithresults directory, or a source root to analyze; defaults to the current directory
+        path: Option<String>,
+    },
+    /// Run a query over CFGs and edges, e.g. 'NET after SECRET without CRYPTO', or a named one from config
+    Query {
+        /// The query, or the name of one saved under [queries] in config.toml
+        expr: String,
+        /// A .casesmithresults directory, or a source root to analyze; defaults to the current directory
+        path: Option<String>,
+        /// Print the hits as JSON
+        #[arg(long)]
+        json: bool,
     },
     /// Generate something (example command)
     Generate {
         #[arg(short, long)]
         output: Option<String>,
     },
+    /// Write a Jest/Vitest spec skeleton per source file, one test per CFG path
+    Tests {
+        #[arg(short, long)]
+        output: Option<String>,
+        /// jest or vitest; defaults to vitest when package.json lists it
+        #[arg(short, long)]
+        framework: Option<String>,
+    },
+    /// Write requests each HTTP route should refuse: bad tokens, missing roles, invalid DTOs, oversized and injection payloads
+    Negative {
+        #[arg(short, long)]
+        output: Option<String>,
+        /// json (a case list in .casesmithresults) or supertest (e2e specs next to controllers)
+        #[arg(short, long)]
+        format: Option<String>,
+    },
+    /// Export CFG paths and negative route cases as manual test cases for TestRail/Xray or Gherkin
+    Export {
+        #[arg(short, long)]
+        output: Option<String>,
+        /// csv (TestRail/Xray import) or gherkin (.feature files)
+        #[arg(short, long)]
+        format: Option<String>,
+    },
+    /// Map test coverage onto CFG paths; exits 1 if an auth check is never tested
+    Coverage {
+        #[arg(short, long)]
+        output: Option<String>,
+        /// coverage-final.json or lcov.info; defaults to <output>/coverage/
+        #[arg(short, long)]
+        coverage: Option<String>,
+    },
 }
 
 fn main() {
@@ -36,12 +91,32 @@ fn main() {
     };
 
     match cli {
-        Cli::Run { name, verbose, count } => {
-            handle_run(name, verbose, count, &config);
+        Cli::Run { file, code, lang, json, verbose } => {
+            handle_run(file, code, lang, json, verbose, &config);
+        }
+        Cli::Repl { path } => {
+            handle_repl(path, &config);
+        }
+        Cli::Query { expr, path, json } => {
+            handle_query(expr, path, json, &config);
         }
         Cli::Generate { output } => {
             handle_generate(output, &config);
         }
+        Cli::Tests { output, framework } => {
+            handle_tests(output, framework, &config);
+        }
+        Cli::Negative { output, format } => {
+            handle_negative(output, format, &config);
+        }
+        Cli::Export { output, format } => {
+            handle_export(output, format, &config);
+        }
+        Cli::Coverage { output, coverage } => {
+            if handle_coverage(output, coverage, &config) > 0 {
+                std::process::exit(1);
+            }
+        }
     }
 }
 
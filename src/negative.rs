@@ -0,0 +1,413 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::testgen::{quote, Describe, Framework, Spec};
+use crate::validation::DtoRule;
+use crate::SimpleCfg;
+
+// Express's json parser refuses bodies over 100kb by default; a megabyte clears any common limit
+const OVERSIZE: usize = 1024 * 1024 + 1;
+const INVALID_TOKEN: &str = "Bearer invalid.token.value";
+
+/// A request an HTTP entrypoint should refuse, and the statuses that count as refusing it
+#[derive(Debug, Clone, Serialize)]
+pub struct NegativeCase {
+    pub title: String,
+    /// auth, role, validation, size, injection
+    pub category: &'static str,
+    pub method: String,
+    /// Route path with its parameters filled in
+    pub path: String,
+    /// none, invalid, valid (a token with the route's roles), unprivileged (a token with no roles)
+    pub auth: &'static str,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub query: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    /// A body field padded to `bytes` characters; kept out of `body` so the case list stays readable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oversize: Option<Oversize>,
+    pub expect: Vec<u16>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Oversize {
+    pub field: String,
+    pub bytes: usize,
+}
+
+/// Negative cases for one route handler
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteCases {
+    pub file: String,
+    pub func: String,
+    pub method: String,
+    pub path: String,
+    pub guarded: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dto: Option<String>,
+    pub cases: Vec<NegativeCase>,
+}
+
+impl RouteCases {
+    /// `POST /loans/:id/approve`
+    pub fn route(&self) -> String {
+        format!("{} {}", self.method, self.path)
+    }
+}
+
+// "@Min(1000)" → ("Min", "1000")
+fn rule_parts(rule: &str) -> (&str, &str) {
+    let r = rule.trim_start_matches('@');
+    match r.split_once('(') {
+        Some((name, args)) => (name, args.rsplit_once(')').map(|(a, _)| a).unwrap_or(args).trim()),
+        None => (r, ""),
+    }
+}
+
+fn num_arg(args: &str, i: usize) -> Option<f64> {
+    args.split(',').nth(i).and_then(|a| a.trim().parse().ok())
+}
+
+fn has(rules: &[String], name: &str) -> bool {
+    rules.iter().any(|r| rule_parts(r).0 == name)
+}
+
+fn number(n: f64, int: bool) -> Value {
+    if int || n.fract() == 0.0 { json!(n as i64) } else { json!(n) }
+}
+
+// A value that passes every rule on a field
+fn valid_value(rules: &[String]) -> Value {
+    let int = has(rules, "IsInt");
+    let min = rules.iter().find_map(|r| match rule_parts(r) { ("Min", a) => num_arg(a, 0), _ => None });
+    let max = rules.iter().find_map(|r| match rule_parts(r) { ("Max", a) => num_arg(a, 0), _ => None });
+    if int || has(rules, "IsNumber") || has(rules, "IsPositive") || min.is_some() || max.is_some() {
+        let n = min.or(max).unwrap_or(1.0);
+        return number(if has(rules, "IsPositive") && n <= 0.0 { 1.0 } else { n }, int);
+    }
+    for r in rules {
+        let (name, args) = rule_parts(r);
+        let v = match name {
+            "IsEmail" => json!("user@example.com"),
+            "IsBoolean" => json!(true),
+            "IsUUID" => json!("00000000-0000-4000-8000-000000000000"),
+            "IsDateString" | "IsISO8601" | "IsDate" | "MinDate" | "MaxDate" => json!("2024-01-01T00:00:00.000Z"),
+            "IsUrl" => json!("https://example.com"),
+            "IsArray" | "ArrayMinSize" | "ArrayNotEmpty" => json!([]),
+            "IsObject" | "ValidateNested" => json!({}),
+            "IsIn" => args.trim_start_matches('[').split([',', ']']).next()
+                .map(|v| json!(v.trim().trim_matches(['\'', '"'])))
+                .unwrap_or(json!("text")),
+            "MinLength" | "Length" => json!("x".repeat(num_arg(args, 0).unwrap_or(1.0).max(1.0) as usize)),
+            _ => continue,
+        };
+        return v;
+    }
+    json!("text")
+}
+
+// Values that each break one rule: (what's wrong, value)
+fn violations(rules: &[String]) -> Vec<(String, Value)> {
+    let int = has(rules, "IsInt");
+    let mut out = Vec::new();
+    for r in rules {
+        let (name, args) = rule_parts(r);
+        let v = match name {
+            "Min" => num_arg(args, 0).map(|n| number(n - if int || n.fract() == 0.0 { 1.0 } else { 0.01 }, int)),
+            "Max" => num_arg(args, 0).map(|n| number(n + if int || n.fract() == 0.0 { 1.0 } else { 0.01 }, int)),
+            "IsPositive" => Some(json!(0)),
+            "IsNegative" => Some(json!(0)),
+            "IsInt" => Some(json!(1.5)),
+            "IsNumber" => Some(json!("not-a-number")),
+            "IsString" => Some(json!(123)),
+            "IsBoolean" => Some(json!("yes")),
+            "IsEmail" => Some(json!("not-an-email")),
+            "IsUUID" => Some(json!("not-a-uuid")),
+            "IsDateString" | "IsISO8601" | "IsDate" => Some(json!("not-a-date")),
+            "IsUrl" => Some(json!("not a url")),
+            "IsEnum" | "IsIn" => Some(json!("__invalid__")),
+            "IsArray" => Some(json!("not-an-array")),
+            "IsObject" => Some(json!("not-an-object")),
+            "IsNotEmpty" | "ArrayNotEmpty" => Some(if has(rules, "IsArray") { json!([]) } else { json!("") }),
+            "Matches" => Some(json!("!")),
+            "MinLength" => num_arg(args, 0).filter(|n| *n >= 1.0).map(|n| json!("x".repeat(n as usize - 1))),
+            "MaxLength" => num_arg(args, 0).map(|n| json!("x".repeat(n as usize + 1))),
+            "Length" => {
+                if let Some(n) = num_arg(args, 0).filter(|n| *n >= 1.0) {
+                    out.push((format!("shorter than {}", r), json!("x".repeat(n as usize - 1))));
+                }
+                num_arg(args, 1).map(|n| json!("x".repeat(n as usize + 1)))
+            }
+            _ => None,
+        };
+        if let Some(v) = v { out.push((format!("violating {}", r), v)); }
+    }
+    out
+}
+
+// Percent-encode a payload for a URL path segment or query value
+fn encode(s: &str) -> String {
+    s.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
+
+// `/loans/:id/approve` with `id` set to `value` and any other parameter to 1
+fn fill_path(path: &str, key: &str, value: &str) -> String {
+    path.split('/')
+        .map(|seg| match seg.strip_prefix(':') {
+            Some(k) if k.trim_end_matches('?') == key => encode(value),
+            Some(_) => "1".to_string(),
+            None => seg.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Role names from `@Roles('admin', Role.Officer)` labels
+fn roles(cfg: &SimpleCfg) -> Vec<String> {
+    let mut out = Vec::new();
+    for label in cfg.nodes.iter().filter_map(|n| n.strip_prefix("AUTHZ: @")) {
+        let Some((name, args)) = label.split_once('(') else { continue };
+        if !["roles", "hasrole", "authorize"].contains(&name.to_lowercase().as_str()) { continue; }
+        for a in args.trim_end_matches(')').split(',').map(str::trim).filter(|a| !a.is_empty()) {
+            let r = a.trim_matches(['\'', '"', '`']).to_string();
+            if !out.contains(&r) { out.push(r); }
+        }
+    }
+    out
+}
+
+// Whether `name` appears as a whole word in a sink label (`id`, `dto.amount`); with `whole`, only
+// where the value itself is passed rather than one of its members (`data: dto`, not `dto.amount`)
+fn mentions(label: &str, name: &str, whole: bool) -> bool {
+    label.match_indices(name).any(|(i, _)| {
+        let before = label[..i].chars().next_back();
+        let after = label[i + name.len()..].chars().next();
+        let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$');
+        let member = whole && matches!(after, Some('.' | '['));
+        !word(before) && !word(after) && !member
+    })
+}
+
+// Payloads for each kind of sink an input reaches
+fn payloads(sink: &str) -> &'static [(&'static str, &'static str)] {
+    match sink {
+        "DB:" => &[("SQL injection", "' OR '1'='1")],
+        "EXEC:" => &[("command injection", "; cat /etc/passwd")],
+        "EVAL:" => &[("code injection", "require('child_process').execSync('id')")],
+        "STORAGE:" => &[("path traversal", "../../../etc/passwd")],
+        _ => &[],
+    }
+}
+
+const SINKS: &[&str] = &["DB:", "EXEC:", "EVAL:", "STORAGE:"];
+
+/// Negative cases for a Nest route handler: missing and invalid tokens on guarded routes, a user
+/// without the route's roles, each DTO rule broken in turn, an oversized body, and injection
+/// payloads for inputs that reach a DB/exec/eval/storage call. `dtos` maps DTO class names to their
+/// rules. None for functions that aren't routes.
+pub fn negative_cases(file: &str, func: &str, cfg: &SimpleCfg, dtos: &HashMap<String, Vec<DtoRule>>) -> Option<RouteCases> {
+    let route = cfg.route.as_ref()?;
+    let method = route.method.clone();
+    let path = fill_path(&route.path, "", "");
+    let guarded = cfg.nodes.iter().any(|n| n.starts_with("AUTH: "));
+    let roles = roles(cfg);
+    let authed = if guarded || !roles.is_empty() { "valid" } else { "none" };
+
+    let body_param = cfg.params.iter().find(|p| p.source.as_deref() == Some("body") && p.key.is_none());
+    let dto = body_param.and_then(|p| p.ty.as_deref()).map(|t| t.trim_end_matches("[]").to_string());
+    let rules: &[DtoRule] = dto.as_ref().and_then(|d| dtos.get(d)).map(Vec::as_slice).unwrap_or_default();
+    let mut valid = Map::new();
+    for r in rules.iter().filter(|r| !r.optional) {
+        valid.insert(r.field.clone(), valid_value(&r.rules));
+    }
+    let sends_body = !["GET", "HEAD", "DELETE", "OPTIONS"].contains(&method.as_str());
+    let baseline = (sends_body && body_param.is_some()).then(|| Value::Object(valid.clone()));
+
+    let case = |title: String, category, auth, body: Option<Value>, expect: Vec<u16>| NegativeCase {
+        title, category, method: method.clone(), path: path.clone(), auth, query: BTreeMap::new(), body, oversize: None, expect,
+    };
+    let mut cases = Vec::new();
+    if guarded {
+        cases.push(case("rejects a request without a token".to_string(), "auth", "none", baseline.clone(), vec![401]));
+        cases.push(case("rejects an invalid token".to_string(), "auth", "invalid", baseline.clone(), vec![401]));
+    }
+    if !roles.is_empty() {
+        cases.push(case(format!("rejects a user without {}", roles.join(" or ")), "role", "unprivileged", baseline.clone(), vec![403]));
+    }
+
+    if sends_body {
+        for r in rules {
+            for (why, v) in violations(&r.rules) {
+                let mut body = valid.clone();
+                body.insert(r.field.clone(), v);
+                cases.push(case(format!("rejects {} {}", r.field, why), "validation", authed, Some(Value::Object(body)), vec![400]));
+            }
+            if !r.optional {
+                let mut body = valid.clone();
+                body.remove(&r.field);
+                cases.push(case(format!("rejects a body without {}", r.field), "validation", authed, Some(Value::Object(body)), vec![400]));
+            }
+        }
+        let field = rules.iter().find(|r| valid.get(&r.field).is_some_and(Value::is_string))
+            .map(|r| r.field.clone())
+            .unwrap_or_else(|| "data".to_string());
+        let mut big = case("rejects an oversized body".to_string(), "size", authed, Some(Value::Object(valid.clone())), vec![413]);
+        big.oversize = Some(Oversize { field, bytes: OVERSIZE });
+        cases.push(big);
+    }
+
+    // inputs that reach a sink: route/query parameters by their request name, DTO fields by name
+    let sink_labels: Vec<(&str, &String)> = cfg.nodes.iter()
+        .filter_map(|n| SINKS.iter().find(|s| n.starts_with(**s)).map(|s| (*s, n)))
+        .collect();
+    let reaches = |name: &str, whole: bool| -> Vec<&str> {
+        let mut kinds: Vec<&str> = sink_labels.iter().filter(|(_, l)| mentions(l, name, whole)).map(|(k, _)| *k).collect();
+        kinds.sort_unstable();
+        kinds.dedup();
+        kinds
+    };
+    let injection = vec![400, 403, 404, 422];
+    for p in &cfg.params {
+        let Some(source) = p.source.as_deref().filter(|s| ["param", "query"].contains(s)) else { continue };
+        let key = p.key.as_deref().unwrap_or(&p.name);
+        for kind in reaches(&p.name, false) {
+            for (what, payload) in payloads(kind) {
+                let mut c = case(format!("rejects {} in {} {}", what, source, key), "injection", authed, baseline.clone(), injection.clone());
+                if source == "param" {
+                    c.path = fill_path(&route.path, key, payload);
+                } else {
+                    c.query.insert(key.to_string(), payload.to_string());
+                }
+                cases.push(c);
+            }
+        }
+    }
+    if sends_body && let Some(bp) = body_param {
+        // a whole DTO handed to the sink carries every field into it
+        let whole = reaches(&bp.name, true);
+        for r in rules {
+            let mut kinds = reaches(&r.field, false);
+            kinds.extend(whole.iter().filter(|k| !kinds.contains(k)).copied().collect::<Vec<_>>());
+            for kind in kinds {
+                let mut attempts: Vec<(&str, Value)> = payloads(kind).iter().map(|(w, p)| (*w, json!(p))).collect();
+                if kind == "DB:" { attempts.push(("NoSQL operator injection", json!({ "$ne": null }))); }
+                for (what, payload) in attempts {
+                    let mut body = valid.clone();
+                    body.insert(r.field.clone(), payload);
+                    cases.push(case(format!("rejects {} in {}", what, r.field), "injection", authed, Some(Value::Object(body)), injection.clone()));
+                }
+            }
+        }
+    }
+
+    Some(RouteCases {
+        file: file.to_string(),
+        func: func.to_string(),
+        method: route.method.clone(),
+        path: route.path.clone(),
+        guarded,
+        roles,
+        dto: dto.filter(|_| !rules.is_empty()),
+        cases,
+    })
+}
+
+/// The module every generated e2e spec imports `createApp` and `tokenFor` from
+pub const SETUP_FILE: &str = "casesmith.e2e-setup.ts";
+
+/// Written once next to the specs; the project fills it in
+pub const SETUP: &str = "import { INestApplication } from '@nestjs/common';
+
+// Generated by `casesmith negative`. Fill these in once: every *.casesmith.e2e-spec.ts uses them.
+
+/** Boot the app under test, e.g. Test.createTestingModule({ imports: [AppModule] }), then app.init() */
+export async function createApp(): Promise<INestApplication> {
+  throw new Error('casesmith: implement createApp in casesmith.e2e-setup.ts');
+}
+
+/** A valid access token for a user holding `roles` ([] = signed in, but with no roles) */
+export async function tokenFor(roles: string[]): Promise<string> {
+  throw new Error(`casesmith: implement tokenFor in casesmith.e2e-setup.ts (roles: ${roles})`);
+}
+";
+
+fn js(v: &Value) -> String {
+    serde_json::to_string(v).unwrap_or_default()
+}
+
+fn render_case(c: &NegativeCase, roles: &[String]) -> String {
+    let mut out = vec![format!("  it({}, async () => {{", quote(&c.title))];
+    let mut req = vec![format!("    const res = await request(app.getHttpServer()).{}({})", c.method.to_lowercase(), quote(&c.path))];
+    match c.auth {
+        "invalid" => req.push(format!("      .set('Authorization', '{}')", INVALID_TOKEN)),
+        "valid" => req.push(format!("      .set('Authorization', `Bearer ${{await tokenFor({})}}`)", js(&json!(roles)))),
+        "unprivileged" => req.push("      .set('Authorization', `Bearer ${await tokenFor([])}`)".to_string()),
+        _ => {}
+    }
+    if !c.query.is_empty() {
+        req.push(format!("      .query({})", js(&json!(c.query))));
+    }
+    match (&c.body, &c.oversize) {
+        (Some(Value::Object(b)), Some(o)) => {
+            let rest = js(&Value::Object(b.clone()));
+            let spread = if b.is_empty() { String::new() } else { format!("...{}, ", rest) };
+            req.push(format!("      .send({{ {}{}: 'x'.repeat({}) }})", spread, o.field, o.bytes));
+        }
+        (Some(b), _) => req.push(format!("      .send({})", js(b))),
+        _ => {}
+    }
+    let last = req.len() - 1;
+    req[last].push(';');
+    out.extend(req);
+    match c.expect.as_slice() {
+        [code] => out.push(format!("    expect(res.status).toBe({});", code)),
+        codes => out.push(format!("    expect({}).toContain(res.status);", js(&json!(codes)))),
+    }
+    out.push("  });".to_string());
+    out.join("\n")
+}
+
+/// A Supertest e2e spec for the routes of one controller file. `setup` is the import path of
+/// casesmith.e2e-setup from the spec (`../casesmith.e2e-setup`).
+pub fn render_supertest(file_name: &str, setup: &str, routes: &[RouteCases], fw: Framework) -> Spec {
+    let mut header = vec![
+        "// casesmith:begin header".to_string(),
+        format!("// Generated by `casesmith negative` from {}: requests each route should refuse. A rerun only", file_name),
+        "// refreshes the casesmith:begin/end blocks and adds cases that are new.".to_string(),
+    ];
+    if fw == Framework::Vitest {
+        header.push("import { afterAll, beforeAll, describe, expect, it } from 'vitest';".to_string());
+    }
+    header.push("import request from 'supertest';".to_string());
+    header.push("import { INestApplication } from '@nestjs/common';".to_string());
+    header.push(format!("import {{ createApp, tokenFor }} from '{}';", setup));
+    header.push("// casesmith:end header".to_string());
+
+    let describes = routes.iter().filter(|r| !r.cases.is_empty()).map(|r| {
+        let key = r.route();
+        let setup = [
+            format!("  // casesmith:begin setup {}", key),
+            format!("  // {}", r.func),
+            "  let app: INestApplication;".to_string(),
+            String::new(),
+            "  beforeAll(async () => {".to_string(),
+            "    app = await createApp();".to_string(),
+            "  });".to_string(),
+            String::new(),
+            "  afterAll(async () => {".to_string(),
+            "    await app.close();".to_string(),
+            "  });".to_string(),
+            format!("  // casesmith:end setup {}", key),
+        ].join("\n");
+        let tests = r.cases.iter().map(|c| (c.title.clone(), render_case(c, &r.roles))).collect();
+        Describe { func: key, setup, tests }
+    }).collect();
+    Spec { header: header.join("\n"), describes, fixtures: BTreeSet::new() }
+}
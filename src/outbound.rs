@@ -0,0 +1,272 @@
+use serde::Serialize;
+use tree_sitter::Node;
+
+use crate::embedded::resolve_const;
+use crate::tag::call_name;
+
+// Header names (lowercase) that carry credentials; any header mentioning token/secret/api key/signature counts too
+const CREDENTIAL_HEADERS: &[&str] = &["authorization", "proxy-authorization", "cookie", "x-api-key", "api-key", "apikey"];
+
+/// An outbound HTTP request made by a NET node: who makes it, how, and where it goes.
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpCall {
+    /// Index of the CFG node that makes the request
+    pub node: usize,
+    /// `axios`, `fetch`, `got`, `this.httpService` or an instance such as `this.experian`
+    pub client: String,
+    pub method: String,
+    /// URL expression as written (`/v2/credit-report`, `` `${BASE}/charges` ``)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Base URL expression of the client instance, if the request goes through one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Host, when it is a literal, a same-file const or the fallback of an env var
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// Env var / config key the destination comes from (`EXPERIAN_BASE_URL`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub credential_headers: Vec<String>,
+}
+
+impl HttpCall {
+    /// Short suffix for the CFG label: "[POST api.experian.com]", "[GET $PLAID_URL]"
+    pub fn label_suffix(&self) -> String {
+        match (&self.host, &self.env) {
+            (Some(h), _) => format!("[{} {}]", self.method, h),
+            (None, Some(e)) => format!("[{} ${}]", self.method, e),
+            (None, None) => format!("[{}]", self.method),
+        }
+    }
+}
+
+/// An HTTP client created with defaults: `axios.create({ baseURL, headers })`, `got.extend({ prefixUrl })`
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    /// How calls refer to it: `api`, `this.client`
+    pub name: String,
+    pub base_url: Option<String>,
+    pub dest: Destination,
+    pub credential_headers: Vec<String>,
+}
+
+/// Where a URL expression points, as far as it can be known statically
+#[derive(Debug, Clone, Default)]
+pub struct Destination {
+    pub host: Option<String>,
+    pub env: Option<String>,
+}
+
+/// Every client instance created in a file, keyed by the name calls use for it.
+pub fn http_clients(code: &str, root: Node) -> Vec<HttpClient> {
+    let mut out = Vec::new();
+    let mut stack = vec![root];
+    while let Some(n) = stack.pop() {
+        for i in 0..n.named_child_count() { stack.push(n.named_child(i).unwrap()); }
+        if n.kind() != "call_expression" { continue; }
+        let name = call_name(code, n).unwrap_or_default().to_lowercase();
+        if !matches!(name.as_str(), "axios.create" | "got.extend" | "ky.create" | "ky.extend") { continue; }
+        let Some(parent) = n.parent() else { continue };
+        let binding = match parent.kind() {
+            "variable_declarator" => parent.child_by_field_name("name").map(|x| text(code, x)),
+            "assignment_expression" => parent.child_by_field_name("left").map(|x| text(code, x)),
+            "public_field_definition" => parent.child_by_field_name("name").map(|x| format!("this.{}", text(code, x))),
+            _ => None,
+        };
+        let Some(binding) = binding else { continue };
+        let opts = n.child_by_field_name("arguments").and_then(|a| a.named_child(0)).filter(|a| a.kind() == "object");
+        let base = opts.and_then(|o| prop(code, o, &["baseURL", "baseUrl", "prefixUrl", "prefixURL"]));
+        out.push(HttpClient {
+            name: binding,
+            base_url: base.map(|b| text(code, b)),
+            dest: base.map(|b| destination(code, b)).unwrap_or_default(),
+            credential_headers: opts.map(|o| credential_headers(code, o)).unwrap_or_default(),
+        });
+    }
+    out
+}
+
+/// Describe an outbound request made by `call`, if it is one (axios, fetch, got, Nest HttpService or an instance).
+pub fn outbound_call(code: &str, call: Node, clients: &[HttpClient]) -> Option<HttpCall> {
+    let name = call_name(code, call)?;
+    let lower = name.to_lowercase();
+    let instance = clients.iter().find(|c| name == c.name || name.strip_prefix(&c.name).is_some_and(|r| r.starts_with('.')));
+    let (client, op) = if let Some(c) = instance {
+        (c.name.clone(), name[c.name.len()..].trim_start_matches('.').to_lowercase())
+    } else if lower == "axios" || lower == "fetch" || lower == "got" || lower == "ky" {
+        (name.clone(), String::new())
+    } else if lower.starts_with("axios.") || lower.starts_with("got.") || lower.starts_with("ky.") || lower.contains("httpservice.") {
+        let (recv, op) = name.rsplit_once('.')?;
+        (recv.to_string(), op.to_lowercase())
+    } else {
+        return None;
+    };
+    const NO_BODY: &[&str] = &["get", "delete", "head", "options"];
+    const WITH_BODY: &[&str] = &["post", "put", "patch"];
+    if !op.is_empty() && op != "request" && !NO_BODY.contains(&op.as_str()) && !WITH_BODY.contains(&op.as_str()) {
+        return None;
+    }
+    let args = call.child_by_field_name("arguments")?;
+    let arg = |i: usize| args.named_child(i);
+    let fetch_like = lower == "fetch" || client.to_lowercase().starts_with("got") || client.to_lowercase().starts_with("ky");
+
+    // axios(config) / axios.request(config) carry the URL and method inside the config object
+    let config_style = (op.is_empty() || op == "request") && arg(0).is_some_and(|a| a.kind() == "object");
+    let (url, opts) = if config_style {
+        (arg(0).and_then(|o| prop(code, o, &["url"])), arg(0))
+    } else if fetch_like || NO_BODY.contains(&op.as_str()) || op.is_empty() {
+        (arg(0), arg(1))
+    } else {
+        (arg(0), arg(2))
+    };
+    let opts = opts.filter(|o| o.kind() == "object");
+    let method = if op.is_empty() || op == "request" {
+        opts.and_then(|o| prop(code, o, &["method"]))
+            .map(|m| text(code, m).trim_matches(|c| c == '\'' || c == '"' || c == '`').to_uppercase())
+            .unwrap_or_else(|| "GET".to_string())
+    } else {
+        op.to_uppercase()
+    };
+
+    let mut dest = url.map(|u| destination(code, u)).unwrap_or_default();
+    let mut base_url = None;
+    let mut creds = opts.map(|o| credential_headers(code, o)).unwrap_or_default();
+    if let Some(c) = instance {
+        base_url = c.base_url.clone();
+        if dest.host.is_none() && dest.env.is_none() { dest = c.dest.clone(); }
+        for h in &c.credential_headers {
+            if !creds.contains(h) { creds.push(h.clone()); }
+        }
+    }
+    Some(HttpCall {
+        node: 0,
+        client,
+        method,
+        url: url.map(|u| text(code, u)),
+        base_url,
+        host: dest.host,
+        env: dest.env,
+        credential_headers: creds,
+    })
+}
+
+/// Resolve a URL expression: literals, templates, same-file consts, `process.env.X` (with `|| 'fallback'`)
+/// and `configService.get('X')`.
+pub fn destination(code: &str, n: Node) -> Destination {
+    resolve_destination(code, n, &mut vec![])
+}
+
+// `destination`, with the consts already followed (see `resolve_const`)
+fn resolve_destination(code: &str, n: Node, seen: &mut Vec<usize>) -> Destination {
+    match n.kind() {
+        "string" => Destination { host: host_of(unquote(&text(code, n))), env: None },
+        "template_string" => {
+            // the host is only known if it's spelled out before the first substitution
+            let first = n.named_child(0).filter(|c| c.kind() == "template_substitution");
+            if let Some(sub) = first.filter(|s| s.start_byte() == n.start_byte() + 1) {
+                return sub.named_child(0).map(|e| resolve_destination(code, e, seen)).unwrap_or_default();
+            }
+            let end = (0..n.named_child_count())
+                .filter_map(|i| n.named_child(i))
+                .find(|c| c.kind() == "template_substitution")
+                .map(|c| c.start_byte())
+                .unwrap_or(n.end_byte() - 1);
+            let prefix = &code[n.start_byte() + 1..end];
+            let complete = prefix.split_once("://").is_some_and(|(_, rest)| rest.contains(['/', '?', ':']) || end == n.end_byte() - 1);
+            Destination { host: if complete { host_of(prefix) } else { None }, env: None }
+        }
+        "identifier" => resolve_const(code, n, seen).map(|v| resolve_destination(code, v, seen)).unwrap_or_default(),
+        "parenthesized_expression" => n.named_child(0).map(|e| resolve_destination(code, e, seen)).unwrap_or_default(),
+        "binary_expression" => {
+            let (Some(l), Some(r)) = (n.child_by_field_name("left"), n.child_by_field_name("right")) else {
+                return Destination::default();
+            };
+            let op = n.child_by_field_name("operator").map(|o| text(code, o)).unwrap_or_default();
+            let left = resolve_destination(code, l, seen);
+            if op == "||" || op == "??" {
+                let right = resolve_destination(code, r, seen);
+                return Destination { host: left.host.or(right.host), env: left.env.or(right.env) };
+            }
+            // BASE + '/path'
+            if op == "+" { left } else { Destination::default() }
+        }
+        "member_expression" | "subscript_expression" => Destination { host: None, env: env_name(code, n) },
+        "call_expression" => {
+            // configService.get('EXPERIAN_URL') / this.config.get<string>('X')
+            let name = call_name(code, n).unwrap_or_default().to_lowercase();
+            let key = n.child_by_field_name("arguments").and_then(|a| a.named_child(0)).filter(|a| a.kind() == "string");
+            match key {
+                Some(k) if name.ends_with(".get") && name.contains("config") => {
+                    Destination { host: None, env: Some(unquote(&text(code, k)).to_string()) }
+                }
+                _ => Destination::default(),
+            }
+        }
+        _ => Destination::default(),
+    }
+}
+
+/// `process.env.X` / `process.env['X']` → "X"
+pub fn env_name(code: &str, n: Node) -> Option<String> {
+    let obj = n.child_by_field_name("object")?;
+    if text(code, obj) != "process.env" { return None; }
+    let key = n.child_by_field_name("property").or_else(|| n.child_by_field_name("index"))?;
+    match key.kind() {
+        "property_identifier" => Some(text(code, key)),
+        "string" => Some(unquote(&text(code, key)).to_string()),
+        _ => None,
+    }
+}
+
+/// "https://user@api.experian.com:8443/v2?x" → "api.experian.com"
+pub fn host_of(url: &str) -> Option<String> {
+    let (scheme, rest) = url.trim().split_once("://")?;
+    if !matches!(scheme.to_lowercase().as_str(), "http" | "https" | "ws" | "wss") { return None; }
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?.to_lowercase();
+    if host.is_empty() || host.contains(['$', '{', ' ']) { None } else { Some(host) }
+}
+
+// Credential-bearing header names in a request config or instance defaults, plus `auth` (basic auth)
+fn credential_headers(code: &str, opts: Node) -> Vec<String> {
+    let mut out = Vec::new();
+    if let Some(headers) = prop(code, opts, &["headers"]).filter(|h| h.kind() == "object") {
+        for i in 0..headers.named_child_count() {
+            let p = headers.named_child(i).unwrap();
+            let key = match p.kind() {
+                "pair" => p.child_by_field_name("key").map(|k| unquote(&text(code, k)).to_string()),
+                "shorthand_property_identifier" => Some(text(code, p)),
+                _ => None,
+            };
+            let Some(key) = key else { continue };
+            let k = key.to_lowercase();
+            if CREDENTIAL_HEADERS.contains(&k.as_str()) || ["token", "secret", "api-key", "apikey", "access-key", "signature"].iter().any(|t| k.contains(t)) {
+                out.push(key);
+            }
+        }
+    }
+    if prop(code, opts, &["auth"]).is_some() { out.push("auth".to_string()); }
+    out
+}
+
+// Value of the first property named one of `keys` in an object literal
+fn prop<'t>(code: &str, obj: Node<'t>, keys: &[&str]) -> Option<Node<'t>> {
+    (0..obj.named_child_count()).filter_map(|i| obj.named_child(i)).find_map(|p| match p.kind() {
+        "pair" => {
+            let k = p.child_by_field_name("key").map(|k| text(code, k))?;
+            keys.contains(&unquote(&k)).then(|| p.child_by_field_name("value")).flatten()
+        }
+        "shorthand_property_identifier" if keys.contains(&text(code, p).as_str()) => Some(p),
+        _ => None,
+    })
+}
+
+fn text(code: &str, n: Node) -> String {
+    code[n.start_byte()..n.end_byte()].split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn unquote(s: &str) -> &str {
+    s.trim_matches(|c| c == '\'' || c == '"' || c == '`')
+}
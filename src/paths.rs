@@ -0,0 +1,384 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::conditions::items;
+use crate::config::PathsConfig;
+use crate::SimpleCfg;
+
+/// One branch taken along a path: the If/Loop node, its condition text and which way it went
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Decision {
+    pub node: usize,
+    pub condition: String,
+    pub taken: bool,
+    /// Values that send the condition this way, from its parsed boundaries
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<Input>,
+}
+
+/// Suggested values for one input of a decision: boundary first, then a representative
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Input {
+    pub subject: String,
+    pub kind: &'static str,
+    pub values: Vec<String>,
+}
+
+/// How many times a loop body runs on a path
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LoopCount {
+    pub node: usize,
+    pub iterations: usize,
+}
+
+/// A walk through the CFG, as node indices plus the decisions that pick it out
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CfgPath {
+    pub nodes: Vec<usize>,
+    pub decisions: Vec<Decision>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub loops: Vec<LoopCount>,
+}
+
+impl CfgPath {
+    /// The branch outcome of each step (`nodes[i]` → `nodes[i + 1]`), None where the step isn't a decision
+    pub fn outcomes(&self) -> Vec<Option<bool>> {
+        let mut ds = self.decisions.iter().peekable();
+        self.nodes.windows(2).map(|w| ds.next_if(|d| d.node == w[0]).map(|d| d.taken)).collect()
+    }
+}
+
+/// Test-planning paths for one function
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionPaths {
+    /// McCabe cyclomatic complexity (E - N + 2 over the reachable graph); the size of a basis set
+    pub complexity: usize,
+    /// Entry → Exit paths, every loop taken 0, 1 or `loop_unroll` times
+    pub simple: Vec<CfgPath>,
+    /// Maximal simple paths (no repeated node except a closing cycle); covering them all is prime path coverage
+    pub prime: Vec<CfgPath>,
+    /// Linearly independent Entry → Exit paths: each one takes a branch none of the earlier ones did
+    pub basis: Vec<CfgPath>,
+    /// A bound in `[paths]` cut enumeration short
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+// One step of a walk: the next node, and the outcome if the edge leaves a decision
+type Arc = (usize, Option<bool>);
+
+struct Graph<'a> {
+    cfg: &'a SimpleCfg,
+    succ: Vec<Vec<Arc>>,
+}
+
+impl<'a> Graph<'a> {
+    // An edge that is both the true and the false successor (`if (x) {}`) becomes two arcs
+    fn new(cfg: &'a SimpleCfg) -> Self {
+        let mut succ = vec![Vec::new(); cfg.nodes.len()];
+        for &(s, d) in &cfg.edges {
+            if s >= succ.len() || d >= succ.len() { continue; }
+            let outcomes: BTreeSet<bool> = cfg.branches.iter().filter(|b| (b.0, b.1) == (s, d)).map(|b| b.2).collect();
+            if outcomes.is_empty() {
+                succ[s].push((d, None));
+            }
+            for o in outcomes {
+                succ[s].push((d, Some(o)));
+            }
+        }
+        Graph { cfg, succ }
+    }
+
+    fn reachable(&self) -> Vec<usize> {
+        let mut seen = vec![false; self.succ.len()];
+        let mut stack = vec![0];
+        while let Some(n) = stack.pop() {
+            if n >= seen.len() || seen[n] { continue; }
+            seen[n] = true;
+            stack.extend(self.succ[n].iter().map(|a| a.0));
+        }
+        (0..seen.len()).filter(|&n| seen[n]).collect()
+    }
+
+    // Fewest arcs from each node to Exit (usize::MAX where Exit can't be reached)
+    fn to_exit(&self) -> Vec<usize> {
+        let mut pred = vec![Vec::new(); self.succ.len()];
+        for (s, arcs) in self.succ.iter().enumerate() {
+            for a in arcs { pred[a.0].push(s); }
+        }
+        let mut dist = vec![usize::MAX; self.succ.len()];
+        if dist.len() < 2 { return dist; }
+        dist[1] = 0;
+        let mut queue = VecDeque::from([1]);
+        while let Some(d) = queue.pop_front() {
+            for &s in &pred[d] {
+                if dist[s] == usize::MAX {
+                    dist[s] = dist[d] + 1;
+                    queue.push_back(s);
+                }
+            }
+        }
+        dist
+    }
+
+    fn is_loop(&self, n: usize) -> bool {
+        self.cfg.nodes[n].starts_with("Loop:")
+    }
+
+    fn path(&self, nodes: Vec<usize>, outs: &[Option<bool>]) -> CfgPath {
+        let mut decisions = Vec::new();
+        let mut loops: BTreeMap<usize, usize> = BTreeMap::new();
+        for (i, o) in outs.iter().enumerate() {
+            let n = nodes[i];
+            if self.is_loop(n) {
+                let c = loops.entry(n).or_default();
+                if *o == Some(true) { *c += 1; }
+            }
+            if let Some(taken) = *o {
+                let parsed = self.cfg.conditions.iter().find(|c| c.node == n);
+                let inputs = parsed.map(|c| c.inputs.iter().map(|b| Input {
+                    subject: b.subject.clone(),
+                    kind: b.kind,
+                    values: if taken { b.when_true.clone() } else { b.when_false.clone() },
+                }).collect()).unwrap_or_default();
+                let condition = parsed.map(|c| c.text.clone()).unwrap_or_else(|| condition(&self.cfg.nodes[n]));
+                decisions.push(Decision { node: n, condition, taken, inputs });
+            }
+        }
+        // a loop over items runs as often as there are items
+        for d in decisions.iter_mut() {
+            let Some(&count) = loops.get(&d.node) else { continue };
+            for i in d.inputs.iter_mut().filter(|i| i.kind == "iterable") {
+                i.values = vec![items(count)];
+            }
+        }
+        CfgPath { nodes, decisions, loops: loops.into_iter().map(|(node, iterations)| LoopCount { node, iterations }).collect() }
+    }
+}
+
+// "If: if (amount > limit) {" → "amount > limit", for decisions without a parsed condition; labels
+// hold only the first line, so an unbalanced condition keeps whatever is there
+fn condition(label: &str) -> String {
+    let text = label.split_once(": ").map(|(_, t)| t).unwrap_or(label).trim();
+    let Some(open) = text.find('(').filter(|_| ["if", "for", "while"].iter().any(|k| text.starts_with(k))) else {
+        return text.trim_end_matches('{').trim().to_string();
+    };
+    let mut depth = 0;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 { return text[open + 1..open + i].trim().to_string(); }
+            }
+            _ => {}
+        }
+    }
+    text[open + 1..].trim_end_matches('{').trim().to_string()
+}
+
+/// Enumerate simple, prime and basis paths for one function's CFG within the configured bounds.
+pub fn enumerate_paths(cfg: &SimpleCfg, opts: &PathsConfig) -> FunctionPaths {
+    let g = Graph::new(cfg);
+    let reachable = g.reachable();
+    let arcs: usize = reachable.iter().map(|&n| g.succ[n].len()).sum();
+    let complexity = (arcs + 2).saturating_sub(reachable.len()).max(1);
+
+    let to_exit = g.to_exit();
+    let mut truncated = false;
+    let simple = simple_paths(&g, &to_exit, opts, &mut truncated);
+    // a cut-short walk misses branches, so the basis comes from one shortest path per branch outcome
+    let basis = if truncated { basis_paths(&branch_paths(&g, &reachable, &to_exit), complexity) } else { basis_paths(&simple, complexity) };
+    let prime = prime_paths(&g, &reachable, opts, &mut truncated);
+    FunctionPaths { complexity, simple, prime, basis, truncated }
+}
+
+// Entry → Exit walks where no node repeats more than `loop_unroll + 1` times; then keep the ones whose
+// loops all ran 0, 1 or N times. A walk that can no longer reach Exit within `max_length` is dropped
+// early, and the whole search stops after a step budget like `prime_paths`.
+fn simple_paths(g: &Graph, to_exit: &[usize], opts: &PathsConfig, truncated: &mut bool) -> Vec<CfgPath> {
+    struct Walk<'g, 'a> {
+        g: &'g Graph<'a>,
+        to_exit: &'g [usize],
+        opts: &'g PathsConfig,
+        steps: usize,
+        nodes: Vec<usize>,
+        outs: Vec<Option<bool>>,
+        visits: Vec<usize>,
+        found: Vec<CfgPath>,
+        truncated: bool,
+    }
+    fn go(w: &mut Walk, n: usize) {
+        w.steps += 1;
+        if w.found.len() >= w.opts.max_paths || w.steps > w.opts.max_paths.saturating_mul(64) {
+            w.truncated = true;
+            return;
+        }
+        if n == 1 {
+            let p = w.g.path(w.nodes.clone(), &w.outs);
+            let n_times = w.opts.loop_unroll.max(1);
+            if p.loops.iter().all(|l| l.iterations <= 1 || l.iterations == n_times) {
+                w.found.push(p);
+            }
+            return;
+        }
+        if w.nodes.len().saturating_add(w.to_exit[n]) > w.opts.max_length {
+            // unreachable Exit is a dead end, not a cut
+            w.truncated |= w.to_exit[n] != usize::MAX;
+            return;
+        }
+        for &(d, o) in &w.g.succ[n] {
+            if w.visits[d] > w.opts.loop_unroll { continue; }
+            w.visits[d] += 1;
+            w.nodes.push(d);
+            w.outs.push(o);
+            go(w, d);
+            w.nodes.pop();
+            w.outs.pop();
+            w.visits[d] -= 1;
+        }
+    }
+
+    let mut w = Walk { g, to_exit, opts, steps: 0, nodes: vec![0], outs: vec![], visits: vec![0; g.succ.len()], found: vec![], truncated: false };
+    w.visits[0] = 1;
+    go(&mut w, 0);
+    *truncated |= w.truncated;
+    w.found
+}
+
+// For every arc out of a reachable node, the shortest Entry → arc → Exit walk; unbounded by `max_length`
+fn branch_paths(g: &Graph, reachable: &[usize], to_exit: &[usize]) -> Vec<CfgPath> {
+    // shortest way in: the arc each node is first reached by
+    let mut from: Vec<Option<(usize, Option<bool>)>> = vec![None; g.succ.len()];
+    let mut queue = VecDeque::from([0]);
+    while let Some(n) = queue.pop_front() {
+        for &(d, o) in &g.succ[n] {
+            if d == 0 || from[d].is_some() { continue; }
+            from[d] = Some((n, o));
+            queue.push_back(d);
+        }
+    }
+    let mut out: Vec<CfgPath> = Vec::new();
+    for &s in reachable {
+        for &(d, o) in &g.succ[s] {
+            if to_exit[d] == usize::MAX { continue; }
+            let (mut nodes, mut outs) = (vec![s], vec![]);
+            while let Some((p, po)) = from[nodes[0]] {
+                nodes.insert(0, p);
+                outs.insert(0, po);
+            }
+            nodes.push(d);
+            outs.push(o);
+            let mut n = d;
+            while n != 1 {
+                let Some(&(next, no)) = g.succ[n].iter().find(|a| to_exit[a.0] + 1 == to_exit[n]) else { break };
+                nodes.push(next);
+                outs.push(no);
+                n = next;
+            }
+            let p = g.path(nodes, &outs);
+            if !out.contains(&p) { out.push(p); }
+        }
+    }
+    out
+}
+
+// Extend every node into all simple paths that can't grow further (or close a cycle),
+// then drop the ones that sit inside a longer one
+fn prime_paths(g: &Graph, reachable: &[usize], opts: &PathsConfig, truncated: &mut bool) -> Vec<CfgPath> {
+    let budget = opts.max_paths.saturating_mul(64);
+    let mut steps = 0;
+    let mut frontier: Vec<(Vec<usize>, Vec<Option<bool>>)> = reachable.iter().rev().map(|&n| (vec![n], vec![])).collect();
+    let mut done = Vec::new();
+    while let Some((nodes, outs)) = frontier.pop() {
+        steps += 1;
+        if steps > budget {
+            *truncated = true;
+            break;
+        }
+        let first = nodes[0];
+        let last = *nodes.last().unwrap();
+        let closed = nodes.len() > 1 && first == last;
+        let mut grew = false;
+        if !closed && nodes.len() < opts.max_length {
+            for &(d, o) in g.succ[last].iter().rev() {
+                if d != first && nodes.contains(&d) { continue; }
+                let mut n2 = nodes.clone();
+                n2.push(d);
+                let mut o2 = outs.clone();
+                o2.push(o);
+                frontier.push((n2, o2));
+                grew = true;
+            }
+        }
+        if !grew { done.push((nodes, outs)); }
+    }
+
+    let inside = |(pn, po): &(Vec<usize>, Vec<Option<bool>>), (qn, qo): &(Vec<usize>, Vec<Option<bool>>)| {
+        qn.len() > pn.len()
+            && (0..=qn.len() - pn.len()).any(|i| qn[i..i + pn.len()] == pn[..] && qo[i..i + po.len()] == po[..])
+    };
+    let mut prime: Vec<CfgPath> = done
+        .iter()
+        .filter(|p| !done.iter().any(|q| inside(p, q)))
+        .map(|(n, o)| g.path(n.clone(), o))
+        .collect();
+    prime.sort_by(|a, b| a.nodes.cmp(&b.nodes));
+    prime.dedup();
+    if prime.len() > opts.max_paths {
+        prime.truncate(opts.max_paths);
+        *truncated = true;
+    }
+    prime
+}
+
+// Greedy basis: keep taking the path that walks the most arcs not walked yet, then top up to the
+// complexity with any path whose arc counts are independent of those already taken
+fn basis_paths(simple: &[CfgPath], complexity: usize) -> Vec<CfgPath> {
+    let arcs = |p: &CfgPath| -> Vec<(usize, usize, Option<bool>)> {
+        p.nodes.windows(2).zip(p.outcomes()).map(|(w, o)| (w[0], w[1], o)).collect()
+    };
+    let walked: Vec<Vec<(usize, usize, Option<bool>)>> = simple.iter().map(arcs).collect();
+    let columns: BTreeMap<(usize, usize, Option<bool>), usize> = walked.iter().flatten().copied()
+        .collect::<BTreeSet<_>>().into_iter().enumerate().map(|(i, a)| (a, i)).collect();
+
+    // rows in echelon form, with the pivot column of each
+    let mut rows: Vec<(usize, Vec<f64>)> = Vec::new();
+    let mut independent = |arcs: &[(usize, usize, Option<bool>)]| -> bool {
+        let mut v = vec![0.0; columns.len()];
+        for a in arcs { v[columns[a]] += 1.0; }
+        for (pivot, row) in &rows {
+            let f = v[*pivot] / row[*pivot];
+            if f != 0.0 { v.iter_mut().zip(row).for_each(|(x, r)| *x -= f * r); }
+        }
+        match v.iter().position(|x| x.abs() > 1e-9) {
+            Some(pivot) => { rows.push((pivot, v)); true }
+            None => false,
+        }
+    };
+
+    let mut covered = BTreeSet::new();
+    let mut taken = vec![false; simple.len()];
+    let mut basis: Vec<CfgPath> = Vec::new();
+    while basis.len() < complexity {
+        let best = (0..simple.len())
+            .filter(|&i| !taken[i])
+            .map(|i| (walked[i].iter().filter(|a| !covered.contains(*a)).collect::<BTreeSet<_>>().len(), i))
+            .filter(|(n, _)| *n > 0)
+            .max_by(|a, b| a.0.cmp(&b.0).then(simple[b.1].nodes.len().cmp(&simple[a.1].nodes.len())));
+        let Some((_, i)) = best else { break };
+        independent(&walked[i]);
+        covered.extend(walked[i].iter().copied());
+        taken[i] = true;
+        basis.push(simple[i].clone());
+    }
+    let mut rest: Vec<usize> = (0..simple.len()).collect();
+    rest.sort_by_key(|&i| simple[i].nodes.len());
+    for i in rest {
+        if basis.len() >= complexity { break; }
+        if !taken[i] && independent(&walked[i]) {
+            basis.push(simple[i].clone());
+        }
+    }
+    basis
+}
@@ -0,0 +1,424 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::classify::{classify_label, sensitive_params, DataClass, Taxonomy, TypeCatalogue};
+use crate::tag::{has_tag, is_db_write, is_entrypoint, EdgeKind, TAG_KINDS};
+use crate::{edge_kind, SimpleCfg};
+
+const FLOW_KINDS: &[EdgeKind] = &[EdgeKind::Branch, EdgeKind::Loop, EdgeKind::Return, EdgeKind::Other];
+
+/// `net`, `code_eval`, .. as security-flow.json writes them
+pub(crate) fn kind_name(k: EdgeKind) -> String {
+    serde_json::to_value(k).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+}
+
+/// Every edge kind, tags first
+pub(crate) fn all_kinds() -> impl Iterator<Item = EdgeKind> {
+    TAG_KINDS.iter().chain(FLOW_KINDS).copied()
+}
+
+// Node tags beyond the label prefixes: entry points, DB writes and statement kinds
+const PSEUDO_TAGS: &[&str] = &["ENTRY", "DB_WRITE", "IF", "LOOP", "RETURN", "THROW"];
+
+fn is_tag(t: &str) -> bool {
+    PSEUDO_TAGS.contains(&t) || TAG_KINDS.iter().any(|k| k.prefix() == Some(t))
+}
+
+fn tag_matches(tag: &str, label: &str) -> bool {
+    match tag {
+        "ENTRY" => is_entrypoint(label),
+        "DB_WRITE" => has_tag(label, EdgeKind::Db) && is_db_write(label),
+        "IF" => label.starts_with("If:"),
+        "LOOP" => label.starts_with("Loop:"),
+        "RETURN" => label.starts_with("Return:"),
+        "THROW" => label.starts_with("Throw:"),
+        t => label.strip_prefix(t).is_some_and(|r| r.starts_with(':')),
+    }
+}
+
+fn contains(hay: &str, needle: &str) -> bool {
+    hay.to_lowercase().contains(&needle.to_lowercase())
+}
+
+// One alternative of a node pattern: `NET`, or `NET("stripe")` for NET nodes whose label mentions stripe
+#[derive(Debug, Clone)]
+struct NodeAtom {
+    tag: String,
+    text: Option<String>,
+}
+
+// Alternatives: `CRYPTO|VALIDATE`
+type NodePattern = Vec<NodeAtom>;
+
+fn node_matches(p: &NodePattern, label: &str) -> bool {
+    p.iter().any(|a| tag_matches(&a.tag, label) && a.text.as_ref().is_none_or(|t| contains(label, t)))
+}
+
+#[derive(Debug, Clone)]
+enum Cond {
+    Has(NodePattern),
+    /// `later` on a CFG path from `earlier`, through no node matching `without`
+    After { later: NodePattern, earlier: NodePattern, without: NodePattern },
+    Calls(String),
+    Name(String),
+    Kind(EdgeKind),
+    Class(DataClass),
+    Src(String),
+    Dst(String),
+    Not(Box<Cond>),
+    And(Box<Cond>, Box<Cond>),
+    Or(Box<Cond>, Box<Cond>),
+}
+
+/// What a query selects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Functions,
+    /// Edges inside functions, kinded and classified as in security-flow.json
+    Edges,
+}
+
+/// A parsed query:
+///
+/// ```text
+/// [functions where] NET after SECRET without CRYPTO
+/// functions where DB_WRITE after ENTRY without AUTHZ and not name("health")
+/// functions where NET("stripe") and not calls("idempotencyKey")
+/// edges where kind(net) and class(credential)
+/// ```
+///
+/// Nodes are matched by tag (`NET`, `DB`, `SECRET`, .., plus `ENTRY`, `DB_WRITE`, `IF`, `LOOP`, `RETURN`,
+/// `THROW`), optionally with text the label must contain, and `|` for alternatives. `A after B` holds
+/// when an A node is reachable from a B node; `A before B` is `B after A`. Functions also take
+/// `calls("text")` and `name("text")`; edges take `kind(..)`, `class(pii|financial|credential)`,
+/// `src("text")`, `dst("text")`, `name("text")` and a node pattern for either end. Conditions combine
+/// with `and`, `or`, `not` and parentheses.
+#[derive(Debug, Clone)]
+pub struct Query {
+    pub target: Target,
+    cond: Cond,
+}
+
+/// One result: the function, the line it points at, and what matched
+#[derive(Debug, Clone, Serialize)]
+pub struct Hit {
+    pub id: String,
+    pub line: usize,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub detail: String,
+}
+
+impl fmt::Display for Hit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (line {})", self.id, self.line)?;
+        if !self.detail.is_empty() { write!(f, ": {}", self.detail)?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Word(String),
+    Str(String),
+    Sym(char),
+}
+
+impl fmt::Display for Tok {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tok::Word(w) => write!(f, "'{}'", w),
+            Tok::Str(s) => write!(f, "\"{}\"", s),
+            Tok::Sym(c) => write!(f, "'{}'", c),
+        }
+    }
+}
+
+fn tokens(src: &str) -> Result<Vec<Tok>, String> {
+    let mut out = Vec::new();
+    let mut chars = src.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' || c == '\'' {
+            chars.next();
+            let s: String = chars.by_ref().take_while(|&x| x != c).collect();
+            out.push(Tok::Str(s));
+        } else if "()|".contains(c) {
+            chars.next();
+            out.push(Tok::Sym(c));
+        } else if c.is_alphanumeric() || c == '_' {
+            let mut w = String::new();
+            while let Some(&x) = chars.peek().filter(|x| x.is_alphanumeric() || **x == '_' || **x == '-') {
+                w.push(x);
+                chars.next();
+            }
+            out.push(Tok::Word(w));
+        } else {
+            return Err(format!("Unexpected '{}' in query", c));
+        }
+    }
+    Ok(out)
+}
+
+struct Parser {
+    toks: Vec<Tok>,
+    at: usize,
+    target: Target,
+}
+
+impl Parser {
+    fn peek_word(&self) -> Option<String> {
+        match self.toks.get(self.at) {
+            Some(Tok::Word(w)) => Some(w.to_lowercase()),
+            _ => None,
+        }
+    }
+
+    fn keyword(&mut self, k: &str) -> bool {
+        let hit = self.peek_word().as_deref() == Some(k);
+        if hit { self.at += 1; }
+        hit
+    }
+
+    fn sym(&mut self, c: char) -> bool {
+        let hit = self.toks.get(self.at) == Some(&Tok::Sym(c));
+        if hit { self.at += 1; }
+        hit
+    }
+
+    fn found(&self) -> String {
+        self.toks.get(self.at).map(|t| t.to_string()).unwrap_or_else(|| "end of query".to_string())
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.sym(c) { Ok(()) } else { Err(format!("Expected '{}', found {}", c, self.found())) }
+    }
+
+    fn or(&mut self) -> Result<Cond, String> {
+        let mut c = self.and()?;
+        while self.keyword("or") { c = Cond::Or(Box::new(c), Box::new(self.and()?)); }
+        Ok(c)
+    }
+
+    fn and(&mut self) -> Result<Cond, String> {
+        let mut c = self.unary()?;
+        while self.keyword("and") { c = Cond::And(Box::new(c), Box::new(self.unary()?)); }
+        Ok(c)
+    }
+
+    fn unary(&mut self) -> Result<Cond, String> {
+        if self.keyword("not") { return Ok(Cond::Not(Box::new(self.unary()?))); }
+        if self.sym('(') {
+            let c = self.or()?;
+            self.expect(')')?;
+            return Ok(c);
+        }
+        let Some(word) = self.peek_word() else { return Err(format!("Expected a condition, found {}", self.found())) };
+        if ["calls", "name", "func", "kind", "class", "src", "dst"].contains(&word.as_str()) {
+            self.at += 1;
+            return self.predicate(&word);
+        }
+        let node = self.node()?;
+        let (later, earlier) = if self.keyword("after") {
+            let other = self.node()?;
+            (node, other)
+        } else if self.keyword("before") {
+            let other = self.node()?;
+            (other, node)
+        } else {
+            return Ok(Cond::Has(node));
+        };
+        if self.target == Target::Edges { return Err("after/before only apply to function queries".to_string()); }
+        let without = if self.keyword("without") { self.node()? } else { vec![] };
+        Ok(Cond::After { later, earlier, without })
+    }
+
+    fn predicate(&mut self, name: &str) -> Result<Cond, String> {
+        self.expect('(')?;
+        let arg = match self.toks.get(self.at) {
+            Some(Tok::Str(s) | Tok::Word(s)) => s.clone(),
+            _ => return Err(format!("Expected an argument to {}(), found {}", name, self.found())),
+        };
+        self.at += 1;
+        self.expect(')')?;
+        let edges_only = matches!(name, "kind" | "class" | "src" | "dst");
+        if edges_only && self.target == Target::Functions {
+            return Err(format!("{}() only applies to edge queries (`edges where ..`)", name));
+        }
+        if name == "calls" && self.target == Target::Edges {
+            return Err("calls() only applies to function queries".to_string());
+        }
+        let lower = arg.to_lowercase();
+        Ok(match name {
+            "calls" => Cond::Calls(arg),
+            "name" | "func" => Cond::Name(arg),
+            "src" => Cond::Src(arg),
+            "dst" => Cond::Dst(arg),
+            "kind" => match all_kinds().find(|k| kind_name(*k) == lower || k.prefix().is_some_and(|p| p.eq_ignore_ascii_case(&arg))) {
+                Some(k) => Cond::Kind(k),
+                None => return Err(format!("Unknown edge kind '{}'; one of {}", arg, all_kinds().map(kind_name).collect::<Vec<_>>().join(", "))),
+            },
+            _ => match lower.as_str() {
+                "pii" => Cond::Class(DataClass::Pii),
+                "financial" => Cond::Class(DataClass::Financial),
+                "credential" => Cond::Class(DataClass::Credential),
+                _ => return Err(format!("Unknown data class '{}'; one of pii, financial, credential", arg)),
+            },
+        })
+    }
+
+    fn node(&mut self) -> Result<NodePattern, String> {
+        let mut alts = Vec::new();
+        loop {
+            let Some(word) = self.peek_word() else { return Err(format!("Expected a node tag, found {}", self.found())) };
+            let tag = word.to_uppercase();
+            if !is_tag(&tag) {
+                let tags: Vec<&str> = TAG_KINDS.iter().filter_map(|k| k.prefix()).chain(PSEUDO_TAGS.iter().copied()).collect();
+                return Err(format!("Unknown node tag '{}'; one of {}", word, tags.join(", ")));
+            }
+            self.at += 1;
+            let text = if self.sym('(') {
+                let Some(Tok::Str(s)) = self.toks.get(self.at).cloned() else {
+                    return Err(format!("Expected a quoted string in {}(..), found {}", tag, self.found()));
+                };
+                self.at += 1;
+                self.expect(')')?;
+                Some(s)
+            } else {
+                None
+            };
+            alts.push(NodeAtom { tag, text });
+            if !self.sym('|') { return Ok(alts); }
+        }
+    }
+}
+
+// First line of a label
+fn short(label: &str) -> &str {
+    label.lines().next().unwrap_or_default()
+}
+
+impl Query {
+    pub fn parse(src: &str) -> Result<Query, String> {
+        let toks = tokens(src)?;
+        let mut p = Parser { toks, at: 0, target: Target::Functions };
+        let target = match p.peek_word().as_deref() {
+            Some("functions") => Some(Target::Functions),
+            Some("edges") => Some(Target::Edges),
+            _ => None,
+        };
+        if let Some(t) = target {
+            p.at += 1;
+            p.target = t;
+            if !p.keyword("where") { return Err(format!("Expected 'where', found {}", p.found())); }
+        }
+        let cond = p.or()?;
+        if p.at < p.toks.len() { return Err(format!("Unexpected {} after the condition", p.found())); }
+        Ok(Query { target: p.target, cond })
+    }
+
+    /// Hits across functions keyed `file::func`, in key order
+    pub fn run(&self, funcs: &BTreeMap<String, SimpleCfg>, taxonomy: &Taxonomy, types: &TypeCatalogue) -> Vec<Hit> {
+        funcs.iter().flat_map(|(id, cfg)| self.hits(id, cfg, taxonomy, types)).collect()
+    }
+
+    /// Hits in one function: at most one for a function query (pointing at the nodes that made it match),
+    /// one per distinct matching edge for an edge query
+    pub fn hits(&self, id: &str, cfg: &SimpleCfg, taxonomy: &Taxonomy, types: &TypeCatalogue) -> Vec<Hit> {
+        let line = |n: usize| cfg.locs.get(n).map(|l| l.0).unwrap_or_default();
+        match self.target {
+            Target::Functions => {
+                let Some(witness) = holds(&self.cond, id, cfg) else { return vec![] };
+                vec![Hit {
+                    id: id.to_string(),
+                    line: line(witness.last().copied().unwrap_or(0)),
+                    detail: witness.iter().map(|&n| short(&cfg.nodes[n])).collect::<Vec<_>>().join(" → "),
+                }]
+            }
+            Target::Edges => {
+                let params = sensitive_params(&cfg.params, taxonomy, types);
+                let mut out: Vec<Hit> = Vec::new();
+                for &(si, di) in &cfg.edges {
+                    let (s, d) = (cfg.nodes[si].as_str(), cfg.nodes[di].as_str());
+                    let kind = edge_kind(s, d);
+                    let classes: Vec<DataClass> = classify_label(&format!("{s} {d}"), &params, taxonomy).into_iter().collect();
+                    let edge = Edge { id, src: s, dst: d, kind, classes: &classes };
+                    if !edge.holds(&self.cond) { continue; }
+                    let mut detail = format!("{} → {} ({}", short(s), short(d), kind_name(kind));
+                    for c in &classes { detail.push_str(&format!(", {}", serde_json::to_value(c).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default())); }
+                    detail.push(')');
+                    if out.iter().any(|h| h.detail == detail) { continue; }
+                    out.push(Hit { id: id.to_string(), line: line(si), detail });
+                }
+                out
+            }
+        }
+    }
+}
+
+// The nodes that make a function condition hold, or None
+fn holds(c: &Cond, id: &str, cfg: &SimpleCfg) -> Option<Vec<usize>> {
+    match c {
+        Cond::Has(p) => cfg.nodes.iter().position(|l| node_matches(p, l)).map(|n| vec![n]),
+        Cond::After { later, earlier, without } => {
+            (0..cfg.nodes.len())
+                .filter(|&a| node_matches(earlier, &cfg.nodes[a]))
+                .find_map(|a| reach(cfg, a, later, without).map(|b| vec![a, b]))
+        }
+        Cond::Calls(t) => cfg.invokes.iter().any(|c| contains(c, t)).then(Vec::new),
+        Cond::Name(t) => contains(id, t).then(Vec::new),
+        Cond::Not(c) => holds(c, id, cfg).is_none().then(Vec::new),
+        Cond::And(a, b) => {
+            let mut w = holds(a, id, cfg)?;
+            w.extend(holds(b, id, cfg)?);
+            Some(w)
+        }
+        Cond::Or(a, b) => holds(a, id, cfg).or_else(|| holds(b, id, cfg)),
+        // edge-only predicates are refused by the parser
+        Cond::Kind(_) | Cond::Class(_) | Cond::Src(_) | Cond::Dst(_) => None,
+    }
+}
+
+// First node matching `to` reachable from `from` without passing a node matching `without`
+fn reach(cfg: &SimpleCfg, from: usize, to: &NodePattern, without: &NodePattern) -> Option<usize> {
+    let mut seen = vec![false; cfg.nodes.len()];
+    let mut queue = VecDeque::from([from]);
+    while let Some(n) = queue.pop_front() {
+        for &(_, d) in cfg.edges.iter().filter(|(s, _)| *s == n) {
+            if seen[d] { continue; }
+            seen[d] = true;
+            if node_matches(to, &cfg.nodes[d]) { return Some(d); }
+            if !node_matches(without, &cfg.nodes[d]) { queue.push_back(d); }
+        }
+    }
+    None
+}
+
+// A CFG edge as security-flow.json records it
+struct Edge<'a> {
+    id: &'a str,
+    src: &'a str,
+    dst: &'a str,
+    kind: EdgeKind,
+    classes: &'a [DataClass],
+}
+
+impl Edge<'_> {
+    fn holds(&self, c: &Cond) -> bool {
+        match c {
+            Cond::Has(p) => node_matches(p, self.src) || node_matches(p, self.dst),
+            Cond::Name(t) => contains(self.id, t),
+            Cond::Kind(k) => kind_name(*k) == kind_name(self.kind),
+            Cond::Class(k) => self.classes.contains(k),
+            Cond::Src(t) => contains(self.src, t),
+            Cond::Dst(t) => contains(self.dst, t),
+            Cond::Not(c) => !self.holds(c),
+            Cond::And(a, b) => self.holds(a) && self.holds(b),
+            Cond::Or(a, b) => self.holds(a) || self.holds(b),
+            // function-only predicates are refused by the parser
+            Cond::After { .. } | Cond::Calls(_) => false,
+        }
+    }
+}
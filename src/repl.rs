@@ -0,0 +1,383 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use serde::Deserialize;
+
+use crate::classify::{Taxonomy, TypeCatalogue};
+use crate::inspect::render_tree;
+use crate::query::{all_kinds, kind_name, Hit, Query};
+use crate::tag::{has_tag, is_db_write, EdgeKind};
+use crate::{analyze_with_dtos, edge_kind, Param, Route, SimpleCfg};
+
+/// Commands and their one-line help, in `help` order
+const COMMANDS: &[(&str, &str)] = &[
+    ("functions", "functions [text]     function IDs, or only those containing text"),
+    ("cfg", "cfg <id> [-v]        a function's CFG as a tree; -v adds lines and condition inputs"),
+    ("routes", "routes [db-writes]   HTTP routes; db-writes keeps those reaching a DB write, with the call chain"),
+    ("callers", "callers <id>         functions that call <id>"),
+    ("callees", "callees <id>         what <id> calls; calls outside the analyzed code are listed by name"),
+    ("edges", "edges <kind> [id]    CFG edges of one kind (net, db, auth, secret, ..), optionally of one function"),
+    ("query", "query <expr>         run a query, e.g. `NET after SECRET without CRYPTO` or `edges where class(pii)`"),
+    ("help", "help                 this list"),
+    ("quit", "quit                 leave (also exit or Ctrl-D)"),
+];
+
+// The parts of a .cfg.json function the session uses; conditions and findings aren't read back
+#[derive(Deserialize)]
+struct StoredCfg {
+    nodes: Vec<String>,
+    #[serde(default)]
+    locs: Vec<(usize, usize)>,
+    #[serde(default)]
+    edges: Vec<(usize, usize)>,
+    #[serde(default)]
+    branches: Vec<(usize, usize, bool)>,
+    #[serde(default)]
+    calls: Vec<(usize, String)>,
+    #[serde(default)]
+    results: Vec<(usize, String)>,
+    #[serde(default)]
+    invokes: Vec<String>,
+    #[serde(default)]
+    params: Vec<Param>,
+    #[serde(default)]
+    route: Option<Route>,
+}
+
+impl From<StoredCfg> for SimpleCfg {
+    fn from(s: StoredCfg) -> Self {
+        SimpleCfg {
+            nodes: s.nodes,
+            locs: s.locs,
+            edges: s.edges,
+            branches: s.branches,
+            conditions: vec![],
+            calls: s.calls,
+            results: s.results,
+            invokes: s.invokes,
+            params: s.params,
+            findings: vec![],
+            queries: vec![],
+            outbound: vec![],
+            sdk_calls: vec![],
+            route: s.route,
+            log_sites: vec![],
+        }
+    }
+}
+
+fn cfg_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for e in entries.flatten() {
+        let p = e.path();
+        if p.is_dir() {
+            cfg_files(&p, out);
+        } else if p.to_string_lossy().ends_with(".cfg.json") {
+            out.push(p);
+        }
+    }
+}
+
+// `src/loan.service.ts::LoanService.approve` → (`src/loan.service.ts`, `LoanService.approve`)
+fn split_id(id: &str) -> (&str, &str) {
+    id.split_once("::").unwrap_or(("", id))
+}
+
+fn first_line(label: &str) -> &str {
+    label.lines().next().unwrap_or_default()
+}
+
+/// Functions loaded for interactive queries, keyed `file::func` with the file relative to the root
+pub struct Session {
+    pub funcs: BTreeMap<String, SimpleCfg>,
+    taxonomy: Taxonomy,
+    // type → data classes; only known when the root was analyzed
+    types: TypeCatalogue,
+}
+
+impl Session {
+    /// Read back a `.casesmithresults` directory, or analyze the source root `path` now
+    pub fn load(path: &Path, taxonomy: &Taxonomy) -> Result<Session, String> {
+        if !path.is_dir() {
+            return Err(format!("'{}' is not a directory", path.display()));
+        }
+        let results = path.file_name().is_some_and(|n| n == ".casesmithresults") || path.join("security-flow.json").is_file();
+        if results { Session::from_results(path, taxonomy) } else { Ok(Session::from_root(path, taxonomy)) }
+    }
+
+    pub fn from_root(root: &Path, taxonomy: &Taxonomy) -> Session {
+        let mut funcs = BTreeMap::new();
+        let mut types = TypeCatalogue::default();
+        for (path, fa) in analyze_with_dtos(root, taxonomy).0 {
+            let rel = path.strip_prefix(root).unwrap_or(&path).display().to_string();
+            types.add(&fa.type_fields);
+            funcs.extend(fa.cfgs.into_iter().map(|(func, cfg)| (format!("{}::{}", rel, func), cfg)));
+        }
+        Session { funcs, taxonomy: taxonomy.clone(), types }
+    }
+
+    /// The .cfg.json files under `dir`. The source extension is taken from the file next to the results
+    /// dir when it exists, else `.ts`.
+    pub fn from_results(dir: &Path, taxonomy: &Taxonomy) -> Result<Session, String> {
+        let mut files = Vec::new();
+        cfg_files(dir, &mut files);
+        if files.is_empty() {
+            return Err(format!("No .cfg.json files under {}; run `casesmith generate` first", dir.display()));
+        }
+        let root = dir.parent().unwrap_or(dir);
+        let mut funcs = BTreeMap::new();
+        for path in files {
+            let text = std::fs::read_to_string(&path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+            let stored: BTreeMap<String, StoredCfg> = serde_json::from_str(&text).map_err(|e| format!("Could not parse {}: {}", path.display(), e))?;
+            let rel = path.strip_prefix(dir).unwrap_or(&path).display().to_string();
+            let stem = rel.trim_end_matches(".cfg.json");
+            let source = [".ts", ".tsx", ".js", ".jsx"].iter()
+                .map(|ext| format!("{}{}", stem, ext))
+                .find(|f| root.join(f).is_file())
+                .unwrap_or_else(|| format!("{}.ts", stem));
+            funcs.extend(stored.into_iter().map(|(func, cfg)| (format!("{}::{}", source, func), cfg.into())));
+        }
+        Ok(Session { funcs, taxonomy: taxonomy.clone(), types: TypeCatalogue::default() })
+    }
+
+    /// The function an argument names: a whole ID, or when only one function fits, the part after `::`
+    /// (`LoanService.approve`), its method name or a path suffix
+    pub fn resolve(&self, arg: &str) -> Result<&str, String> {
+        if let Some((id, _)) = self.funcs.get_key_value(arg) { return Ok(id); }
+        let hits: Vec<&str> = self.funcs.keys()
+            .map(String::as_str)
+            .filter(|id| {
+                let func = split_id(id).1;
+                func == arg || func.rsplit('.').next() == Some(arg) || id.ends_with(&format!("/{}", arg))
+            })
+            .collect();
+        match hits.as_slice() {
+            [one] => Ok(one),
+            [] => Err(format!("No function matches '{}'; try `functions {}`", arg, arg)),
+            more => Err(format!("'{}' is ambiguous: {}", arg, more.join(", "))),
+        }
+    }
+
+    // Functions a call of `callee` (as `call_name` flattens it) made in `caller` can land on. `this.m` stays
+    // in the caller's class; `this.loanService.approve` wants a class named like the receiver or a file
+    // whose stem is the receiver (`billing.charge`); a bare `f` prefers the caller's file.
+    fn targets(&self, caller: &str, callee: &str) -> Vec<&str> {
+        let (file, func) = split_id(caller);
+        let segs: Vec<&str> = callee.split('.').collect();
+        let method = segs[segs.len() - 1];
+        let named = |id: &&str| split_id(id).1.rsplit('.').next() == Some(method);
+        let candidates: Vec<&str> = self.funcs.keys().map(String::as_str).filter(named).collect();
+        match segs.as_slice() {
+            [_] => {
+                let bare: Vec<&str> = candidates.into_iter().filter(|id| split_id(id).1 == method).collect();
+                let local: Vec<&str> = bare.iter().copied().filter(|id| split_id(id).0 == file).collect();
+                if local.is_empty() { bare } else { local }
+            }
+            ["this", _] => {
+                let class = func.split_once('.').map(|(c, _)| c).unwrap_or_default();
+                let own = format!("{}::{}.{}", file, class, method);
+                candidates.into_iter().filter(|id| *id == own).collect()
+            }
+            _ => {
+                let receiver = segs[segs.len() - 2].trim_start_matches('_').to_lowercase();
+                candidates.into_iter().filter(|id| {
+                    let (f, name) = split_id(id);
+                    let stem = f.rsplit('/').next().unwrap_or(f).split('.').next().unwrap_or_default();
+                    match name.split_once('.') {
+                        Some((class, _)) => class.to_lowercase() == receiver,
+                        None => stem.to_lowercase() == receiver,
+                    }
+                }).collect()
+            }
+        }
+    }
+
+    /// What `id` calls, in first-call order: each callee as written and the analyzed functions it lands on
+    pub fn callees(&self, id: &str) -> Vec<(&str, Vec<&str>)> {
+        let Some(cfg) = self.funcs.get(id) else { return vec![] };
+        cfg.invokes.iter().map(|c| (c.as_str(), self.targets(id, c))).collect()
+    }
+
+    /// Functions with a call that lands on `id`
+    pub fn callers(&self, id: &str) -> Vec<&str> {
+        self.funcs.keys()
+            .map(String::as_str)
+            .filter(|caller| self.callees(caller).iter().any(|(_, t)| t.contains(&id)))
+            .collect()
+    }
+
+    // Shortest call chain from `id` to a function that writes to the DB, and the write's label
+    fn db_write_chain(&self, id: &str) -> Option<(Vec<&str>, &str)> {
+        let mut prev: HashMap<&str, &str> = HashMap::new();
+        let (start, _) = self.funcs.get_key_value(id)?;
+        let mut queue = VecDeque::from([start.as_str()]);
+        while let Some(f) = queue.pop_front() {
+            if let Some(write) = self.funcs[f].nodes.iter().find(|n| has_tag(n, EdgeKind::Db) && is_db_write(n)) {
+                let mut chain = vec![f];
+                while let Some(p) = prev.get(chain[chain.len() - 1]) { chain.push(p); }
+                chain.reverse();
+                return Some((chain, write));
+            }
+            for (_, targets) in self.callees(f) {
+                for t in targets {
+                    if t == start || prev.contains_key(t) { continue; }
+                    prev.insert(t, f);
+                    queue.push_back(t);
+                }
+            }
+        }
+        None
+    }
+
+    /// Hits of a query over every loaded function
+    pub fn query(&self, q: &Query) -> Vec<Hit> {
+        q.run(&self.funcs, &self.taxonomy, &self.types)
+    }
+
+    /// Run one command line and return what it prints
+    pub fn eval(&self, line: &str) -> String {
+        if let Some(expr) = line.trim().strip_prefix("query ") {
+            return match Query::parse(expr) {
+                Ok(q) => self.query(&q).iter().map(Hit::to_string).collect::<Vec<_>>().join("\n"),
+                Err(e) => e,
+            };
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let out = match words.as_slice() {
+            [] => Ok(String::new()),
+            ["help"] => Ok(COMMANDS.iter().map(|(_, h)| *h).collect::<Vec<_>>().join("\n")),
+            ["functions" | "ls", rest @ ..] => Ok(self.functions(rest.first().copied())),
+            ["cfg", arg, rest @ ..] => self.resolve(arg).map(|id| {
+                let (file, func) = split_id(id);
+                let grammar = if file.ends_with("x") { "tsx" } else { "typescript" };
+                let one = BTreeMap::from([(func.to_string(), self.funcs[id].clone())]);
+                render_tree(file, grammar, &one, rest.contains(&"-v")).trim_end().to_string()
+            }),
+            ["routes"] => Ok(self.routes(false)),
+            ["routes", "db-writes"] => Ok(self.routes(true)),
+            ["callers", arg] => self.resolve(arg).map(|id| self.callers(id).join("\n")),
+            ["callees", arg] => self.resolve(arg).map(|id| {
+                self.callees(id).iter()
+                    .map(|(c, t)| if t.is_empty() { c.to_string() } else { format!("{} → {}", c, t.join(", ")) })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }),
+            ["edges", kind] => self.edges(kind, None),
+            ["edges", kind, arg] => self.resolve(arg).and_then(|id| self.edges(kind, Some(id))),
+            [cmd, ..] => match COMMANDS.iter().find(|(c, _)| c == cmd) {
+                Some((_, h)) => Err(format!("usage: {}", h)),
+                None => Err(format!("Unknown command '{}'; `help` lists them", cmd)),
+            },
+        };
+        out.unwrap_or_else(|e| e)
+    }
+
+    fn functions(&self, filter: Option<&str>) -> String {
+        self.funcs.iter()
+            .filter(|(id, _)| filter.is_none_or(|f| id.to_lowercase().contains(&f.to_lowercase())))
+            .map(|(id, cfg)| match &cfg.route {
+                Some(r) => format!("{}  [{} {}]", id, r.method, r.path),
+                None => id.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn routes(&self, db_writes: bool) -> String {
+        let mut out = Vec::new();
+        for (id, cfg) in &self.funcs {
+            let Some(r) = &cfg.route else { continue };
+            if !db_writes {
+                out.push(format!("{} {}  {}", r.method, r.path, id));
+            } else if let Some((chain, write)) = self.db_write_chain(id) {
+                out.push(format!("{} {}  {}  {}", r.method, r.path, chain.join(" → "), first_line(write)));
+            }
+        }
+        out.join("\n")
+    }
+
+    fn edges(&self, kind: &str, only: Option<&str>) -> Result<String, String> {
+        let Some(k) = all_kinds().find(|k| kind_name(*k) == kind.to_lowercase() || k.prefix().is_some_and(|p| p.eq_ignore_ascii_case(kind))) else {
+            let names: Vec<String> = all_kinds().map(kind_name).collect();
+            return Err(format!("Unknown edge kind '{}'; one of {}", kind, names.join(", ")));
+        };
+        let mut out = Vec::new();
+        for (id, cfg) in self.funcs.iter().filter(|(id, _)| only.is_none_or(|o| o == id.as_str())) {
+            for &(s, d) in &cfg.edges {
+                let (s, d) = (&cfg.nodes[s], &cfg.nodes[d]);
+                if kind_name(edge_kind(s, d)) != kind_name(k) { continue; }
+                let line = format!("{}: {} → {}", id, first_line(s), first_line(d));
+                if !out.contains(&line) { out.push(line); }
+            }
+        }
+        Ok(out.join("\n"))
+    }
+
+    /// Completions for the word ending at `pos`: command names first, then edge kinds or function IDs. An
+    /// ID also completes from its function name, so `LoanSer` offers `src/loan.service.ts::LoanService.approve`.
+    pub fn complete(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let start = line[..pos].rfind(char::is_whitespace).map(|i| i + 1).unwrap_or(0);
+        let word = &line[start..pos];
+        let before: Vec<&str> = line[..start].split_whitespace().collect();
+        let fixed: Vec<String> = match before.as_slice() {
+            [] => COMMANDS.iter().map(|(c, _)| c.to_string()).collect(),
+            ["edges"] => all_kinds().map(kind_name).collect(),
+            ["routes"] => vec!["db-writes".to_string()],
+            ["cfg", _] => vec!["-v".to_string()],
+            ["cfg" | "callers" | "callees", ..] | ["edges", _] => {
+                let ids = self.funcs.keys().filter(|id| id.starts_with(word) || split_id(id).1.starts_with(word));
+                return (start, ids.cloned().collect());
+            }
+            _ => vec![],
+        };
+        (start, fixed.into_iter().filter(|c| c.starts_with(word)).collect())
+    }
+}
+
+struct ReplHelper(Session);
+
+impl Completer for ReplHelper {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.0.complete(line, pos))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+/// Read commands until `quit` or end of input, with tab completion over commands and function IDs
+pub fn interact(session: Session) -> Result<(), String> {
+    let mut rl: Editor<ReplHelper, DefaultHistory> = Editor::new().map_err(|e| e.to_string())?;
+    rl.set_helper(Some(ReplHelper(session)));
+    loop {
+        let line = match rl.readline("casesmith> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => return Ok(()),
+            Err(e) => return Err(e.to_string()),
+        };
+        let line = line.trim();
+        if matches!(line, "quit" | "exit") { return Ok(()); }
+        if line.is_empty() { continue; }
+        let _ = rl.add_history_entry(line);
+        let Some(helper) = rl.helper() else { return Ok(()) };
+        let out = helper.0.eval(line);
+        if !out.is_empty() { println!("{}", out); }
+    }
+}
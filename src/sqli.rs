@@ -0,0 +1,99 @@
+use tree_sitter::Node;
+
+use crate::findings::{Finding, Severity};
+use crate::tag::call_name;
+
+// Query-builder methods whose first argument is a raw SQL fragment
+const BUILDER_FRAGMENTS: &[&str] = &["where", "andwhere", "orwhere", "having", "andhaving", "orhaving", "orderby", "addorderby", "groupby", "select", "addselect"];
+const KNEX_RAW: &[&str] = &["raw", "whereraw", "orwhereraw", "havingraw", "orderbyraw", "joinraw", "groupbyraw", "selectraw"];
+
+/// Raw SQL built from non-constant parts: `$queryRawUnsafe`, `.query()`, `knex.raw`, query-builder `.where()`.
+/// Placeholders with a separate values array and Prisma's tagged `$queryRaw` template are safe.
+pub fn check_sql_call(code: &str, call: Node) -> Option<Finding> {
+    let args = call.child_by_field_name("arguments")?;
+    // tagged template: prisma.$queryRaw`... ${id}` → parameterized by Prisma
+    if args.kind() == "template_string" { return None; }
+    let name = call_name(code, call)?;
+    let lname = name.to_lowercase();
+    let op = lname.rsplit('.').next().unwrap_or("");
+    let in_builder = lname.contains("createquerybuilder") || lname.contains("querybuilder") || lname.contains("qb.");
+
+    let (severity, hint) = match op {
+        "$queryrawunsafe" | "$executerawunsafe" => (Severity::Critical, "use the tagged template prisma.$queryRaw`...${value}` instead"),
+        "query" if !lname.starts_with("this.http") => (Severity::High, "use placeholders ($1 / ?) and pass values as the second argument"),
+        _ if KNEX_RAW.contains(&op) && (lname.contains("knex") || lname.contains("db.") || op != "raw") =>
+            (Severity::High, "use knex.raw('... ?', [value]) bindings or the query builder"),
+        _ if BUILDER_FRAGMENTS.contains(&op) && in_builder => (Severity::High, "use named parameters: .where('x = :x', { x })"),
+        "raw" | "sql" if lname == "prisma.raw" || lname == "prisma.sql" => (Severity::High, "use Prisma.sql`...${value}` which parameterizes values"),
+        _ => return None,
+    };
+
+    let mut sql = args.named_child(0)?;
+    // pg: client.query({ text, values })
+    if sql.kind() == "object" {
+        sql = (0..sql.named_child_count()).filter_map(|i| sql.named_child(i)).find_map(|p| {
+            let key = p.child_by_field_name("key")?;
+            (&code[key.start_byte()..key.end_byte()] == "text").then(|| p.child_by_field_name("value"))?
+        })?;
+    }
+    // a bare variable may hold SQL built elsewhere; only the *Unsafe APIs are worth a (weaker) warning then
+    let dynamic = if sql.kind() == "identifier" { vec![] } else { dynamic_parts(code, sql) };
+    let unsafe_api = severity == Severity::Critical;
+    if dynamic.is_empty() && !(unsafe_api && sql.kind() == "identifier") { return None; }
+
+    let message = if dynamic.is_empty() {
+        format!("`{}` runs SQL held in `{}`; check it is not built from input", name, &code[sql.start_byte()..sql.end_byte()])
+    } else {
+        format!("`{}` builds SQL from non-constant {}", name, dynamic.iter().map(|d| format!("`{}`", d)).collect::<Vec<_>>().join(", "))
+    };
+    Some(Finding {
+        rule: "sql-injection".to_string(),
+        severity: if dynamic.is_empty() { Severity::Medium } else { severity },
+        line: call.start_position().row + 1,
+        message,
+        hint: Some(hint.to_string()),
+    })
+}
+
+/// Non-constant pieces of a string expression: template substitutions and non-literal `+` operands.
+/// SCREAMING_CASE identifiers are treated as constants (`'SELECT * FROM ' + LOANS_TABLE`).
+fn dynamic_parts(code: &str, n: Node) -> Vec<String> {
+    let text = |x: Node| code[x.start_byte()..x.end_byte()].to_string();
+    let mut out = Vec::new();
+    match n.kind() {
+        "template_string" => {
+            for i in 0..n.named_child_count() {
+                let c = n.named_child(i).unwrap();
+                if c.kind() == "template_substitution"
+                    && let Some(e) = c.named_child(0)
+                    && !is_constant(code, e)
+                {
+                    out.push(text(e));
+                }
+            }
+        }
+        "binary_expression" => {
+            for side in ["left", "right"] {
+                if let Some(s) = n.child_by_field_name(side) { out.extend(dynamic_parts(code, s)); }
+            }
+        }
+        "parenthesized_expression" => {
+            if let Some(inner) = n.named_child(0) { out.extend(dynamic_parts(code, inner)); }
+        }
+        _ if !is_constant(code, n) => out.push(text(n)),
+        _ => {}
+    }
+    out
+}
+
+fn is_constant(code: &str, n: Node) -> bool {
+    match n.kind() {
+        "string" | "number" | "true" | "false" | "null" => true,
+        "template_string" => n.named_children(&mut n.walk()).all(|c| c.kind() != "template_substitution"),
+        "identifier" => {
+            let t = &code[n.start_byte()..n.end_byte()];
+            t.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        }
+        _ => false,
+    }
+}
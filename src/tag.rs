@@ -2,7 +2,51 @@ use serde::Serialize;
 
 #[derive(Debug, Clone, Copy, Serialize)]
 #[serde(rename_all = "snake_case")]
-pub enum EdgeKind { Branch, Loop, Return, Net, Db, Auth, Crypto, Secret, Log, Other }
+pub enum EdgeKind { Branch, Loop, Return, Net, Db, Auth, Authz, Crypto, Secret, Log, Exec, CodeEval, Storage, Queue, Xss, Redirect, ClientStorage, Validate, Sdk, Other }
+
+impl EdgeKind {
+    /// Label prefix used for tag nodes of this kind in a `SimpleCfg` (e.g. "NET" → "NET: axios.get(..)")
+    pub fn prefix(self) -> Option<&'static str> {
+        match self {
+            EdgeKind::Net => Some("NET"),
+            EdgeKind::Db => Some("DB"),
+            EdgeKind::Auth => Some("AUTH"),
+            EdgeKind::Authz => Some("AUTHZ"),
+            EdgeKind::Crypto => Some("CRYPTO"),
+            EdgeKind::Secret => Some("SECRET"),
+            EdgeKind::Log => Some("LOG"),
+            EdgeKind::Exec => Some("EXEC"),
+            EdgeKind::CodeEval => Some("EVAL"),
+            EdgeKind::Storage => Some("STORAGE"),
+            EdgeKind::Queue => Some("QUEUE"),
+            EdgeKind::Xss => Some("XSS"),
+            EdgeKind::Redirect => Some("REDIRECT"),
+            EdgeKind::ClientStorage => Some("CLIENT_STORAGE"),
+            EdgeKind::Validate => Some("VALIDATE"),
+            EdgeKind::Sdk => Some("SDK"),
+            EdgeKind::Branch | EdgeKind::Loop | EdgeKind::Return | EdgeKind::Other => None,
+        }
+    }
+}
+
+/// Tag kinds in the priority order used when an edge touches two differently-tagged nodes
+pub const TAG_KINDS: &[EdgeKind] = &[
+    EdgeKind::Exec, EdgeKind::CodeEval, EdgeKind::Xss, EdgeKind::Redirect, EdgeKind::ClientStorage,
+    EdgeKind::Storage, EdgeKind::Queue, EdgeKind::Net, EdgeKind::Sdk, EdgeKind::Db, EdgeKind::Authz, EdgeKind::Auth,
+    EdgeKind::Crypto, EdgeKind::Secret, EdgeKind::Validate, EdgeKind::Log,
+];
+
+/// True if a CFG node label marks a function entry point (HTTP route or message consumer)
+pub fn is_entrypoint(label: &str) -> bool {
+    label.starts_with("USER ENTRY") || label.starts_with("QUEUE ENTRY")
+}
+
+/// True if a CFG node label carries the tag prefix of `kind`
+pub fn has_tag(label: &str, kind: EdgeKind) -> bool {
+    kind.prefix()
+        .and_then(|p| label.strip_prefix(p))
+        .is_some_and(|rest| rest.starts_with(':'))
+}
 
 pub fn snippet(code: &str, n: tree_sitter::Node) -> String {
     let start = n.start_byte();
@@ -11,8 +55,9 @@ pub fn snippet(code: &str, n: tree_sitter::Node) -> String {
 }
 
 // Try to flatten a call target like axios.post -> "axios.post", prisma.user.findMany -> "prisma.user.findMany"
+// (`new vm.Script(..)` flattens its constructor the same way -> "vm.Script")
 pub fn call_name(code: &str, call: tree_sitter::Node) -> Option<String> {
-    let func = call.child_by_field_name("function")?;
+    let func = call.child_by_field_name("function").or_else(|| call.child_by_field_name("constructor"))?;
     fn flatten(code: &str, n: tree_sitter::Node, out: &mut Vec<String>) {
         match n.kind() {
             "identifier" => out.push(snippet(code, n)),
@@ -30,11 +75,49 @@ pub fn call_name(code: &str, call: tree_sitter::Node) -> Option<String> {
     if parts.is_empty() { None } else { Some(parts.join(".")) }
 }
 
-// Heuristics: classify a call into one of our security kinds
+// Heuristics: classify a call (or `new` expression) into one of our security kinds
 pub fn classify_call(code: &str, call: tree_sitter::Node) -> Option<EdgeKind> {
     let name = call_name(code, call).unwrap_or_default().to_lowercase();
+    // EXEC (child_process, shelljs, execa)
+    if is_exec_call(&name) {
+        return Some(EdgeKind::Exec);
+    }
+    // EVAL (eval, new Function, vm.*, require(dynamic), string timers)
+    if is_code_eval(call, &name) {
+        return Some(EdgeKind::CodeEval);
+    }
+    // STORAGE (local fs, path joins from input, S3/GCS/Azure blob SDKs)
+    if is_storage_call(code, call, &name) {
+        return Some(EdgeKind::Storage);
+    }
+    // Browser sinks: HTML injection, navigation, client-side storage
+    if name == "document.write" || name == "document.writeln" || name.ends_with(".insertadjacenthtml") {
+        return Some(EdgeKind::Xss);
+    }
+    if name.ends_with("location.assign") || name.ends_with("location.replace") || name == "window.open" {
+        return Some(EdgeKind::Redirect);
+    }
+    if name.ends_with("localstorage.setitem") || name.ends_with("sessionstorage.setitem") {
+        return Some(EdgeKind::ClientStorage);
+    }
+    // postMessage hands data to another window/origin → treat as a boundary crossing
+    if name.ends_with("postmessage") && !name.contains("port") {
+        return Some(EdgeKind::Net);
+    }
+    // QUEUE producers (kafkajs, SQS/SNS, BullMQ, amqplib, Nest ClientProxy)
+    if is_queue_producer(call, &name) {
+        return Some(EdgeKind::Queue);
+    }
+    // VALIDATE (zod/yup/joi schemas, class-validator, express-validator chains, Nest global ValidationPipe)
+    if is_validator_call(code, call, &name) {
+        return Some(EdgeKind::Validate);
+    }
+    // Other constructors are not sinks on their own
+    if call.kind() == "new_expression" {
+        return None;
+    }
     // NET
-    if name.starts_with("axios") || name.starts_with("fetch") || name.contains("httpservice")
+    if (name.starts_with("axios") && name != "axios.create") || name.starts_with("fetch") || name.contains("httpservice")
         || name.contains("got.") || name.contains("grpc.") {
         return Some(EdgeKind::Net);
     }
@@ -44,6 +127,13 @@ pub fn classify_call(code: &str, call: tree_sitter::Node) -> Option<EdgeKind> {
     {
         return Some(EdgeKind::Db);
     }
+    // AUTHZ (role/permission checks: CASL abilities, casbin, role guards)
+    if name.contains("ability.can") || name.contains("ability.cannot") || name.contains("throwunlesscan")
+        || name.contains("rolesguard") || name.contains("hasrole") || name.contains("haspermission")
+        || name.contains("enforcer.enforce") || name.contains("checkpolicies")
+    {
+        return Some(EdgeKind::Authz);
+    }
     // AUTH / CRYPTO / JWT / BCRYPT
     if name.contains("jwt") || name.contains("authguard") || name.contains("passport") {
         return Some(EdgeKind::Auth);
@@ -68,3 +158,522 @@ pub fn is_secretish(code: &str, n: tree_sitter::Node) -> bool {
     s.contains("process.env") || s.contains("configservice.get") || s.contains("secret")
         || s.contains("privatekey") || s.contains("apikey") || s.contains("token")
 }
+
+// Validation pipes on a handler or one of its parameters (@UsePipes(new ValidationPipe()), @Param('id', ParseIntPipe))
+pub fn is_validation_decorator(deco: &str) -> bool {
+    let d = deco.to_lowercase();
+    d.starts_with("@usepipes")
+        || (d.contains("pipe")
+            && ["@body", "@query", "@param", "@headers", "@payload", "@uploadedfile"].iter().any(|p| d.starts_with(p)))
+}
+
+// Role/permission decorators and guards (@Roles, @Permissions, @CheckPolicies, RolesGuard, PoliciesGuard)
+pub fn is_authz_decorator(deco: &str) -> bool {
+    let d = deco.to_lowercase();
+    d.starts_with("@roles") || d.starts_with("@permissions") || d.starts_with("@requirepermissions")
+        || d.starts_with("@checkpolicies") || d.starts_with("@hasrole") || d.starts_with("@authorize")
+        || d.contains("rolesguard") || d.contains("policiesguard") || d.contains("permissionsguard")
+        || d.contains("caslguard") || d.contains("abilityguard")
+}
+
+// Authentication decorators and guards (@UseGuards(AuthGuard('jwt')), @Auth(), JwtAuthGuard)
+pub fn is_authn_decorator(deco: &str) -> bool {
+    let d = deco.to_lowercase();
+    if d.starts_with("@authorize") { return false; }
+    d.contains("authguard") || d.contains("jwt") || d.contains("passport") || d.starts_with("@auth")
+        || (d.contains("useguards") && !is_authz_decorator(deco))
+}
+
+// Ownership checks: an `if` comparing the current user's id against a record owner field
+pub fn is_ownership_check(code: &str, if_stmt: tree_sitter::Node) -> bool {
+    let Some(cond) = if_stmt.child_by_field_name("condition") else { return false };
+    let c = code[cond.start_byte()..cond.end_byte()].to_lowercase();
+    let compares = c.contains("===") || c.contains("!==") || c.contains("==") || c.contains("!=");
+    let current_user = c.contains("user.id") || c.contains("user.sub") || c.contains("user.userid")
+        || c.contains("currentuser") || c.contains("userid");
+    let owner = c.contains("owner") || c.contains("borrowerid") || c.contains("createdby")
+        || c.contains(".userid") || c.contains("applicantid");
+    compares && current_user && owner
+}
+
+// DB write operations (create/update/upsert/delete/save/insert/remove) on a DB-tagged label
+pub fn is_db_write(label: &str) -> bool {
+    let l = label.to_lowercase();
+    [".create", ".update", ".upsert", ".delete", ".save", ".insert", ".remove", ".destroy", "$executeraw"]
+        .iter()
+        .any(|w| l.contains(w))
+}
+
+fn is_exec_call(name: &str) -> bool {
+    const BARE: &[&str] = &["exec", "execsync", "execfile", "execfilesync", "spawn", "spawnsync"];
+    const MODULES: &[&str] = &["child_process.", "childprocess.", "cp.", "shelljs.", "shell."];
+    BARE.contains(&name)
+        || MODULES.iter().any(|m| name.starts_with(m) || name.contains(&format!(".{m}")))
+        || name == "execa" || name.starts_with("execa.")
+}
+
+fn is_code_eval(call: tree_sitter::Node, name: &str) -> bool {
+    let first_arg = call
+        .child_by_field_name("arguments")
+        .and_then(|a| a.named_child(0));
+    let arg_is_literal = first_arg
+        .map(|a| a.kind() == "string" || (a.kind() == "template_string" && a.named_child_count() == 0))
+        .unwrap_or(true);
+    match name {
+        "eval" | "window.eval" | "globalthis.eval" | "global.eval" => true,
+        "function" => true,
+        "require" => !arg_is_literal,
+        "settimeout" | "setinterval" => first_arg.map(|a| a.kind() == "string" || a.kind() == "template_string").unwrap_or(false),
+        _ => {
+            name.starts_with("vm.")
+                && ["runincontext", "runinnewcontext", "runinthiscontext", "script", "compilefunction", "sourcetextmodule"]
+                    .iter()
+                    .any(|m| name.ends_with(m))
+        }
+    }
+}
+
+fn is_storage_call(code: &str, call: tree_sitter::Node, name: &str) -> bool {
+    const FS_MODULES: &[&str] = &["fs.", "fspromises.", "fsp.", "fse.", "fsextra."];
+    const FS_BARE: &[&str] = &[
+        "readfile", "readfilesync", "writefile", "writefilesync", "appendfile", "appendfilesync",
+        "createreadstream", "createwritestream", "unlink", "unlinksync", "copyfile", "readdir",
+    ];
+    const BLOB_OPS: &[&str] = &[
+        "putobject", "getobject", "deleteobject", "deleteobjects", "copyobject", "headobject", "listobjects",
+        "listobjectsv2", "upload", "uploaddata", "uploadfile", "download", "downloadtofile", "getsignedurl",
+        "createpresignedpost", "save", "delete", "deleteblob", "createreadstream", "createwritestream",
+    ];
+    if FS_MODULES.iter().any(|m| name.starts_with(m)) || FS_BARE.contains(&name) {
+        return true;
+    }
+    // path.join/resolve only matter when some segment is not a constant
+    if name == "path.join" || name == "path.resolve" {
+        let Some(args) = call.child_by_field_name("arguments") else { return false };
+        return (0..args.named_child_count()).filter_map(|i| args.named_child(i)).any(|a| {
+            let t = &code[a.start_byte()..a.end_byte()];
+            a.kind() != "string" && t != "__dirname" && t != "process.cwd()"
+        });
+    }
+    // AWS SDK v3 commands: new PutObjectCommand({ Bucket, Key })
+    if call.kind() == "new_expression" {
+        return name.ends_with("objectcommand") || name.ends_with("objectscommand") || name.ends_with("objectsv2command");
+    }
+    let op = name.rsplit('.').next().unwrap_or("");
+    let blob_client = name.contains("s3") || name.contains("bucket") || name.contains("blobclient")
+        || name.contains("containerclient") || name.contains("storage.");
+    blob_client && BLOB_OPS.contains(&op)
+}
+
+/// The bucket/key or path a storage call touches, e.g. "bucket='docs' key=`loans/${id}.pdf`" or "path=filePath".
+/// Looks at `{ Bucket, Key }` params, chained `.bucket(b).file(k)` / `getContainerClient(c).getBlobClient(k)`
+/// calls and finally the first argument.
+pub fn storage_target(code: &str, call: tree_sitter::Node) -> Option<String> {
+    let text = |n: tree_sitter::Node| snippet(code, n);
+    let args = call.child_by_field_name("arguments");
+    let first = args.and_then(|a| a.named_child(0));
+    let mut bucket = None;
+    let mut key = None;
+
+    if let Some(obj) = first.filter(|a| a.kind() == "object") {
+        for i in 0..obj.named_child_count() {
+            let pair = obj.named_child(i).unwrap();
+            let k = match pair.kind() {
+                "pair" => pair.child_by_field_name("key").map(text).unwrap_or_default(),
+                "shorthand_property_identifier" => text(pair),
+                _ => continue,
+            };
+            let v = pair.child_by_field_name("value").map(text).unwrap_or_else(|| k.clone());
+            match k.to_lowercase().as_str() {
+                "bucket" | "container" => bucket = Some(v),
+                "key" | "prefix" | "blobname" => key = Some(v),
+                _ => {}
+            }
+        }
+    }
+
+    // Walk the callee chain for storage.bucket(b).file(k) and Azure container/blob clients
+    let mut callee = call.child_by_field_name("function");
+    while let Some(f) = callee {
+        let inner = if f.kind() == "member_expression" { f.child_by_field_name("object") } else { None };
+        if let Some(c) = inner.filter(|c| c.kind() == "call_expression") {
+            let method = c.child_by_field_name("function")
+                .and_then(|m| m.child_by_field_name("property"))
+                .map(text)
+                .unwrap_or_default()
+                .to_lowercase();
+            let arg = c.child_by_field_name("arguments").and_then(|a| a.named_child(0)).map(text);
+            match method.as_str() {
+                "bucket" | "getcontainerclient" => bucket = bucket.or(arg),
+                "file" | "getblobclient" | "getblockblobclient" => key = key.or(arg),
+                _ => {}
+            }
+            callee = c.child_by_field_name("function");
+        } else {
+            callee = inner;
+        }
+    }
+
+    match (bucket, key) {
+        (None, None) => {
+            let a = args?;
+            let parts: Vec<String> = (0..a.named_child_count()).filter_map(|i| a.named_child(i)).map(text).collect();
+            let name = call_name(code, call).unwrap_or_default();
+            // path.join(a, b) → the whole join; fs.readFile(p, 'utf8') → just the path
+            if name == "path.join" || name == "path.resolve" { Some(format!("path={}", parts.join(", "))) }
+            else { parts.first().map(|p| format!("path={}", p)) }
+        }
+        (b, k) => Some(
+            [b.map(|b| format!("bucket={}", b)), k.map(|k| format!("key={}", k))]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+    }
+}
+
+fn is_validator_call(code: &str, call: tree_sitter::Node, name: &str) -> bool {
+    const SCHEMA_OPS: &[&str] = &[
+        "parse", "parseasync", "safeparse", "safeparseasync", "validate", "validateasync", "validatesync",
+        "isvalid", "isvalidsync",
+    ];
+    // class-validator / class-transformer-validator / express-validator functions called on their own
+    const BARE: &[&str] = &["validate", "validateorreject", "validatesync", "transformandvalidate", "validationresult", "checkschema"];
+    const CHAIN_ROOTS: &[&str] = &["body(", "param(", "query(", "check(", "header(", "cookie("];
+    if call.kind() != "call_expression" { return false; }
+    if BARE.contains(&name) { return true; }
+    let (recv, op) = name.rsplit_once('.').unwrap_or(("", name));
+    if op == "useglobalpipes" {
+        return call.child_by_field_name("arguments")
+            .is_some_and(|a| code[a.start_byte()..a.end_byte()].to_lowercase().contains("validationpipe"));
+    }
+    // schema.parse(body), z.object({..}).safeParse(x), yup.object().validate(x), Joi.attempt(x, schema)
+    if SCHEMA_OPS.contains(&op) {
+        return recv.contains("schema") || recv.contains("validator")
+            || ["z.", "yup.", "joi."].iter().any(|p| recv.starts_with(p));
+    }
+    if recv == "joi" { return op == "attempt" || op == "assert"; }
+    // validator.js: validator.isEmail(x)
+    if recv == "validator" { return op.starts_with("is"); }
+    // express-validator: body('email').isEmail().normalizeEmail() is tagged once, at its outermost call
+    if CHAIN_ROOTS.iter().any(|r| recv.starts_with(r)) {
+        let chained = call.parent()
+            .filter(|p| p.kind() == "member_expression")
+            .and_then(|p| p.parent())
+            .is_some_and(|g| g.kind() == "call_expression");
+        return !chained;
+    }
+    false
+}
+
+fn queue_parts(name: &str) -> (&str, &str) {
+    name.rsplit_once('.').unwrap_or(("", name))
+}
+
+fn is_queue_producer(call: tree_sitter::Node, name: &str) -> bool {
+    if call.kind() == "new_expression" {
+        return name.ends_with("sendmessagecommand") || name.ends_with("sendmessagebatchcommand")
+            || name.ends_with("publishcommand") || name.ends_with("publishbatchcommand");
+    }
+    let (obj, op) = queue_parts(name);
+    match op {
+        "send" | "sendbatch" if obj.contains("producer") => true,
+        "sendmessage" | "sendmessagebatch" | "publish" if obj.contains("sqs") || obj.contains("sns") => true,
+        "add" | "addbulk" if obj.contains("queue") => true,
+        "sendtoqueue" | "publish" if obj.contains("channel") || obj.contains("amqp") || obj.contains("rabbit") => true,
+        "emit" | "send" if obj.contains("client") && !obj.contains("http") => true,
+        _ => false,
+    }
+}
+
+/// Consumer registrations (kafka subscribe/run, amqplib consume, BullMQ Worker, sqs-consumer, ReceiveMessage)
+pub fn is_queue_consumer(code: &str, call: tree_sitter::Node) -> bool {
+    let name = call_name(code, call).unwrap_or_default().to_lowercase();
+    if call.kind() == "new_expression" {
+        return name == "worker" || name.ends_with(".worker") || name.ends_with("receivemessagecommand");
+    }
+    let (obj, op) = queue_parts(&name);
+    match op {
+        "subscribe" | "run" => obj.contains("consumer"),
+        "consume" => obj.contains("channel") || obj.contains("amqp") || obj.contains("rabbit"),
+        "receivemessage" => obj.contains("sqs"),
+        "create" => obj == "consumer",
+        _ => false,
+    }
+}
+
+// Nest microservice / queue handler decorators
+pub fn is_queue_handler_decorator(deco: &str) -> bool {
+    let d = deco.to_lowercase();
+    ["@messagepattern", "@eventpattern", "@processor", "@process", "@sqsmessagehandler", "@rabbitsubscribe"]
+        .iter()
+        .any(|p| d.starts_with(p))
+}
+
+/// String-literal topic/queue names a producer, consumer or handler decorator refers to.
+/// Looks at a literal first argument (`sendToQueue('q')`, `@EventPattern('loan.created')`) and at
+/// `topic`/`topics`/`queue`/`QueueUrl`/`TopicArn` properties of an object argument.
+pub fn queue_topics(code: &str, call: tree_sitter::Node) -> Vec<String> {
+    let mut out = Vec::new();
+    let Some(args) = call.child_by_field_name("arguments") else { return out };
+    let literal = |n: tree_sitter::Node, out: &mut Vec<String>| {
+        if n.kind() == "string" {
+            out.push(code[n.start_byte()..n.end_byte()].trim_matches(|c| c == '\'' || c == '"').to_string());
+        } else if n.kind() == "array" {
+            for i in 0..n.named_child_count() {
+                let e = n.named_child(i).unwrap();
+                if e.kind() == "string" {
+                    out.push(code[e.start_byte()..e.end_byte()].trim_matches(|c| c == '\'' || c == '"').to_string());
+                }
+            }
+        }
+    };
+    let Some(first) = args.named_child(0) else { return out };
+    if first.kind() == "object" {
+        for i in 0..first.named_child_count() {
+            let pair = first.named_child(i).unwrap();
+            if pair.kind() != "pair" { continue; }
+            let key = pair.child_by_field_name("key").map(|k| snippet(code, k).to_lowercase()).unwrap_or_default();
+            if ["topic", "topics", "queue", "queueurl", "topicarn", "name"].contains(&key.as_str())
+                && let Some(v) = pair.child_by_field_name("value")
+            {
+                literal(v, &mut out);
+            }
+        }
+    } else {
+        literal(first, &mut out);
+    }
+    out
+}
+
+// Assignment sinks: `el.innerHTML = x`, `window.location = x`, `document.cookie = x`, `localStorage.token = x`
+pub fn classify_assignment(code: &str, assign: tree_sitter::Node) -> Option<EdgeKind> {
+    let left = assign.child_by_field_name("left")?;
+    let l = code[left.start_byte()..left.end_byte()].to_lowercase().replace(' ', "");
+    if l.ends_with(".innerhtml") || l.ends_with(".outerhtml") {
+        return Some(EdgeKind::Xss);
+    }
+    // only the browser's location, not a `location` property on some backend object
+    let nav = l.strip_suffix(".href").unwrap_or(&l);
+    if ["location", "window.location", "document.location", "self.location", "top.location"].contains(&nav) {
+        return Some(EdgeKind::Redirect);
+    }
+    if l == "document.cookie" || l.starts_with("localstorage") || l.starts_with("sessionstorage")
+        || l.starts_with("window.localstorage") || l.starts_with("window.sessionstorage")
+    {
+        return Some(EdgeKind::ClientStorage);
+    }
+    None
+}
+
+// JSX attribute sinks: `dangerouslySetInnerHTML={{ __html: x }}`
+pub fn is_jsx_html_sink(code: &str, attr: tree_sitter::Node) -> bool {
+    attr.named_child(0)
+        .map(|n| &code[n.start_byte()..n.end_byte()] == "dangerouslySetInnerHTML")
+        .unwrap_or(false)
+}
+
+/// Name of the function containing `n`, matching the CFG keys ("Class.method", "fn", "handler"), or "<module>".
+pub fn enclosing_function(code: &str, n: tree_sitter::Node) -> String {
+    let text = |x: tree_sitter::Node| code[x.start_byte()..x.end_byte()].to_string();
+    let class_of = |member: tree_sitter::Node| {
+        member.parent()
+            .and_then(|body| body.parent())
+            .and_then(|c| c.child_by_field_name("name"))
+            .map(text)
+            .unwrap_or_else(|| "<anon_class>".to_string())
+    };
+    let mut cur = n.parent();
+    while let Some(p) = cur {
+        match p.kind() {
+            "method_definition" => {
+                if let Some(name) = p.child_by_field_name("name") {
+                    return format!("{}.{}", class_of(p), text(name));
+                }
+            }
+            "function_declaration" => {
+                if let Some(name) = p.child_by_field_name("name") { return text(name); }
+            }
+            "arrow_function" | "function_expression" | "function" => {
+                if let Some(parent) = p.parent() {
+                    match parent.kind() {
+                        "variable_declarator" => {
+                            if let Some(name) = parent.child_by_field_name("name") { return text(name); }
+                        }
+                        "public_field_definition" => {
+                            if let Some(name) = parent.child_by_field_name("name") {
+                                return format!("{}.{}", class_of(parent), text(name));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+        cur = p.parent();
+    }
+    "<module>".to_string()
+}
+
+/// A third-party SDK: the vendor, the import sources it ships as and the client classes code holds instances of.
+/// Sources ending in `*` match by prefix, sources wrapped in `*..*` match anywhere (in-house bureau wrappers).
+pub struct Sdk {
+    pub vendor: &'static str,
+    pub sources: &'static [&'static str],
+    pub constructors: &'static [&'static str],
+}
+
+pub const SDK_CATALOGUE: &[Sdk] = &[
+    Sdk { vendor: "stripe", sources: &["stripe"], constructors: &["Stripe"] },
+    Sdk { vendor: "plaid", sources: &["plaid"], constructors: &["PlaidApi", "PlaidClient"] },
+    Sdk { vendor: "twilio", sources: &["twilio"], constructors: &["Twilio"] },
+    Sdk { vendor: "sendgrid", sources: &["@sendgrid/mail", "@sendgrid/client"], constructors: &["MailService"] },
+    Sdk { vendor: "experian", sources: &["*experian*"], constructors: &["ExperianClient", "ExperianService", "ExperianApi"] },
+    Sdk { vendor: "equifax", sources: &["*equifax*"], constructors: &["EquifaxClient", "EquifaxService", "EquifaxApi"] },
+    Sdk { vendor: "docusign", sources: &["docusign-esign"], constructors: &["EnvelopesApi", "TemplatesApi"] },
+    Sdk { vendor: "aws", sources: &["@aws-sdk/client-*", "@aws-sdk/lib-*"], constructors: &[] },
+];
+
+fn sdk_for_source(source: &str) -> Option<&'static Sdk> {
+    let s = source.to_lowercase();
+    SDK_CATALOGUE.iter().find(|sdk| {
+        sdk.sources.iter().any(|p| match (p.strip_prefix('*'), p.strip_suffix('*')) {
+            (Some(inner), Some(_)) => s.contains(inner.trim_end_matches('*')),
+            (None, Some(prefix)) => s.starts_with(prefix),
+            _ => s == *p,
+        })
+    })
+}
+
+/// A binding that refers to a vendor SDK: an import (`sgMail`), or a client instance (`this.plaid`, `s3`)
+#[derive(Debug, Clone)]
+pub struct SdkClient {
+    pub name: String,
+    pub vendor: &'static str,
+    /// AWS service from `@aws-sdk/client-<service>`
+    pub service: Option<String>,
+    /// True for imported modules/classes, false for instances built from them
+    pub import: bool,
+}
+
+/// Vendor SDK bindings in a file: imports/requires from catalogued sources, instances created from them
+/// (`new Stripe(key)`, `twilio(sid, token)`) and injected clients typed with a catalogued class.
+pub fn sdk_clients(code: &str, root: tree_sitter::Node) -> Vec<SdkClient> {
+    let text = |n: tree_sitter::Node| code[n.start_byte()..n.end_byte()].to_string();
+    let unquote = |n: tree_sitter::Node| text(n).trim_matches(|c| c == '\'' || c == '"' || c == '`').to_string();
+    let service = |source: &str| source.strip_prefix("@aws-sdk/client-").map(str::to_string);
+    let mut out: Vec<SdkClient> = Vec::new();
+
+    // imports first, so instances below can refer to them
+    let mut stack = vec![root];
+    while let Some(n) = stack.pop() {
+        for i in 0..n.named_child_count() { stack.push(n.named_child(i).unwrap()); }
+        let (source, bindings) = match n.kind() {
+            "import_statement" => {
+                let Some(src) = n.child_by_field_name("source") else { continue };
+                let mut names = vec![];
+                let mut q = vec![n];
+                while let Some(x) = q.pop() {
+                    match x.kind() {
+                        "import_specifier" => names.extend(x.child_by_field_name("alias").or_else(|| x.child_by_field_name("name")).map(text)),
+                        "identifier" => names.push(text(x)),
+                        "string" => {}
+                        _ => for i in 0..x.named_child_count() { q.push(x.named_child(i).unwrap()); },
+                    }
+                }
+                (unquote(src), names)
+            }
+            "variable_declarator" => {
+                let value = n.child_by_field_name("value").filter(|v| v.kind() == "call_expression");
+                let Some(src) = value
+                    .filter(|v| v.child_by_field_name("function").is_some_and(|f| text(f) == "require"))
+                    .and_then(|v| v.child_by_field_name("arguments"))
+                    .and_then(|a| a.named_child(0))
+                    .filter(|a| a.kind() == "string")
+                else { continue };
+                let Some(pat) = n.child_by_field_name("name") else { continue };
+                let names = if pat.kind() == "identifier" { vec![text(pat)] } else {
+                    (0..pat.named_child_count()).filter_map(|i| pat.named_child(i)).map(|p| {
+                        p.child_by_field_name("value").map(text).unwrap_or_else(|| text(p))
+                    }).collect()
+                };
+                (unquote(src), names)
+            }
+            _ => continue,
+        };
+        if let Some(sdk) = sdk_for_source(&source) {
+            for name in bindings {
+                out.push(SdkClient { name, vendor: sdk.vendor, service: service(&source), import: true });
+            }
+        }
+    }
+
+    let class_of = |ty: &str| -> Option<SdkClient> {
+        let ty = ty.trim_start_matches(':').trim();
+        if let Some(c) = out.iter().find(|c| c.import && c.name == ty) { return Some(c.clone()); }
+        SDK_CATALOGUE.iter()
+            .find(|s| s.constructors.contains(&ty))
+            .map(|s| SdkClient { name: ty.to_string(), vendor: s.vendor, service: None, import: true })
+    };
+    let mut instances = Vec::new();
+    let mut stack = vec![root];
+    while let Some(n) = stack.pop() {
+        for i in 0..n.named_child_count() { stack.push(n.named_child(i).unwrap()); }
+        let found = match n.kind() {
+            // new Stripe(key) / twilio(sid, token) assigned to a variable, a field or this.x
+            "new_expression" | "call_expression" => {
+                let ctor = n.child_by_field_name("constructor").or_else(|| n.child_by_field_name("function")).map(text);
+                let Some(client) = ctor.and_then(|c| class_of(&c)) else { continue };
+                let Some(parent) = n.parent() else { continue };
+                let name = match parent.kind() {
+                    "variable_declarator" => parent.child_by_field_name("name").filter(|p| p.kind() == "identifier").map(text),
+                    "assignment_expression" => parent.child_by_field_name("left").map(text),
+                    "public_field_definition" => parent.child_by_field_name("name").map(|x| format!("this.{}", text(x))),
+                    _ => None,
+                };
+                name.map(|name| SdkClient { name, import: false, ..client })
+            }
+            // constructor(private readonly plaid: PlaidApi) / private stripe: Stripe;
+            "required_parameter" | "optional_parameter" | "public_field_definition" => {
+                let is_field = n.kind() == "public_field_definition"
+                    || (0..n.child_count()).filter_map(|i| n.child(i)).any(|c| c.kind() == "accessibility_modifier" || text(c) == "readonly");
+                let name = n.child_by_field_name("pattern").or_else(|| n.child_by_field_name("name")).map(text);
+                let ty = n.child_by_field_name("type").map(text);
+                match (is_field, name, ty.and_then(|t| class_of(&t))) {
+                    (true, Some(name), Some(client)) => Some(SdkClient { name: format!("this.{}", name), import: false, ..client }),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        instances.extend(found);
+    }
+    out.extend(instances);
+    out
+}
+
+/// Vendor and operation of a call made through an SDK binding: `this.plaid.transactionsGet(..)` →
+/// ("plaid", "transactionsGet"), `stripe.paymentIntents.create(..)` → ("stripe", "paymentIntents.create"),
+/// `s3.send(new PutObjectCommand(..))` → ("aws", "s3.PutObject").
+pub fn sdk_call(code: &str, call: tree_sitter::Node, clients: &[SdkClient]) -> Option<(&'static str, String)> {
+    let name = call_name(code, call)?;
+    // prefer the longest binding: `this.plaid` over a `this` that happens to match nothing
+    let client = clients.iter()
+        .filter(|c| name.strip_prefix(c.name.as_str()).is_some_and(|r| r.starts_with('.')))
+        .max_by_key(|c| (!c.import, c.name.len()))?;
+    let op = name[client.name.len() + 1..].to_string();
+    if client.vendor == "aws" && op == "send" {
+        // `send(new PutObjectCommand(..))`, or `send(cmd)` with `const cmd = new PutObjectCommand(..)`
+        let arg = call.child_by_field_name("arguments").and_then(|a| a.named_child(0));
+        let arg = arg.and_then(|a| if a.kind() == "identifier" { crate::embedded::resolve_const(code, a, &mut Vec::new()) } else { Some(a) });
+        let cmd = arg
+            .filter(|a| a.kind() == "new_expression")
+            .and_then(|a| a.child_by_field_name("constructor"))
+            .map(|c| snippet(code, c));
+        let cmd = cmd.as_deref().map_or(op.as_str(), |c| c.strip_suffix("Command").unwrap_or(c));
+        return Some(("aws", match &client.service { Some(s) => format!("{}.{}", s, cmd), None => cmd.to_string() }));
+    }
+    Some((client.vendor, op))
+}
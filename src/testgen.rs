@@ -0,0 +1,554 @@
+use std::collections::{BTreeMap, BTreeSet};
+use tree_sitter::Node;
+
+use crate::conditions::{items, Boundary};
+use crate::fixtures::{prisma_shape, PrismaSchema, Shape};
+use crate::paths::{CfgPath, FunctionPaths};
+use crate::{Param, SimpleCfg};
+
+/// One binding a file imports: `axios` from 'axios', `sgMail` from '@sendgrid/mail'
+#[derive(Debug, Clone)]
+pub struct Import {
+    pub local: String,
+    pub source: String,
+    /// How to import it again in a spec: `axios`, `* as sgMail`, `{ S3Client }`, `{ default as x }`
+    pub spec: String,
+}
+
+/// Value imports of a file (type-only imports are skipped; a spec can't mock those)
+pub fn collect_imports(code: &str, root: Node) -> Vec<Import> {
+    let text = |n: Node| code[n.start_byte()..n.end_byte()].to_string();
+    let mut out = Vec::new();
+    for i in 0..root.named_child_count() {
+        let n = root.named_child(i).unwrap();
+        if n.kind() != "import_statement" || text(n).starts_with("import type") { continue; }
+        let Some(src) = n.child_by_field_name("source") else { continue };
+        let source = text(src).trim_matches(|c| c == '\'' || c == '"').to_string();
+        let Some(clause) = (0..n.named_child_count()).filter_map(|j| n.named_child(j)).find(|c| c.kind() == "import_clause") else { continue };
+        for j in 0..clause.named_child_count() {
+            let c = clause.named_child(j).unwrap();
+            match c.kind() {
+                "identifier" => out.push(Import { local: text(c), source: source.clone(), spec: text(c) }),
+                "namespace_import" => {
+                    if let Some(id) = c.named_child(0) {
+                        out.push(Import { local: text(id), source: source.clone(), spec: format!("* as {}", text(id)) });
+                    }
+                }
+                "named_imports" => {
+                    for k in 0..c.named_child_count() {
+                        let s = c.named_child(k).unwrap();
+                        let local = s.child_by_field_name("alias").or_else(|| s.child_by_field_name("name"));
+                        if s.kind() == "import_specifier" && !text(s).starts_with("type ")
+                            && let Some(local) = local
+                        {
+                            out.push(Import { local: text(local), source: source.clone(), spec: format!("{{ {} }}", text(s)) });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    out
+}
+
+/// Which runner the skeletons target; decides `jest.fn()` vs `vi.fn()` and the vitest imports
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Framework {
+    Jest,
+    Vitest,
+}
+
+impl Framework {
+    fn ns(self) -> &'static str {
+        match self { Framework::Jest => "jest", Framework::Vitest => "vi" }
+    }
+}
+
+/// A generated spec: a header block, then one describe per function
+pub struct Spec {
+    pub header: String,
+    pub describes: Vec<Describe>,
+    /// Fixture factories the spec imports (`loanFixture`, `axiosResponse`)
+    pub fixtures: BTreeSet<String>,
+}
+
+/// Tests for one function. `setup` is regenerated on every run; tests are only ever added.
+pub struct Describe {
+    pub func: String,
+    pub setup: String,
+    pub tests: Vec<(String, String)>,
+}
+
+// A NET/DB/LOG callee and how the spec stands it in
+enum Mock {
+    // injected `this.<dep>...`: a literal object of jest.fn()s handed to the constructor
+    Injected { dep: String, members: Vec<String> },
+    // imported module: jest.mock('<source>')
+    Module { import: Import },
+    // console.log / console.error: spied on
+    Console { method: String },
+    // fetch and other globals
+    Global { name: String },
+    // anything else (module-level instances, locals) is left to the author
+    Unknown,
+}
+
+fn is_ident(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$') && !s.starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn mock_for(name: &str, imports: &[Import]) -> Mock {
+    let parts: Vec<&str> = name.split('.').collect();
+    if !parts.iter().enumerate().all(|(i, p)| is_ident(p) || (i == 0 && *p == "this")) {
+        return Mock::Unknown;
+    }
+    match parts.as_slice() {
+        ["this", dep, members @ ..] => Mock::Injected { dep: dep.to_string(), members: members.iter().map(|m| m.to_string()).collect() },
+        ["console", method] => Mock::Console { method: method.to_string() },
+        [root, ..] if imports.iter().any(|i| i.local == *root) => {
+            Mock::Module { import: imports.iter().find(|i| i.local == *root).unwrap().clone() }
+        }
+        [global] => Mock::Global { name: global.to_string() },
+        _ => Mock::Unknown,
+    }
+}
+
+// Tagged NET/DB/LOG calls of a function with the mock standing in for each: (nodes, callee, mock)
+fn mocked_calls(cfg: &SimpleCfg, imports: &[Import]) -> Vec<(Vec<usize>, String, Mock)> {
+    let mut by_name: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (node, name) in &cfg.calls {
+        let label = &cfg.nodes[*node];
+        if ["NET:", "DB:", "LOG:"].iter().any(|p| label.starts_with(p)) {
+            by_name.entry(name).or_default().push(*node);
+        }
+    }
+    by_name.into_iter().map(|(name, nodes)| (nodes, name.to_string(), mock_for(name, imports))).collect()
+}
+
+// What a mocked callee resolves to: a Prisma model from the schema, or an axios response
+fn shape_of<'s>(name: &str, mock: &Mock, schema: &'s PrismaSchema) -> Option<Shape<'s>> {
+    match mock {
+        Mock::Module { import } if import.source == "axios" => Some(Shape::Axios),
+        Mock::Injected { .. } | Mock::Module { .. } => prisma_shape(name, schema),
+        _ => None,
+    }
+}
+
+// How a spec sets what a mock returns: injected mocks are jest.fn()s already, imports need mocked()
+fn mock_target(r: &str, mock: &Mock, fw: Framework) -> String {
+    match mock {
+        Mock::Injected { .. } => r.to_string(),
+        _ => format!("{}.mocked({})", fw.ns(), r),
+    }
+}
+
+// How a spec refers to the mocked callee in assertions: `prisma.loan.update`, `axios.post`
+fn mock_ref(name: &str, mock: &Mock) -> Option<String> {
+    match mock {
+        Mock::Injected { .. } => Some(name.trim_start_matches("this.").to_string()),
+        Mock::Module { .. } | Mock::Console { .. } | Mock::Global { .. } => Some(name.to_string()),
+        Mock::Unknown => None,
+    }
+}
+
+// { loan: { update: jest.fn() } } from the member chains called on one dependency
+fn mock_object(members: &BTreeSet<Vec<String>>, fw: Framework) -> String {
+    if members.iter().all(|m| m.is_empty()) {
+        return format!("{}.fn()", fw.ns());
+    }
+    let mut groups: BTreeMap<&str, BTreeSet<Vec<String>>> = BTreeMap::new();
+    for m in members.iter().filter(|m| !m.is_empty()) {
+        groups.entry(&m[0]).or_default().insert(m[1..].to_vec());
+    }
+    let fields: Vec<String> = groups.iter().map(|(k, rest)| format!("{}: {}", k, mock_object(rest, fw))).collect();
+    format!("{{ {} }}", fields.join(", "))
+}
+
+/// Test name for a path, from its branch conditions: `loan.amount > limit, !(loan.status === 'PENDING')`;
+/// a loop reads as `const doc of docs (×2)`
+pub fn path_name(path: &CfgPath) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let mut loops_seen = BTreeSet::new();
+    for d in &path.decisions {
+        if let Some(l) = path.loops.iter().find(|l| l.node == d.node) {
+            if loops_seen.insert(d.node) { parts.push(format!("{} (×{})", d.condition, l.iterations)); }
+        } else if d.taken {
+            parts.push(d.condition.clone());
+        } else {
+            parts.push(negate(&d.condition));
+        }
+    }
+    if parts.is_empty() { "runs straight through".to_string() } else { parts.join(", ") }
+}
+
+// A condition turned around: `a > b` → `!(a > b)`, but `!active` → `active` and `done` → `!done`
+pub(crate) fn negate(cond: &str) -> String {
+    let simple = |s: &str| s.chars().all(|c| c.is_alphanumeric() || "_$.".contains(c));
+    match cond.strip_prefix('!') {
+        Some(rest) if simple(rest) => rest.to_string(),
+        _ if simple(cond) => format!("!{}", cond),
+        _ => format!("!({})", cond),
+    }
+}
+
+// A JS string literal; double quotes when the text has single quotes of its own ('PENDING')
+pub(crate) fn quote(s: &str) -> String {
+    let s = s.replace('\\', "\\\\");
+    if s.contains('\'') && !s.contains('"') { format!("\"{}\"", s) } else { format!("'{}'", s.replace('\'', "\\'")) }
+}
+
+// `LoanService` → `loanService`
+pub(crate) fn lower_first(s: &str) -> String {
+    let mut c = s.chars();
+    c.next().map(|f| f.to_lowercase().chain(c).collect()).unwrap_or_default()
+}
+
+/// Build the spec for one source file. `module` is how the spec imports it (`./loan.service`) and
+/// `fixtures` how it imports casesmith.fixtures; constructors, anonymous functions and functions without
+/// paths get no tests. Prisma calls (for models in `schema`) and axios calls resolve to fixtures, and a
+/// path whose branches test those results gets the values that steer it.
+#[allow(clippy::too_many_arguments)]
+pub fn render_spec(
+    file_name: &str,
+    module: &str,
+    imports: &[Import],
+    cfgs: &BTreeMap<String, SimpleCfg>,
+    paths: &BTreeMap<String, FunctionPaths>,
+    schema: &PrismaSchema,
+    fixtures: &str,
+    fw: Framework,
+) -> Spec {
+    let ns = fw.ns();
+    let mut subjects = BTreeSet::new();
+    let mut factories = BTreeSet::new();
+    let mut module_mocks: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut describes = Vec::new();
+
+    for (func, cfg) in cfgs {
+        let (class, method) = match func.split_once('.') {
+            Some((c, m)) => (Some(c), m),
+            None => (None, func.as_str()),
+        };
+        if method == "constructor" || !is_ident(method) || !class.is_none_or(is_ident) { continue; }
+        let Some(fp) = paths.get(func).filter(|p| !p.simple.is_empty()) else { continue };
+        subjects.insert(class.unwrap_or(method).to_string());
+
+        let calls = mocked_calls(cfg, imports);
+        let mut injected: BTreeMap<&str, BTreeSet<Vec<String>>> = BTreeMap::new();
+        let mut spies = Vec::new();
+        let mut todos = Vec::new();
+        for (_, name, mock) in &calls {
+            match mock {
+                Mock::Injected { dep, members } => { injected.entry(dep).or_default().insert(members.clone()); }
+                Mock::Module { import } => {
+                    module_mocks.entry(import.source.clone()).or_default().insert(import.spec.clone());
+                }
+                Mock::Console { method } => spies.push(format!("{}.spyOn(console, '{}').mockImplementation(() => {{}});", ns, method)),
+                Mock::Global { name } => spies.push(match fw {
+                    Framework::Jest => format!("global.{} = jest.fn() as any;", name),
+                    Framework::Vitest => format!("vi.stubGlobal('{}', vi.fn());", name),
+                }),
+                Mock::Unknown => todos.push(format!("// TODO: mock {} (not an import or an injected dependency)", name)),
+            }
+        }
+        spies.sort();
+        spies.dedup();
+
+        // setup: mocks for injected dependencies, a fresh subject per test
+        let mut setup = vec![format!("  // casesmith:begin setup {}", func)];
+        for (dep, members) in &injected {
+            setup.push(format!("  const {} = {};", dep, mock_object(members, fw)));
+        }
+        setup.extend(todos.iter().map(|t| format!("  {}", t)));
+        let subject = class.map(lower_first);
+        if let (Some(class), Some(subject)) = (class, &subject) {
+            setup.push(format!("  let {}: {};", subject, class));
+        }
+        setup.push(String::new());
+        setup.push("  beforeEach(() => {".to_string());
+        setup.push(format!("    {}.clearAllMocks();", ns));
+        if let (Some(class), Some(subject)) = (class, &subject) {
+            // injected dependencies go to the constructor by parameter name; the rest are set on the instance
+            let ctor: Vec<String> = cfgs.get(&format!("{}.constructor", class)).map(|c| c.params.iter().map(|p| p.name.clone()).collect()).unwrap_or_default();
+            let args: Vec<String> = ctor.iter().map(|p| if injected.contains_key(p.as_str()) { format!("{} as any", p) } else { "{} as any".to_string() }).collect();
+            setup.push(format!("    {} = new {}({});", subject, class, args.join(", ")));
+            for dep in injected.keys().filter(|d| !ctor.iter().any(|p| p == *d)) {
+                setup.push(format!("    ({} as any).{} = {};", subject, dep, dep));
+            }
+        }
+        setup.extend(spies.iter().map(|s| format!("    {}", s)));
+        // mocked DB/HTTP calls hand back a record or response unless a test says otherwise
+        for (_, name, mock) in &calls {
+            let (Some(r), Some(shape)) = (mock_ref(name, mock), shape_of(name, mock, schema)) else { continue };
+            factories.extend(shape.factory());
+            setup.push(format!("    {}.mockResolvedValue({});", mock_target(&r, mock, fw), shape.default_value()));
+        }
+        setup.push("  });".to_string());
+        setup.push(format!("  // casesmith:end setup {}", func));
+
+        let args: Vec<String> = cfg.params.iter().enumerate()
+            .map(|(i, p)| if is_ident(&p.name) { p.name.clone() } else { format!("arg{}", i) })
+            .collect();
+        let call = match &subject {
+            Some(s) => format!("{}.{}({})", s, method, args.join(", ")),
+            None => format!("{}({})", method, args.join(", ")),
+        };
+
+        let mut names = BTreeMap::<String, usize>::new();
+        let mut tests = Vec::new();
+        for path in &fp.simple {
+            let mut name = path_name(path);
+            let seen = names.entry(name.clone()).or_default();
+            *seen += 1;
+            if *seen > 1 { name = format!("{} #{}", name, seen); }
+
+            let mut body = vec![format!("  it({}, async () => {{", quote(&name))];
+            for d in &path.decisions {
+                if path.loops.iter().any(|l| l.node == d.node) { continue; }
+                body.push(format!("    // arrange: {} is {}", d.condition, d.taken));
+            }
+            for l in &path.loops {
+                let cond = path.decisions.iter().find(|d| d.node == l.node).map(|d| d.condition.as_str()).unwrap_or("loop");
+                body.push(format!("    // arrange: {} runs {} time{}", cond, l.iterations, if l.iterations == 1 { "" } else { "s" }));
+            }
+            body.extend(arrange(path, &args, &cfg.params));
+            for (line, factory) in steer(path, cfg, &calls, &args, schema, fw) {
+                body.push(line);
+                factories.extend(factory);
+            }
+            let throws = path.nodes.iter().rev().nth(1).is_some_and(|&n| cfg.nodes[n].starts_with("Throw:"));
+            if throws {
+                // sync or async, a throw ends up as a rejection
+                body.push(format!("    await expect(Promise.resolve().then(() => {})).rejects.toThrow();", call));
+            } else {
+                body.push(format!("    await {};", call));
+                body.push("    // TODO: assert on the result".to_string());
+            }
+            for (nodes, name, mock) in &calls {
+                let Some(r) = mock_ref(name, mock) else { continue };
+                let hit = nodes.iter().any(|n| path.nodes.contains(n));
+                body.push(format!("    expect({}){}.toHaveBeenCalled();", r, if hit { "" } else { ".not" }));
+            }
+            body.push("  });".to_string());
+            tests.push((name, body.join("\n")));
+        }
+        describes.push(Describe { func: func.clone(), setup: setup.join("\n"), tests });
+    }
+
+    let mut header = vec![
+        "// casesmith:begin header".to_string(),
+        format!("// Generated by `casesmith tests` from {}. Fill in the tests as you like: a rerun only", file_name),
+        "// refreshes the casesmith:begin/end blocks and adds tests for paths that are new.".to_string(),
+    ];
+    if fw == Framework::Vitest {
+        header.push("import { beforeEach, describe, expect, it, vi } from 'vitest';".to_string());
+    }
+    for (source, specs) in &module_mocks {
+        for spec in specs {
+            header.push(format!("import {} from '{}';", spec, source));
+        }
+    }
+    if !subjects.is_empty() {
+        header.push(format!("import {{ {} }} from '{}';", subjects.into_iter().collect::<Vec<_>>().join(", "), module));
+    }
+    if !factories.is_empty() {
+        header.push(format!("import {{ {} }} from '{}';", factories.iter().cloned().collect::<Vec<_>>().join(", "), fixtures));
+    }
+    // fixture overrides for Decimal columns (`loanFixture({ amount: new Prisma.Decimal(10001) })`)
+    let uses_prisma = describes.iter().any(|d| d.setup.contains("Prisma.") || d.tests.iter().any(|(_, body)| body.contains("Prisma.")));
+    if uses_prisma && !header.iter().any(|l| l.starts_with("import { Prisma }")) {
+        header.push("import { Prisma } from '@prisma/client';".to_string());
+    }
+    if !module_mocks.is_empty() { header.push(String::new()); }
+    for source in module_mocks.keys() {
+        header.push(format!("{}.mock('{}');", ns, source));
+    }
+    header.push("// casesmith:end header".to_string());
+    Spec { header: header.join("\n"), describes, fixtures: factories }
+}
+
+// Resolved values for mocked calls whose results a path's branches test: `if (!loan)` after
+// `const loan = await prisma.loan.findUnique(..)` makes it resolve to null. Values may refer to the
+// arguments `arrange` declared; anything else is left to the author. Returns each line with the
+// factory it uses.
+fn steer(path: &CfgPath, cfg: &SimpleCfg, calls: &[(Vec<usize>, String, Mock)], args: &[String], schema: &PrismaSchema, fw: Framework) -> Vec<(String, Option<String>)> {
+    let mut out = Vec::new();
+    let mut done = BTreeSet::new();
+    let params: Vec<&str> = cfg.params.iter().map(|p| p.name.as_str()).collect();
+    // in call order, so mockResolvedValueOnce lines queue up the way the calls consume them
+    for &n in &path.nodes {
+        let Some((_, local)) = cfg.results.iter().find(|(node, _)| *node == n) else { continue };
+        let Some((_, name, mock)) = calls.iter().find(|(nodes, _, _)| nodes.contains(&n)) else { continue };
+        let (Some(r), Some(shape)) = (mock_ref(name, mock), shape_of(name, mock, schema)) else { continue };
+        if !done.insert(local.as_str()) { continue; }
+        // every condition on the result, first value per subject
+        let mut inputs: Vec<(Vec<&str>, String)> = Vec::new();
+        let mut avoid = Vec::new();
+        for d in &path.decisions {
+            let mut mentioned = false;
+            for i in &d.inputs {
+                let rest: Vec<&str> = if i.subject == *local { vec![] } else {
+                    match i.subject.strip_prefix(&format!("{}.", local)).filter(|r| r.split('.').all(is_ident)) {
+                        Some(r) => r.split('.').collect(),
+                        None => continue,
+                    }
+                };
+                mentioned = true;
+                let Some(v) = i.values.first() else { continue };
+                let root = v.split(['.', ' ']).next().unwrap_or_default();
+                if !Boundary::is_concrete(v) && !params.contains(&root) && !args.iter().any(|a| a == root) { continue; }
+                if !inputs.iter().any(|(s, _)| *s == rest) { inputs.push((rest, v.clone())); }
+            }
+            // literals the condition compares against: `loan.status !== 'PENDING'`
+            if mentioned {
+                avoid.extend(d.condition.split(['\'', '"']).skip(1).step_by(2));
+            }
+        }
+        if let Some(v) = shape.value_for(&inputs, &avoid) {
+            out.push((format!("    {}.mockResolvedValueOnce({});", mock_target(&r, mock, fw), v), shape.factory().filter(|f| v.contains(f.as_str()))));
+        }
+    }
+    out
+}
+
+// Argument declarations for a path, filled in from its decisions' boundary values. The first value a
+// subject gets wins; values relative to another input (`officer.limit + 1`) are assigned once every
+// argument exists, after giving that other input a value of its own.
+fn arrange(path: &CfgPath, args: &[String], params: &[Param]) -> Vec<String> {
+    let mut chosen: Vec<(String, String)> = Vec::new();
+    for i in path.decisions.iter().flat_map(|d| &d.inputs) {
+        if let Some(v) = i.values.first()
+            && !chosen.iter().any(|(s, _)| s == &i.subject)
+        {
+            chosen.push((i.subject.clone(), v.clone()));
+        }
+    }
+    let relative: Vec<String> = chosen.iter()
+        .filter(|(_, v)| !Boundary::is_concrete(v))
+        .map(|(_, v)| v.trim_end_matches(" + 1").trim_end_matches(" - 1").to_string())
+        .collect();
+    for other in relative {
+        if !chosen.iter().any(|(s, _)| *s == other) { chosen.push((other, "100".to_string())); }
+    }
+
+    let mut lines = Vec::new();
+    let mut later = Vec::new();
+    for (a, p) in args.iter().zip(params) {
+        let array = p.ty.as_deref().is_some_and(|t| t.ends_with("[]") || t.starts_with("Array<"));
+        // `'x'.repeat(n)` is the length-n stand-in; an array parameter gets n items instead
+        let fit = |v: &str| match v.strip_prefix("'x'.repeat(").and_then(|n| n.strip_suffix(')')).and_then(|n| n.parse().ok()) {
+            Some(n) if array => items(n),
+            _ => v.to_string(),
+        };
+        let mut init = None;
+        let mut fields: Vec<(Vec<&str>, String)> = Vec::new();
+        for (subject, v) in &chosen {
+            if *subject == p.name {
+                if Boundary::is_concrete(v) { init = init.or(Some(fit(v))); }
+            } else if let Some(rest) = subject.strip_prefix(&format!("{}.", p.name))
+                && rest.split('.').all(is_ident)
+            {
+                if Boundary::is_concrete(v) {
+                    fields.push((rest.split('.').collect(), v.clone()));
+                } else if !rest.contains('.') {
+                    later.push(format!("    {}.{} = {};", a, rest, v));
+                }
+            }
+        }
+        let ty = p.ty.as_deref().map(|t| format!(" // {}", t)).unwrap_or_default();
+        lines.push(format!("    const {}: any = {};{}", a, init.unwrap_or_else(|| object_literal(&fields)), ty));
+    }
+    lines.extend(later);
+    lines
+}
+
+// { amount: 11, profile: { age: 18 } }
+fn object_literal(fields: &[(Vec<&str>, String)]) -> String {
+    if fields.is_empty() { return "{}".to_string(); }
+    let mut groups: BTreeMap<&str, Vec<(Vec<&str>, String)>> = BTreeMap::new();
+    for (path, v) in fields {
+        groups.entry(path[0]).or_default().push((path[1..].to_vec(), v.clone()));
+    }
+    let parts: Vec<String> = groups.iter().map(|(k, sub)| match sub.iter().find(|(p, _)| p.is_empty()) {
+        Some((_, v)) => format!("{}: {}", k, v),
+        None => format!("{}: {}", k, object_literal(sub)),
+    }).collect();
+    format!("{{ {} }}", parts.join(", "))
+}
+
+fn anchor(func: &str) -> String {
+    format!("  // casesmith:new-tests {}", func)
+}
+
+// The anchor as found in a file, so `approve` doesn't match `approveAll`'s
+fn anchor_line(func: &str) -> String {
+    format!("{}\n", anchor(func))
+}
+
+impl Describe {
+    fn render(&self) -> String {
+        let mut out = vec![format!("describe({}, () => {{", quote(&self.func)), self.setup.clone()];
+        for (_, t) in &self.tests {
+            out.push(String::new());
+            out.push(t.clone());
+        }
+        out.push(String::new());
+        out.push(anchor(&self.func));
+        out.push("});".to_string());
+        out.join("\n")
+    }
+}
+
+impl Spec {
+    /// The spec as a fresh file
+    pub fn render(&self) -> String {
+        let mut out = vec![self.header.clone()];
+        for d in &self.describes {
+            out.push(String::new());
+            out.push(d.render());
+        }
+        out.join("\n") + "\n"
+    }
+
+    /// Update a previously generated spec in place: casesmith:begin/end blocks are replaced, tests for
+    /// new paths go in before each describe's `casesmith:new-tests` line, new functions are appended.
+    /// Everything else (edited test bodies, hand-written tests) is left alone. Returns the text and the
+    /// number of tests added.
+    pub fn merge(&self, old: &str) -> (String, usize) {
+        let mut text = old.to_string();
+        let mut added = 0;
+        replace_block(&mut text, "header", &self.header);
+        for d in &self.describes {
+            let marker = anchor_line(&d.func);
+            let start = text.find(&format!("describe({}, ", quote(&d.func)));
+            let (Some(start), Some(end)) = (start, text.find(&marker)) else {
+                text = format!("{}\n{}\n", text.trim_end(), d.render());
+                added += d.tests.len();
+                continue;
+            };
+            replace_block(&mut text, &format!("setup {}", d.func), &d.setup);
+            let existing = &text[start..end];
+            let fresh: Vec<&String> = d.tests.iter()
+                .filter(|(name, _)| !existing.contains(&format!("it({},", quote(name))))
+                .map(|(_, t)| t)
+                .collect();
+            added += fresh.len();
+            let insert: String = fresh.iter().map(|t| format!("{}\n\n", t)).collect();
+            // re-find: the setup block may have changed length
+            let at = text.find(&marker).unwrap_or(end);
+            text.insert_str(at, &insert);
+        }
+        (text, added)
+    }
+}
+
+// Swap the lines from `// casesmith:begin <id>` to `// casesmith:end <id>` for `block`
+fn replace_block(text: &mut String, id: &str, block: &str) {
+    let begin = format!("// casesmith:begin {}\n", id);
+    let end = format!("// casesmith:end {}", id);
+    let Some(b) = text.find(&begin) else { return };
+    let Some(e) = text[b..].find(&format!("{}\n", end)).map(|e| b + e + end.len()) else { return };
+    let line_start = text[..b].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    text.replace_range(line_start..e, block);
+}
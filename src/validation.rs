@@ -0,0 +1,150 @@
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+use tree_sitter::Node;
+
+use crate::classify::identifiers;
+use crate::tag::{has_tag, is_entrypoint, snippet, EdgeKind};
+use crate::SimpleCfg;
+
+// class-validator decorators that are not spelled @IsXxx
+const RULE_DECORATORS: &[&str] = &[
+    "Min", "Max", "Length", "MinLength", "MaxLength", "Matches", "Contains", "NotContains", "Equals", "NotEquals",
+    "ArrayMinSize", "ArrayMaxSize", "ArrayNotEmpty", "ArrayUnique", "ArrayContains", "ValidateNested", "ValidateIf",
+    "MinDate", "MaxDate", "Allow",
+];
+
+/// A DTO field and the class-validator decorators on it (`CreateLoanDto.amount` → `@IsInt()`, `@Min(1000)`)
+#[derive(Debug, Clone, Serialize)]
+pub struct DtoRule {
+    pub type_name: String,
+    pub field: String,
+    pub rules: Vec<String>,
+    pub optional: bool,
+    pub line: usize,
+}
+
+/// Collect class-validator rules from the property declarations of every class in a file.
+pub fn collect_dto_rules(code: &str, root: Node) -> Vec<DtoRule> {
+    let mut out = Vec::new();
+    let mut stack = vec![root];
+    while let Some(n) = stack.pop() {
+        for i in 0..n.child_count() { stack.push(n.child(i).unwrap()); }
+        if !matches!(n.kind(), "class_declaration" | "abstract_class_declaration" | "class") { continue; }
+        let (Some(body), Some(name)) = (n.child_by_field_name("body"), n.child_by_field_name("name")) else { continue };
+        let type_name = &code[name.start_byte()..name.end_byte()];
+        for i in 0..body.named_child_count() {
+            let f = body.named_child(i).unwrap();
+            if f.kind() != "public_field_definition" { continue; }
+            let Some(fname) = f.child_by_field_name("name") else { continue };
+            let decorators: Vec<String> = (0..f.child_count())
+                .filter_map(|j| f.child(j))
+                .filter(|c| c.kind() == "decorator")
+                .map(|d| snippet(code, d))
+                .collect();
+            let optional = decorators.iter().any(|d| decorator_name(d) == "IsOptional");
+            let rules: Vec<String> = decorators.into_iter()
+                .filter(|d| is_class_validator(decorator_name(d)) && decorator_name(d) != "IsOptional")
+                .collect();
+            if rules.is_empty() { continue; }
+            out.push(DtoRule {
+                type_name: type_name.to_string(),
+                field: code[fname.start_byte()..fname.end_byte()].to_string(),
+                rules,
+                optional,
+                line: f.start_position().row + 1,
+            });
+        }
+    }
+    out.sort_by(|a, b| (a.line, &a.field).cmp(&(b.line, &b.field)));
+    out
+}
+
+// "@IsEmail({ allow_display_name: true })" → "IsEmail"
+fn decorator_name(deco: &str) -> &str {
+    deco.trim_start_matches('@').split(['(', ' ']).next().unwrap_or("")
+}
+
+fn is_class_validator(name: &str) -> bool {
+    let is_x = name.strip_prefix("Is").and_then(|r| r.chars().next()).is_some_and(|c| c.is_ascii_uppercase());
+    is_x || RULE_DECORATORS.contains(&name)
+}
+
+/// Repo-wide set of DTO classes that carry class-validator rules
+#[derive(Debug, Default, Clone)]
+pub struct DtoCatalogue {
+    types: HashSet<String>,
+}
+
+impl DtoCatalogue {
+    pub fn add(&mut self, rules: &[DtoRule]) {
+        self.types.extend(rules.iter().map(|r| r.type_name.clone()));
+    }
+
+    /// True if a type annotation (`CreateLoanDto`, `CreateLoanDto[]`) names a class with validation rules.
+    pub fn is_validated(&self, ty: &str) -> bool {
+        identifiers(ty).any(|t| self.types.contains(t))
+    }
+}
+
+/// Whether an entrypoint (route or message handler) validates the inputs it receives
+#[derive(Debug, Clone, Serialize)]
+pub struct EntrypointValidation {
+    pub file: String,
+    pub func: String,
+    pub validated: bool,
+    pub inputs: Vec<String>,
+    pub unvalidated: Vec<String>,
+    /// The VALIDATE nodes or pipes that cover the validated inputs
+    pub via: Vec<String>,
+}
+
+/// True if the CFG registers a global `ValidationPipe` (`app.useGlobalPipes(new ValidationPipe())`)
+pub fn has_global_pipe(cfg: &SimpleCfg) -> bool {
+    cfg.nodes.iter().any(|n| has_tag(n, EdgeKind::Validate) && n.contains("useGlobalPipes"))
+}
+
+/// Check each input of an entrypoint against the VALIDATE nodes in its CFG.
+/// Inputs are the decorated request parameters (`@Body`, `@Query`, `@Param`, ..), or every parameter of a
+/// message handler. An input counts as validated if a VALIDATE node mentions it, the handler has a
+/// `@UsePipes` pipe, or a global ValidationPipe is registered and its type is a DTO with class-validator rules.
+pub fn entrypoint_validation(
+    file: &str,
+    func: &str,
+    cfg: &SimpleCfg,
+    dtos: &DtoCatalogue,
+    global_pipe: bool,
+) -> Option<EntrypointValidation> {
+    if !cfg.nodes.iter().any(|n| is_entrypoint(n)) { return None; }
+    let mut inputs: Vec<_> = cfg.params.iter().filter(|p| p.source.is_some()).collect();
+    if inputs.is_empty() && cfg.nodes.iter().any(|n| n.starts_with("QUEUE ENTRY")) {
+        inputs = cfg.params.iter().collect();
+    }
+    let checks: Vec<&str> = cfg.nodes.iter()
+        .filter(|n| has_tag(n, EdgeKind::Validate))
+        .map(|n| n.trim_start_matches("VALIDATE: "))
+        .collect();
+    let handler_pipe = checks.iter().find(|c| c.starts_with("@UsePipes"));
+
+    let mut via = BTreeSet::new();
+    let mut unvalidated = Vec::new();
+    for p in &inputs {
+        let dto = p.ty.as_deref().filter(|t| dtos.is_validated(t));
+        if let Some(c) = checks.iter().find(|c| identifiers(c).any(|t| t == p.name)) {
+            via.insert(c.to_string());
+        } else if let Some(c) = handler_pipe {
+            via.insert(c.to_string());
+        } else if global_pipe && let Some(t) = dto {
+            via.insert(format!("global ValidationPipe ({})", t));
+        } else {
+            unvalidated.push(p.name.clone());
+        }
+    }
+    Some(EntrypointValidation {
+        file: file.to_string(),
+        func: func.to_string(),
+        validated: unvalidated.is_empty(),
+        inputs: inputs.iter().map(|p| p.name.clone()).collect(),
+        unvalidated,
+        via: via.into_iter().collect(),
+    })
+}
@@ -0,0 +1,139 @@
+use tree_sitter::Node;
+
+use crate::classify::words;
+use crate::findings::{Finding, Severity};
+use crate::tag::{call_name, enclosing_function};
+
+/// bcrypt cost factors below this are reported (OWASP recommends at least 10)
+pub const MIN_BCRYPT_COST: u32 = 10;
+
+// Words that mark a value as security-relevant randomness (tokens, OTPs, reset codes, salts)
+const SECRET_WORDS: &[&str] = &["token", "otp", "pin", "code", "nonce", "secret", "password", "salt", "key", "verification", "reset", "session"];
+
+/// Weak hashing/ciphers, static IVs, low bcrypt costs, `Math.random()` tokens and unsafe JWT options.
+pub fn check_crypto_call(code: &str, call: Node) -> Vec<Finding> {
+    let Some(name) = call_name(code, call) else { return vec![] };
+    let lname = name.to_lowercase();
+    let op = lname.rsplit('.').next().unwrap_or("");
+    let args: Vec<Node> = call
+        .child_by_field_name("arguments")
+        .map(|a| (0..a.named_child_count()).filter_map(|i| a.named_child(i)).collect())
+        .unwrap_or_default();
+    let text = |n: Node| code[n.start_byte()..n.end_byte()].to_string();
+    let literal = |n: Option<&Node>| n.filter(|a| a.kind() == "string").map(|a| text(*a).trim_matches(['\'', '"', '`']).to_lowercase());
+    // jsonwebtoken takes options third (`sign(payload, key, opts)`), NestJS JwtService second (`sign(payload, opts)`)
+    let options = args.iter().skip(1).take(2).find(|a| a.kind() == "object");
+    let line = call.start_position().row + 1;
+    let finding = |rule: &'static str, severity: Severity, message: String, hint: &str| Finding {
+        rule: rule.to_string(), severity, line, message, hint: Some(hint.to_string()),
+    };
+    let mut out = Vec::new();
+
+    match op {
+        "createhash" => {
+            if let Some(alg) = literal(args.first()).filter(|a| ["md5", "md4", "sha1"].contains(&a.as_str())) {
+                let ctx = words(&statement_text(code, call));
+                let for_password = ctx.iter().any(|w| ["password", "passwd", "pwd", "pin"].contains(&w.as_str()))
+                    || has_secret_word(&enclosing_function(code, call));
+                let severity = if for_password { Severity::High } else { Severity::Low };
+                out.push(finding("weak-hash", severity, format!("`{}` uses broken hash {}", name, alg),
+                    "use bcrypt/argon2/scrypt for passwords and sha256+ for integrity checks"));
+            }
+        }
+        "createcipher" | "createdecipher" => {
+            out.push(finding("deprecated-cipher", Severity::High, format!("`{}` derives key and IV from a password without salt", name),
+                "use crypto.createCipheriv with a random 12/16-byte IV (e.g. aes-256-gcm)"));
+        }
+        "createcipheriv" | "createdecipheriv" => {
+            if let Some(alg) = literal(args.first()).filter(|a| a.contains("ecb")) {
+                out.push(finding("ecb-mode", Severity::High, format!("`{}` uses ECB mode ({})", name, alg),
+                    "use an authenticated mode such as aes-256-gcm"));
+            }
+            if let Some(iv) = args.get(2).filter(|iv| is_static_bytes(code, **iv)) {
+                out.push(finding("static-iv", Severity::High, format!("`{}` uses a constant IV `{}`", name, text(*iv)),
+                    "generate a fresh IV per message with crypto.randomBytes and store it with the ciphertext"));
+            }
+        }
+        "hash" | "hashsync" | "gensalt" | "gensaltsync" if lname.contains("bcrypt") => {
+            let cost_arg = if op.starts_with("gensalt") { args.first() } else { args.get(1) };
+            if let Some(cost) = cost_arg.filter(|a| a.kind() == "number").and_then(|a| text(*a).parse::<u32>().ok())
+                && cost < MIN_BCRYPT_COST
+            {
+                out.push(finding("weak-bcrypt-cost", Severity::Medium, format!("`{}` uses cost factor {} (< {})", name, cost, MIN_BCRYPT_COST),
+                    "use a bcrypt cost of at least 10 (12 for new systems)"));
+            }
+        }
+        "random" if lname == "math.random" => {
+            let ctx = random_context(code, call);
+            if has_secret_word(&ctx) {
+                out.push(finding("insecure-random", Severity::High, format!("`Math.random()` generates `{}`", ctx),
+                    "use crypto.randomInt / crypto.randomBytes / crypto.randomUUID for tokens and OTPs"));
+            }
+        }
+        "sign" if lname.contains("jwt") => {
+            let alg = options.and_then(|o| object_value(code, *o, "algorithm"));
+            if alg.is_some_and(|a| a.trim_matches(['\'', '"']).eq_ignore_ascii_case("none")) {
+                out.push(finding("jwt-alg-none", Severity::Critical, format!("`{}` signs with algorithm 'none'", name),
+                    "sign with HS256/RS256 and a real key"));
+            }
+        }
+        "verify" if lname.contains("jwt") && options.and_then(|o| object_value(code, *o, "algorithms")).is_none() => {
+            out.push(finding("jwt-no-algorithms", Severity::Medium, format!("`{}` does not pin `algorithms`", name),
+                "pass { algorithms: ['RS256'] } (or the algorithm you sign with) to prevent algorithm confusion"));
+        }
+        _ => {}
+    }
+    out
+}
+
+fn has_secret_word(ident: &str) -> bool {
+    words(ident).iter().any(|w| SECRET_WORDS.contains(&w.as_str()))
+}
+
+// 'static-iv', Buffer.from('...'), Buffer.alloc(16) (all zeros) or a literal byte array
+fn is_static_bytes(code: &str, n: Node) -> bool {
+    match n.kind() {
+        "string" | "template_string" | "array" => n.kind() != "template_string" || n.named_child_count() == 0,
+        "call_expression" => {
+            let name = call_name(code, n).unwrap_or_default();
+            let first = n.child_by_field_name("arguments").and_then(|a| a.named_child(0));
+            name == "Buffer.alloc" || (name == "Buffer.from" && first.is_some_and(|a| is_static_bytes(code, a)))
+        }
+        _ => false,
+    }
+}
+
+// Value text of `key` in an object literal argument
+fn object_value(code: &str, obj: Node, key: &str) -> Option<String> {
+    if obj.kind() != "object" { return None; }
+    (0..obj.named_child_count()).filter_map(|i| obj.named_child(i)).find_map(|p| {
+        let k = p.child_by_field_name("key").map(|k| &code[k.start_byte()..k.end_byte()])?;
+        (k.trim_matches(['\'', '"']) == key).then(|| p.child_by_field_name("value").map(|v| code[v.start_byte()..v.end_byte()].to_string()))?
+    })
+}
+
+fn statement_text(code: &str, n: Node) -> String {
+    let mut cur = n;
+    while let Some(p) = cur.parent() {
+        if p.kind().ends_with("statement") || p.kind() == "lexical_declaration" { return code[p.start_byte()..p.end_byte()].to_string(); }
+        cur = p;
+    }
+    code[n.start_byte()..n.end_byte()].to_string()
+}
+
+// Name the random value ends up in: declared variable, assignment target, object key or enclosing function
+fn random_context(code: &str, n: Node) -> String {
+    let mut cur = n;
+    while let Some(p) = cur.parent() {
+        let named = match p.kind() {
+            "variable_declarator" => p.child_by_field_name("name"),
+            "assignment_expression" => p.child_by_field_name("left"),
+            "pair" => p.child_by_field_name("key"),
+            _ => None,
+        };
+        if let Some(x) = named { return code[x.start_byte()..x.end_byte()].to_string(); }
+        if p.kind().ends_with("statement") && p.kind() != "return_statement" { break; }
+        cur = p;
+    }
+    enclosing_function(code, n)
+}
@@ -0,0 +1,50 @@
+use casesmith::classify::{collect_type_fields, DataClass, Taxonomy};
+use casesmith::config::parse_config;
+
+#[test]
+fn test_taxonomy_matches_identifier_words() {
+    let tax = Taxonomy::default();
+    assert!(tax.classify_identifier("borrowerDateOfBirth").contains(&DataClass::Pii));
+    assert!(tax.classify_identifier("BANK_ROUTING_NUMBER").contains(&DataClass::Financial));
+    assert!(tax.classify_identifier("refreshToken").contains(&DataClass::Credential));
+    assert!(tax.classify_identifier("lessons").is_empty());
+    assert!(tax.is_sensitive_type("LoanApplicationDto"));
+
+    let cfg = parse_config("[classification]\nfinancial = ['payoff_amount']\nreplace_defaults = true\n");
+    let custom = Taxonomy::from_config(&cfg.classification);
+    assert!(custom.classify_identifier("payoffAmount").contains(&DataClass::Financial));
+    assert!(custom.classify_identifier("ssn").is_empty());
+}
+
+#[test]
+fn test_type_fields_and_edge_classes() {
+    let code = r#"
+export interface BorrowerDto {
+    dob: string;
+    annualIncome: number;
+    nickname: string;
+}
+export class LoanService {
+    async save(dto: BorrowerDto) {
+        await this.prisma.borrower.create({ data: dto });
+    }
+}
+"#;
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()).unwrap();
+    let tree = parser.parse(code, None).unwrap();
+    let fields = collect_type_fields(code, tree.root_node(), &Taxonomy::default());
+    let names: Vec<String> = fields.iter().map(|f| format!("{}.{}", f.type_name, f.field)).collect();
+    assert_eq!(names, vec!["BorrowerDto.dob", "BorrowerDto.annualIncome"]);
+
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    std::fs::write(tmp.path().join("loan.ts"), code).unwrap();
+    casesmith::handle_generate(Some(tmp.path().to_str().unwrap().to_string()), "");
+    let flow: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(tmp.path().join(".casesmithresults/security-flow.json")).unwrap(),
+    ).unwrap();
+    let db_edge = flow["edges"].as_array().unwrap().iter().find(|e| e["kind"] == "db").unwrap();
+    assert_eq!(db_edge["classes"], serde_json::json!(["pii", "financial"]));
+    assert_eq!(flow["index"]["financial_edges"], 2);
+    assert!(tmp.path().join(".casesmithresults/data-classification.json").exists());
+}
@@ -0,0 +1,59 @@
+use casesmith::config::PathsConfig;
+use casesmith::extract_cfgs_from_code;
+use casesmith::paths::enumerate_paths;
+
+const UNDERWRITE: &str = r#"
+export function underwrite(score: number, income: Income, docs: Doc[], status?: string) {
+  if (score > 700 &&
+      income.monthly >= 2500.5) {
+    return 'prime';
+  }
+  if (status === 'FROZEN' || docs.length === 0) {
+    return 'decline';
+  }
+  if (income.employer == null) {
+    throw new Error('unverified');
+  }
+  if (typeof income.bonus !== 'number' || !('cosigner' in income)) {
+    return 'manual';
+  }
+  for (const doc of docs) {
+    verify(doc);
+  }
+  return 'review';
+}
+"#;
+
+#[test]
+fn test_conditions_parse_into_boundary_inputs() {
+    let cfg = &extract_cfgs_from_code(UNDERWRITE)["underwrite"];
+    let inputs: Vec<(&str, &str, Vec<&str>, Vec<&str>)> = cfg.conditions.iter()
+        .flat_map(|c| c.inputs.iter())
+        .map(|b| (b.subject.as_str(), b.kind, b.when_true.iter().map(String::as_str).collect(), b.when_false.iter().map(String::as_str).collect()))
+        .collect();
+    assert_eq!(inputs, vec![
+        ("score", "compare", vec!["701", "7000"], vec!["700", "0"]),
+        ("income.monthly", "compare", vec!["2500.5", "25005"], vec!["2500.49", "0"]),
+        ("status", "equals", vec!["'FROZEN'"], vec!["''"]),
+        ("docs", "length", vec!["'x'.repeat(0)"], vec!["'x'.repeat(1)"]),
+        ("income.employer", "null", vec!["null", "undefined"], vec!["{}"]),
+        ("income.bonus", "typeof", vec!["'text'"], vec!["1"]),
+        ("income", "in", vec!["{}"], vec!["{ cosigner: {} }"]),
+        ("docs", "iterable", vec!["[{}]"], vec!["[]"]),
+    ]);
+    // the whole condition, not just the first line of the statement
+    assert_eq!(cfg.conditions[0].text, "score > 700 && income.monthly >= 2500.5");
+}
+
+#[test]
+fn test_path_decisions_carry_inputs() {
+    let cfg = &extract_cfgs_from_code(UNDERWRITE)["underwrite"];
+    let paths = enumerate_paths(cfg, &PathsConfig::default());
+    let review = paths.simple.iter()
+        .find(|p| p.loops.first().is_some_and(|l| l.iterations == 2))
+        .unwrap();
+    let score = &review.decisions[0].inputs[0];
+    assert_eq!((score.subject.as_str(), score.values[0].as_str()), ("score", "700"));
+    let docs = &review.decisions.last().unwrap().inputs[0];
+    assert_eq!(docs.values, vec!["[{}, {}]"]);
+}
@@ -0,0 +1,82 @@
+use casesmith::coverage::{function_coverage, parse_lcov};
+use casesmith::config::PathsConfig;
+use casesmith::extract_cfgs_from_code;
+use casesmith::handle_coverage;
+use casesmith::paths::enumerate_paths;
+
+const CONTROLLER: &str = r#"@Controller('loans')
+export class LoanController {
+  constructor(private readonly prisma: PrismaService) {}
+
+  @Post(':id/approve')
+  @UseGuards(JwtAuthGuard)
+  async approve(@Param('id') id: string, @Req() req: Request) {
+    const loan = await this.prisma.loan.findUnique({ where: { id } });
+    if (loan.ownerId !== req.user.id) {
+      throw new ForbiddenException();
+    }
+    await this.prisma.loan.update({ where: { id }, data: { status: 'APPROVED' } });
+    return loan;
+  }
+}
+"#;
+
+// Only the happy path ran: the ownership check was never failed
+const ISTANBUL: &str = r#"{
+  "/ci/workspace/src/loan.controller.ts": {
+    "path": "/ci/workspace/src/loan.controller.ts",
+    "statementMap": {
+      "0": { "start": { "line": 8, "column": 4 }, "end": { "line": 8, "column": 71 } },
+      "1": { "start": { "line": 9, "column": 4 }, "end": { "line": 11, "column": 5 } },
+      "2": { "start": { "line": 10, "column": 6 }, "end": { "line": 10, "column": 37 } },
+      "3": { "start": { "line": 12, "column": 4 }, "end": { "line": 12, "column": 83 } },
+      "4": { "start": { "line": 13, "column": 4 }, "end": { "line": 13, "column": 16 } }
+    },
+    "s": { "0": 3, "1": 3, "2": 0, "3": 3, "4": 3 },
+    "fnMap": {
+      "0": { "name": "approve", "loc": { "start": { "line": 7, "column": 2 }, "end": { "line": 14, "column": 3 } } }
+    },
+    "f": { "0": 3 },
+    "branchMap": {
+      "0": { "type": "if", "loc": { "start": { "line": 9, "column": 4 }, "end": { "line": 11, "column": 5 } }, "line": 9 }
+    },
+    "b": { "0": [0, 3] }
+  }
+}"#;
+
+#[test]
+fn test_istanbul_coverage_reports_untested_auth_branch() {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+    std::fs::create_dir_all(tmp.path().join("coverage")).unwrap();
+    std::fs::write(tmp.path().join("src/loan.controller.ts"), CONTROLLER).unwrap();
+    std::fs::write(tmp.path().join("coverage/coverage-final.json"), ISTANBUL).unwrap();
+
+    let blockers = handle_coverage(Some(tmp.path().to_str().unwrap().to_string()), None, "");
+    assert_eq!(blockers, 1);
+
+    let report: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(tmp.path().join(".casesmithresults/coverage-gaps.json")).unwrap(),
+    ).unwrap();
+    assert_eq!((report["paths"].as_u64(), report["untested_paths"].as_u64()), (Some(3), Some(1)));
+    let gap = &report["gaps"][0];
+    assert_eq!(gap["func"], "LoanController.approve");
+    assert_eq!(gap["untested_paths"][0]["name"], "loan.ownerId !== req.user.id");
+    assert_eq!(gap["untested_branches"][0]["line"], 9);
+    assert_eq!(gap["untested_branches"][0]["taken"], true);
+    // the guard decorator ran with the handler
+    assert!(gap["untested_nodes"].as_array().unwrap().is_empty());
+    assert!(report["blockers"][0].as_str().unwrap().contains("line 9: loan.ownerId !== req.user.id is never true"));
+}
+
+#[test]
+fn test_lcov_lines_map_onto_tagged_nodes() {
+    let lcov = "SF:src/loan.controller.ts\nFN:7,approve\nFNDA:0,approve\nDA:8,0\nDA:9,0\nDA:10,0\nDA:12,0\nDA:13,0\nBRDA:9,0,0,-\nBRDA:9,0,1,-\nend_of_record\n";
+    let cov = parse_lcov(lcov);
+    let cfg = &extract_cfgs_from_code(CONTROLLER)["LoanController.approve"];
+    let paths = enumerate_paths(cfg, &PathsConfig::default());
+    let gaps = function_coverage("src/loan.controller.ts", "LoanController.approve", cfg, &paths, &cov["src/loan.controller.ts"]);
+    let untested: Vec<&str> = gaps.untested_nodes.iter().map(|n| n.label.split(':').next().unwrap()).collect();
+    assert_eq!(untested, vec!["AUTH", "DB", "AUTHZ", "DB"]);
+    assert_eq!(gaps.untested_paths.len(), 2);
+}
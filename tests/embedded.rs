@@ -0,0 +1,87 @@
+use casesmith::embedded::{parse_graphql, parse_sql};
+use casesmith::{extract_cfgs_from_code, handle_generate};
+
+#[test]
+fn test_parse_sql_tables_and_columns() {
+    let sel = &parse_sql("SELECT b.ssn, l.amount AS amt FROM borrowers b JOIN loans l ON l.borrower_id = b.id WHERE l.status = ?")[0];
+    assert_eq!(sel.operation, "select");
+    assert_eq!(sel.reads, vec!["borrowers.id", "borrowers.ssn", "loans.amount", "loans.borrower_id", "loans.status"]);
+    assert!(sel.writes.is_empty());
+
+    let ins = &parse_sql("INSERT INTO borrowers (ssn, dob) VALUES ($1, $2) ON CONFLICT (ssn) DO UPDATE SET dob = EXCLUDED.dob RETURNING id")[0];
+    assert_eq!(ins.writes, vec!["borrowers.dob", "borrowers.ssn"]);
+    assert_eq!(ins.reads, vec!["borrowers.id", "borrowers.ssn"]);
+
+    let upd = &parse_sql("UPDATE loans SET status = 'approved', approved_by = ? WHERE id = ?")[0];
+    assert_eq!(upd.writes, vec!["loans.approved_by", "loans.status"]);
+    assert_eq!(upd.reads, vec!["loans.id"]);
+
+    let del = &parse_sql("DELETE FROM documents WHERE loan_id = ?")[0];
+    assert_eq!(del.writes, vec!["documents.*"]);
+    assert!(parse_sql("not sql at all").is_empty());
+}
+
+#[test]
+fn test_parse_graphql_operations() {
+    let ops = parse_graphql(r#"
+        query GetBorrower($id: ID!) {
+          borrower(id: $id) { id ssn ...Income address { city } }
+        }
+        fragment Income on Borrower { annualIncome }
+    "#);
+    assert_eq!(ops.len(), 1);
+    assert_eq!(ops[0].name.as_deref(), Some("GetBorrower"));
+    assert_eq!(ops[0].fields, vec!["borrower.id", "borrower.ssn", "borrower.annualIncome", "borrower.address.city"]);
+
+    let m = &parse_graphql("mutation { createLoan(input: $input) { id } }")[0];
+    assert_eq!((m.kind.as_str(), m.roots.clone()), ("mutation", vec!["createLoan".to_string()]));
+}
+
+#[test]
+fn test_embedded_queries_annotate_cfg_and_flow() {
+    let code = r#"
+const GET_BORROWER = gql`query GetBorrower { borrower { ssn } }`;
+const INSERT_SQL = `INSERT INTO borrowers (ssn, dob) VALUES ($1, $2)`;
+
+@Controller('borrowers')
+export class BorrowerController {
+  @Post()
+  async create(@Body() dto: CreateBorrowerDto) {
+    await this.pg.query(INSERT_SQL, [dto.ssn, dto.dob]);
+    return this.apollo.query({ query: GET_BORROWER });
+  }
+}
+"#;
+    let cfg = &extract_cfgs_from_code(code)["BorrowerController.create"];
+    assert!(cfg.nodes.iter().any(|n| n.starts_with("DB: this.pg.query") && n.ends_with("[writes borrowers.dob, borrowers.ssn]")));
+    assert!(cfg.nodes.iter().any(|n| n.starts_with("NET: this.apollo.query") && n.ends_with("[query GetBorrower; reads borrower.ssn]")));
+    assert_eq!(cfg.queries.len(), 2);
+
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    std::fs::write(tmp.path().join("borrower.controller.ts"), code).unwrap();
+    handle_generate(Some(tmp.path().to_str().unwrap().to_string()), "");
+    let flow: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(tmp.path().join(".casesmithresults/security-flow.json")).unwrap(),
+    ).unwrap();
+    let da = &flow["data_access"][0];
+    assert_eq!(da["entrypoint"], true);
+    assert_eq!(da["writes"], serde_json::json!(["borrowers.dob", "borrowers.ssn"]));
+    assert_eq!(da["classes"], serde_json::json!(["pii"]));
+}
+
+#[test]
+fn test_const_resolution_is_scoped_and_stops_on_cycles() {
+    // the consts refer to each other across functions, and then within one block
+    let code = r#"
+function f() { const sql = text; }
+function g() { const text = sql; this.db.query(sql); }
+function h() { const a = b; const b = a; this.db.query(a); }
+function loans() { const sql = `SELECT id FROM loans`; this.db.query(sql); }
+function borrowers() { const sql = `DELETE FROM borrowers WHERE id = ?`; this.db.query(sql); }
+"#;
+    let cfgs = extract_cfgs_from_code(code);
+    assert!(cfgs["g"].queries.is_empty());
+    assert!(cfgs["h"].queries.is_empty());
+    assert_eq!(cfgs["loans"].queries[0].reads, vec!["loans.id"]);
+    assert_eq!(cfgs["borrowers"].queries[0].writes, vec!["borrowers.*"]);
+}
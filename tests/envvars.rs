@@ -0,0 +1,47 @@
+use casesmith::handle_generate;
+
+#[test]
+fn test_env_inventory_cross_checks_env_example() {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    std::fs::write(tmp.path().join("database.config.ts"), r#"
+export default registerAs('database', () => ({
+  host: process.env.DB_HOST,
+  port: parseInt(process.env['DB_PORT'] ?? '5432', 10),
+}));
+"#).unwrap();
+    std::fs::write(tmp.path().join("plaid.service.ts"), r#"
+export class PlaidService {
+  constructor(private readonly configService: ConfigService) {}
+
+  client() {
+    const { PLAID_ENV } = process.env;
+    return new PlaidApi(this.configService.get<string>('PLAID_SECRET'), PLAID_ENV);
+  }
+
+  dbHost() {
+    return this.configService.getOrThrow('database.host');
+  }
+}
+"#).unwrap();
+    std::fs::write(tmp.path().join(".env.example"), "DB_HOST=localhost\nDB_PORT=5432\n# Plaid\nPLAID_SECRET=\nLEGACY_FLAG=1\n").unwrap();
+
+    handle_generate(Some(tmp.path().to_str().unwrap().to_string()), "");
+    let results = tmp.path().join(".casesmithresults");
+    let inv: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(results.join("env-inventory.json")).unwrap()).unwrap();
+    let var = |name: &str| inv["vars"].as_array().unwrap().iter().find(|v| v["name"] == name).unwrap().clone();
+
+    assert_eq!(var("DB_HOST")["reads"][0]["func"], "registerAs('database')");
+    assert_eq!(var("DB_PORT")["documented"], true);
+    assert_eq!(var("PLAID_SECRET")["reads"][0]["func"], "PlaidService.client");
+    let db_host = var("database.host");
+    assert_eq!((db_host["kind"].as_str(), db_host["env"].as_str()), (Some("config"), Some("DB_HOST")));
+    assert_eq!(inv["undocumented"], serde_json::json!(["PLAID_ENV"]));
+    assert_eq!(inv["unread"], serde_json::json!(["LEGACY_FLAG"]));
+
+    let findings: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(results.join("findings.json")).unwrap()).unwrap();
+    let rules: Vec<(&str, u64)> = findings.as_array().unwrap().iter()
+        .filter(|f| f["rule"].as_str().unwrap().ends_with("-env"))
+        .map(|f| (f["rule"].as_str().unwrap(), f["line"].as_u64().unwrap()))
+        .collect();
+    assert_eq!(rules, vec![("unread-env", 5), ("undocumented-env", 6)]);
+}
@@ -0,0 +1,59 @@
+use casesmith::handle_export;
+
+const CONTROLLER: &str = r#"
+@Controller('loans')
+@UseGuards(JwtAuthGuard)
+export class LoanController {
+  constructor(private readonly prisma: PrismaService) {}
+
+  @Post(':id/approve')
+  async approve(@Param('id') id: string, @Body() dto: ApproveLoanDto, officer: User) {
+    if (dto.amount > officer.limit) {
+      throw new ForbiddenException('over limit');
+    }
+    await this.prisma.loan.update({ where: { id }, data: { status: 'APPROVED' } });
+    return { ok: true };
+  }
+}
+
+export class ApproveLoanDto {
+  @IsInt()
+  amount: number;
+}
+"#;
+
+#[test]
+fn test_csv_and_gherkin_export() {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+    std::fs::write(tmp.path().join("src/loan.controller.ts"), CONTROLLER).unwrap();
+    let root = Some(tmp.path().to_str().unwrap().to_string());
+
+    handle_export(root.clone(), None, "");
+    let csv = std::fs::read_to_string(tmp.path().join(".casesmithresults/test-cases.csv")).unwrap();
+    assert!(csv.starts_with("Title,Section,Preconditions,Steps,Expected Result\r\n"));
+    assert!(csv.contains(concat!(
+        "\"LoanController.approve: dto.amount > officer.limit\",\"LoanController.approve\",",
+        "\"The route is POST /loans/:id/approve\ndto.amount > officer.limit (e.g. dto.amount = officer.limit + 1)\",",
+        "\"1. Call LoanController.approve(id, dto, officer)\n2. Authentication check: @UseGuards(JwtAuthGuard)\n3. Check dto.amount > officer.limit: yes\",",
+        "\"1. Fails with new ForbiddenException('over limit')\n2. this.prisma.loan.update is not called\"\r\n",
+    )));
+    assert!(csv.contains("\"1. Send POST /loans/1/approve\n2. With body {\"\"amount\"\":1.5}\",\"1. Response status is 400\n"));
+    assert!(!csv.contains("constructor"));
+
+    handle_export(root, Some("gherkin".to_string()), "");
+    let feature = std::fs::read_to_string(tmp.path().join(".casesmithresults/features/src/loan.controller.feature")).unwrap();
+    assert!(feature.starts_with("Feature: src/loan.controller.ts\n\n  Rule: LoanController.approve\n"));
+    assert!(feature.contains(concat!(
+        "    Scenario: !(dto.amount > officer.limit)\n",
+        "      Given the route is POST /loans/:id/approve\n",
+        "      And !(dto.amount > officer.limit) (e.g. dto.amount = officer.limit)\n",
+        "      When call LoanController.approve(id, dto, officer)\n",
+        "      And authentication check: @UseGuards(JwtAuthGuard)\n",
+        "      And check dto.amount > officer.limit: no\n",
+        "      And database call: this.prisma.loan.update\n",
+        "      Then returns { ok: true }\n",
+        "      And this.prisma.loan.update is called\n",
+    )));
+    assert!(feature.contains("    @negative @auth\n    Scenario: rejects an invalid token\n      Given authorization header holds an invalid token\n"));
+}
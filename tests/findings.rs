@@ -0,0 +1,160 @@
+use casesmith::handle_generate;
+
+// Run `generate` over a temp dir holding the given files and return findings.json
+fn findings_for(files: &[(&str, &str)]) -> Vec<serde_json::Value> {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    for (name, code) in files {
+        std::fs::write(tmp.path().join(name), code).expect("write ts file");
+    }
+    handle_generate(Some(tmp.path().to_str().unwrap().to_string()), "");
+    let raw = std::fs::read_to_string(tmp.path().join(".casesmithresults/findings.json")).unwrap();
+    serde_json::from_str::<serde_json::Value>(&raw).unwrap().as_array().unwrap().clone()
+}
+
+fn rule_lines(findings: &[serde_json::Value], rule: &str) -> Vec<u64> {
+    findings.iter().filter(|f| f["rule"] == rule).map(|f| f["line"].as_u64().unwrap()).collect()
+}
+
+#[test]
+fn test_pii_in_log_detection() {
+    let findings = findings_for(&[
+        ("borrower.dto.ts", "export class BorrowerDto {\n  ssn: string;\n  annualIncome: number;\n}\n"),
+        ("borrower.service.ts", r#"export class BorrowerService {
+  async create(dto: BorrowerDto, req: Request) {
+    this.logger.info(`creating borrower ${dto.ssn}`);
+    console.log({ ...dto, at: Date.now() });
+    this.logger.debug(JSON.stringify(req.body));
+    this.logger.info('income', dto.annualIncome);
+    this.logger.info('masked', maskSsn(dto.ssn));
+    this.logger.info('created', dto.id);
+  }
+}
+"#),
+    ]);
+    assert_eq!(rule_lines(&findings, "pii-in-log"), vec![3, 4, 5, 6]);
+    let income = findings.iter().find(|f| f["line"] == 6).unwrap();
+    assert_eq!(income["severity"], "medium");
+    assert_eq!(income["func"], "BorrowerService.create");
+}
+
+#[test]
+fn test_hardcoded_secrets_are_found_and_redacted() {
+    let stripe_key = ["sk_live_", "51HcTq2LkQ8vRb7Zx0aPcD3eFgHiJ"].concat();
+    let aws_key = ["AKIA", "IOSFODNN7EXAMPLQ"].concat();
+    let code = format!(r#"import Stripe from 'stripe';
+const stripe = new Stripe('{stripe_key}');
+const AWS_KEY = '{aws_key}';
+export const JWT_SECRET = 'n0t-s0-r4nd0m-k3y';
+const apiKey = 'your_api_key_here';
+const label = 'Loan application received';
+export function sign(payload: object) {{
+    return jwt.sign(payload, 'Zq8!vR2#pL9$wX4m');
+}}
+"#);
+    let findings = findings_for(&[("keys.ts", code.as_str())]);
+    assert_eq!(rule_lines(&findings, "hardcoded-secret"), vec![2, 3, 4, 8]);
+    let stripe = &findings[0];
+    assert_eq!(stripe["severity"], "critical");
+    assert_eq!(findings[3]["func"], "sign");
+    let dump = serde_json::to_string(&findings).unwrap();
+    assert!(!dump.contains(&stripe_key) && !dump.contains(&aws_key));
+    assert!(dump.contains("[redacted"));
+}
+
+#[test]
+fn test_weak_crypto_rules() {
+    let findings = findings_for(&[("crypto.ts", r#"export class AuthService {
+  hashPassword(password: string) {
+    return crypto.createHash('md5').update(password).digest('hex');
+  }
+  encrypt(data: string) {
+    const c = crypto.createCipheriv('aes-128-ecb', KEY, Buffer.alloc(16));
+    const d = crypto.createCipher('aes192', KEY);
+    return bcrypt.hash(data, 8);
+  }
+  generateOtp() {
+    const otp = Math.floor(Math.random() * 1000000);
+    const jitter = Math.random() * 100;
+    return jwt.sign({ otp }, KEY, { algorithm: 'none' });
+  }
+  check(token: string) {
+    jwt.verify(token, KEY, { algorithms: ['RS256'] });
+    return jwt.verify(token, KEY);
+  }
+}
+"#)]);
+    let rules: Vec<(&str, u64, &str)> = findings
+        .iter()
+        .map(|f| (f["rule"].as_str().unwrap(), f["line"].as_u64().unwrap(), f["severity"].as_str().unwrap()))
+        .collect();
+    assert_eq!(rules, vec![
+        ("weak-hash", 3, "high"),
+        ("ecb-mode", 6, "high"),
+        ("static-iv", 6, "high"),
+        ("deprecated-cipher", 7, "high"),
+        ("weak-bcrypt-cost", 8, "medium"),
+        ("insecure-random", 11, "high"),
+        ("jwt-alg-none", 13, "critical"),
+        ("jwt-no-algorithms", 17, "medium"),
+    ]);
+    assert!(findings.iter().all(|f| f["hint"].is_string()));
+}
+
+#[test]
+fn test_weak_hash_escalates_on_whole_words_only() {
+    let findings = findings_for(&[("labels.ts", r#"export class LabelService {
+  etag(shippingMapping: string) {
+    return crypto.createHash('sha1').update(shippingMapping).digest('hex');
+  }
+  lock(userPin: string) {
+    return crypto.createHash('md5').update(userPin).digest('hex');
+  }
+}
+"#)]);
+    let hashes: Vec<(u64, &str)> = findings
+        .iter()
+        .filter(|f| f["rule"] == "weak-hash")
+        .map(|f| (f["line"].as_u64().unwrap(), f["severity"].as_str().unwrap()))
+        .collect();
+    assert_eq!(hashes, vec![(3, "low"), (6, "high")]);
+}
+
+#[test]
+fn test_jwt_options_in_nest_and_jsonwebtoken_shapes() {
+    let findings = findings_for(&[("tokens.ts", r#"export class TokenService {
+  issue(p: Payload) {
+    this.jwtService.sign(p, { algorithm: 'none' });
+    return jwt.sign(p, KEY, { algorithm: 'none' });
+  }
+  check(token: string) {
+    this.jwtService.verify(token, { algorithms: ['HS256'] });
+    jwt.verify(token, KEY, { algorithms: ['RS256'] });
+    this.jwtService.verify(token, { ignoreExpiration: false });
+    return jwt.verify(token, KEY);
+  }
+}
+"#)]);
+    assert_eq!(rule_lines(&findings, "jwt-alg-none"), vec![3, 4]);
+    assert_eq!(rule_lines(&findings, "jwt-no-algorithms"), vec![9, 10]);
+}
+
+#[test]
+fn test_sql_injection_in_raw_queries() {
+    let findings = findings_for(&[("loans.repo.ts", r#"export class LoanRepo {
+  async search(name: string, sort: string, id: string) {
+    await this.prisma.$queryRawUnsafe(`SELECT * FROM loans WHERE name = '${name}'`);
+    await this.prisma.$queryRaw`SELECT * FROM loans WHERE id = ${id}`;
+    await this.dataSource.query('SELECT * FROM loans WHERE id = ' + id);
+    await this.dataSource.query('SELECT * FROM loans WHERE id = $1', [id]);
+    await this.repo.createQueryBuilder('l').where(`l.name = '${name}'`).getMany();
+    await this.repo.createQueryBuilder('l').where('l.name = :name', { name }).getMany();
+    await knex.raw(`SELECT * FROM loans ORDER BY ${sort}`);
+    await client.query({ text: 'SELECT * FROM ' + LOANS_TABLE + ' WHERE id = $1', values: [id] });
+    return pool.query(`DELETE FROM loans WHERE id = ${id}`);
+  }
+}
+"#)]);
+    assert_eq!(rule_lines(&findings, "sql-injection"), vec![3, 5, 7, 9, 11]);
+    assert_eq!(findings[0]["severity"], "critical");
+    assert!(findings[0]["message"].as_str().unwrap().contains("`name`"));
+}
@@ -4,7 +4,7 @@ use std::fs;
 use std::env;
 use std::path::Path;
 
-use casesmith::{handle_run, handle_generate};
+use casesmith::{handle_run, handle_generate, run_report};
 
 #[test]
 fn test_handle_generate_with_existing_samplets() {
@@ -42,9 +42,31 @@ fn test_config_toml_read() {
 
 #[test]
 fn test_handle_run_basic() {
-    // Should print config and parse dummy code
+    // Should parse an inline snippet and print its tree
     let config = "[section]\nkey = 'value'\n";
-    handle_run("testname".to_string(), true, 1, config);
+    let code = "function helloWorld(param:string):void {\n    console.log('Hello, world!');\n}";
+    handle_run(None, Some(code.to_string()), None, false, true, config);
+}
+
+#[test]
+fn test_run_report_tree_and_json() {
+    let code = "function check(amount: number) {\n  if (amount > 100) {\n    return fetch('/big');\n  }\n  return null;\n}";
+    let tree = run_report("<code>", code, false, false, false, "");
+    assert_eq!(tree, concat!(
+        "<code> · typescript · 1 function\n",
+        "└── check(amount: number) · line 1\n",
+        "    ├── tags: NET 1\n",
+        "    └── cfg (6 nodes, 6 edges)\n",
+        "        ├── [0] Entry → 2\n",
+        "        ├── [1] Exit\n",
+        "        ├── [2] If: if (amount > 100) { → 3 (true), 5 (false)\n",
+        "        ├── [3] NET: fetch('/big') [GET] → 4\n",
+        "        ├── [4] Return: return fetch('/big'); → 1\n",
+        "        └── [5] Return: return null; → 1\n",
+    ));
+    let json: serde_json::Value = serde_json::from_str(&run_report("a.tsx", "const A = () => <div />;", true, true, false, "")).unwrap();
+    assert_eq!(json["grammar"], "tsx");
+    assert!(json["functions"]["A"]["nodes"].is_array());
 }
 
 #[test]
@@ -0,0 +1,126 @@
+use casesmith::handle_negative;
+
+const CONTROLLER: &str = r#"
+import { exec } from 'child_process';
+
+@Controller('loans')
+@UseGuards(JwtAuthGuard)
+export class LoanController {
+  constructor(private readonly prisma: PrismaService) {}
+
+  @Post(':id/approve')
+  @Roles('officer')
+  async approve(@Param('id') loanId: string, @Body() dto: ApproveLoanDto) {
+    return this.prisma.$queryRawUnsafe(`UPDATE loan SET amount = ${dto.amount} WHERE id = ${loanId}`);
+  }
+
+  @Get()
+  list(@Query('status') status: string) {
+    exec(`report --status ${status}`);
+  }
+}
+"#;
+
+const DTO: &str = r#"
+export class ApproveLoanDto {
+  @IsInt()
+  @Min(1000)
+  amount: number;
+
+  @IsEmail()
+  @IsOptional()
+  notify?: string;
+}
+"#;
+
+fn write(tmp: &std::path::Path) {
+    std::fs::create_dir_all(tmp.join("src/loans")).unwrap();
+    std::fs::write(tmp.join("src/loans/loan.controller.ts"), CONTROLLER).unwrap();
+    std::fs::write(tmp.join("src/loans/approve-loan.dto.ts"), DTO).unwrap();
+}
+
+#[test]
+fn test_negative_case_list_per_route() {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    write(tmp.path());
+    handle_negative(Some(tmp.path().to_str().unwrap().to_string()), None, "");
+    let routes: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(tmp.path().join(".casesmithresults/negative-cases.json")).unwrap(),
+    ).unwrap();
+    let approve = routes.as_array().unwrap().iter().find(|r| r["func"] == "LoanController.approve").unwrap();
+    assert_eq!(approve["method"], "POST");
+    assert_eq!(approve["path"], "/loans/:id/approve");
+    assert_eq!(approve["roles"], serde_json::json!(["officer"]));
+    assert_eq!(approve["dto"], "ApproveLoanDto");
+    let titles: Vec<&str> = approve["cases"].as_array().unwrap().iter().map(|c| c["title"].as_str().unwrap()).collect();
+    assert_eq!(titles, vec![
+        "rejects a request without a token",
+        "rejects an invalid token",
+        "rejects a user without officer",
+        "rejects amount violating @IsInt()",
+        "rejects amount violating @Min(1000)",
+        "rejects a body without amount",
+        "rejects notify violating @IsEmail()",
+        "rejects an oversized body",
+        "rejects SQL injection in param id",
+        "rejects SQL injection in amount",
+        "rejects NoSQL operator injection in amount",
+    ]);
+    let min = &approve["cases"][4];
+    assert_eq!(min["body"], serde_json::json!({ "amount": 999 }));
+    assert_eq!(min["auth"], "valid");
+    assert_eq!(approve["cases"][8]["path"], "/loans/%27%20OR%20%271%27%3D%271/approve");
+
+    let list = routes.as_array().unwrap().iter().find(|r| r["func"] == "LoanController.list").unwrap();
+    let injection = list["cases"].as_array().unwrap().iter().find(|c| c["category"] == "injection").unwrap();
+    assert_eq!(injection["query"], serde_json::json!({ "status": "; cat /etc/passwd" }));
+    assert_eq!(injection["expect"], serde_json::json!([400, 403, 404, 422]));
+}
+
+#[test]
+fn test_supertest_specs_and_setup() {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    write(tmp.path());
+    let root = Some(tmp.path().to_str().unwrap().to_string());
+    handle_negative(root.clone(), Some("supertest".to_string()), "");
+    let spec_path = tmp.path().join("src/loans/loan.controller.casesmith.e2e-spec.ts");
+    let spec = std::fs::read_to_string(&spec_path).unwrap();
+    assert!(spec.contains("import { createApp, tokenFor } from '../../casesmith.e2e-setup';"));
+    assert!(spec.contains("describe('POST /loans/:id/approve', () => {"));
+    assert!(spec.contains("const res = await request(app.getHttpServer()).post('/loans/1/approve')\n      .set('Authorization', 'Bearer invalid.token.value')"));
+    assert!(spec.contains("      .set('Authorization', `Bearer ${await tokenFor([\"officer\"])}`)\n      .send({\"amount\":999});\n    expect(res.status).toBe(400);"));
+    assert!(spec.contains(".send({ ...{\"amount\":1000}, data: 'x'.repeat(1048577) });\n    expect(res.status).toBe(413);"));
+    assert!(tmp.path().join("casesmith.e2e-setup.ts").is_file());
+
+    // a rerun keeps edits and adds nothing
+    std::fs::write(&spec_path, spec.replace("toBe(403)", "toBe(404)")).unwrap();
+    handle_negative(root, Some("supertest".to_string()), "");
+    let rerun = std::fs::read_to_string(&spec_path).unwrap();
+    assert!(rerun.contains("toBe(404)"));
+    assert_eq!(rerun.matches("it('rejects an invalid token'").count(), 2);
+}
+
+#[test]
+fn test_injection_cases_once_per_sink_kind() {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    std::fs::write(tmp.path().join("doc.controller.ts"), r#"
+@Controller('docs')
+export class DocController {
+  @Get(':id')
+  async read(@Param('id') id: string) {
+    await this.prisma.$queryRawUnsafe(`SELECT * FROM doc WHERE id = ${id}`);
+    const body = await fs.promises.readFile(`/srv/docs/${id}`);
+    return this.prisma.$queryRawUnsafe(`UPDATE doc SET reads = reads + 1 WHERE id = ${id}`);
+  }
+}
+"#).unwrap();
+    handle_negative(Some(tmp.path().to_str().unwrap().to_string()), None, "");
+    let routes: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(tmp.path().join(".casesmithresults/negative-cases.json")).unwrap(),
+    ).unwrap();
+    let titles: Vec<&str> = routes[0]["cases"].as_array().unwrap().iter()
+        .filter(|c| c["category"] == "injection")
+        .map(|c| c["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, vec!["rejects SQL injection in param id", "rejects path traversal in param id"]);
+}
@@ -0,0 +1,82 @@
+use casesmith::{extract_cfgs_from_code, handle_generate};
+
+const BUREAU_CLIENT: &str = r#"
+const STRIPE_API = 'https://api.stripe.com/v1';
+
+@Injectable()
+export class ExperianClient {
+  private http = axios.create({
+    baseURL: process.env.EXPERIAN_BASE_URL,
+    headers: { Authorization: `Bearer ${process.env.EXPERIAN_TOKEN}`, 'Content-Type': 'application/json' },
+  });
+
+  async pullReport(ssn: string) {
+    return this.http.post('/v2/credit-report', { ssn });
+  }
+
+  async charge(amount: number) {
+    await fetch(`${STRIPE_API}/charges`, { method: 'POST', headers: { 'Idempotency-Key': key, 'X-Client-Secret': secret }, body: JSON.stringify({ amount }) });
+  }
+
+  async rates() {
+    return axios.get('https://rates.example.org/today', { auth: { username: 'u', password: p } });
+  }
+
+  async proxy(url: string) {
+    return axios({ url, method: 'put' });
+  }
+}
+"#;
+
+#[test]
+fn test_outbound_calls_record_method_host_and_credentials() {
+    let cfgs = extract_cfgs_from_code(BUREAU_CLIENT);
+
+    let pull = &cfgs["ExperianClient.pullReport"];
+    assert!(pull.nodes.iter().any(|n| n == "NET: this.http.post('/v2/credit-report', { ssn }) [POST $EXPERIAN_BASE_URL]"));
+    let call = &pull.outbound[0];
+    assert_eq!((call.client.as_str(), call.method.as_str()), ("this.http", "POST"));
+    assert_eq!(call.base_url.as_deref(), Some("process.env.EXPERIAN_BASE_URL"));
+    assert_eq!(call.env.as_deref(), Some("EXPERIAN_BASE_URL"));
+    assert_eq!(call.credential_headers, vec!["Authorization"]);
+
+    let charge = &cfgs["ExperianClient.charge"].outbound[0];
+    assert_eq!((charge.method.as_str(), charge.host.as_deref()), ("POST", Some("api.stripe.com")));
+    assert_eq!(charge.credential_headers, vec!["X-Client-Secret"]);
+
+    let rates = &cfgs["ExperianClient.rates"].outbound[0];
+    assert_eq!((rates.method.as_str(), rates.host.as_deref()), ("GET", Some("rates.example.org")));
+    assert_eq!(rates.credential_headers, vec!["auth"]);
+
+    let proxy = &cfgs["ExperianClient.proxy"].outbound[0];
+    assert_eq!((proxy.method.as_str(), proxy.host.as_deref(), proxy.url.as_deref()), ("PUT", None, Some("url")));
+}
+
+#[test]
+fn test_external_hosts_inventory_resolves_env_bases() {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    std::fs::write(tmp.path().join("experian.client.ts"), BUREAU_CLIENT).unwrap();
+    std::fs::write(tmp.path().join(".env.example"), "# bureau\nEXPERIAN_BASE_URL=https://sandbox-us-api.experian.com\n").unwrap();
+    handle_generate(Some(tmp.path().to_str().unwrap().to_string()), "");
+    let flow: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(tmp.path().join(".casesmithresults/security-flow.json")).unwrap(),
+    ).unwrap();
+    let hosts: Vec<&str> = flow["external_hosts"].as_array().unwrap().iter().map(|h| h["host"].as_str().unwrap()).collect();
+    assert_eq!(hosts, vec!["api.stripe.com", "rates.example.org", "sandbox-us-api.experian.com"]);
+    let experian = &flow["external_hosts"][2];
+    assert_eq!(experian["env"], "EXPERIAN_BASE_URL");
+    assert_eq!(experian["credential_headers"], serde_json::json!(["Authorization"]));
+    assert_eq!(flow["index"]["external_hosts"], 3);
+}
+
+#[test]
+fn test_destination_stops_on_cyclic_consts() {
+    let code = r#"
+function f() { const url = base; return url; }
+function g() { const base = url; return axios.get(url); }
+function h() { const a = b || 'https://fallback.example.com'; const b = a; return axios.get(a); }
+"#;
+    let cfgs = extract_cfgs_from_code(code);
+    assert_eq!(cfgs["g"].outbound[0].host, None);
+    assert_eq!(cfgs["h"].outbound[0].host.as_deref(), Some("fallback.example.com"));
+}
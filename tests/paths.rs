@@ -0,0 +1,84 @@
+use casesmith::config::PathsConfig;
+use casesmith::extract_cfgs_from_code;
+use casesmith::paths::enumerate_paths;
+
+const APPROVAL: &str = r#"
+export class LoanService {
+  async approve(loan: Loan, officer: User) {
+    if (loan.amount > officer.limit) {
+      throw new ForbiddenException('over limit');
+    }
+    for (const doc of loan.documents) {
+      await this.prisma.document.update({ where: { id: doc.id }, data: { verified: true } });
+    }
+    if (loan.status === 'PENDING') {
+      await this.prisma.loan.update({ where: { id: loan.id }, data: { status: 'APPROVED' } });
+    } else {
+      this.logger.warn('already decided');
+    }
+    return loan;
+  }
+}
+"#;
+
+#[test]
+fn test_paths_enumerate_decisions_and_loop_unrolling() {
+    let cfg = &extract_cfgs_from_code(APPROVAL)["LoanService.approve"];
+    let paths = enumerate_paths(cfg, &PathsConfig::default());
+
+    // two ifs and a loop
+    assert_eq!(paths.complexity, 4);
+    // throw, or loop 0/1/2 times × status branch
+    assert_eq!(paths.simple.len(), 7);
+    assert!(!paths.truncated);
+
+    let throws = &paths.simple.iter().find(|p| p.decisions.len() == 1).unwrap().decisions[0];
+    assert_eq!((throws.condition.as_str(), throws.taken), ("loan.amount > officer.limit", true));
+
+    let twice: Vec<(&str, bool)> = paths.simple.iter()
+        .find(|p| p.loops.first().is_some_and(|l| l.iterations == 2) && p.decisions.last().unwrap().taken)
+        .unwrap()
+        .decisions.iter().map(|d| (d.condition.as_str(), d.taken)).collect();
+    assert_eq!(twice, vec![
+        ("loan.amount > officer.limit", false),
+        ("const doc of loan.documents", true),
+        ("const doc of loan.documents", true),
+        ("const doc of loan.documents", false),
+        ("loan.status === 'PENDING'", true),
+    ]);
+
+    // each basis path adds a branch outcome; together they take every one
+    assert_eq!(paths.basis.len(), 4);
+    let outcomes: std::collections::BTreeSet<(usize, bool)> =
+        paths.basis.iter().flat_map(|p| p.decisions.iter().map(|d| (d.node, d.taken))).collect();
+    assert_eq!(outcomes.len(), 6);
+    // the loop body cycle is a prime path
+    assert!(paths.prime.iter().any(|p| p.nodes.len() > 1 && p.nodes.first() == p.nodes.last()));
+}
+
+#[test]
+fn test_paths_stay_bounded_with_many_sequential_ifs() {
+    // 24 ifs, then more DB calls than `max_length` allows on one path
+    let mut body = String::new();
+    for i in 0..24 {
+        body.push_str(&format!("    if (input.flag{i}) {{ await this.audit.log('{i}'); }}\n"));
+    }
+    for i in 0..70 {
+        body.push_str(&format!("    await this.prisma.loan.update({{ where: {{ id }}, data: {{ step: {i} }} }});\n"));
+    }
+    let code = format!("export class LoanService {{\n  async run(id: string, input: Input) {{\n{body}  }}\n}}\n");
+    let cfg = &extract_cfgs_from_code(&code)["LoanService.run"];
+
+    let start = std::time::Instant::now();
+    let paths = enumerate_paths(cfg, &PathsConfig::default());
+    assert!(start.elapsed() < std::time::Duration::from_secs(2), "took {:?}", start.elapsed());
+    assert_eq!(paths.complexity, 25);
+    assert!(paths.truncated);
+    assert!(paths.simple.is_empty());
+    // the basis falls back to shortest paths through each branch outcome
+    assert_eq!(paths.basis.len(), 25);
+    let outcomes: std::collections::BTreeSet<(usize, bool)> =
+        paths.basis.iter().flat_map(|p| p.decisions.iter().map(|d| (d.node, d.taken))).collect();
+    assert_eq!(outcomes.len(), 48);
+    assert!(paths.basis.iter().all(|p| p.nodes.first() == Some(&0) && p.nodes.last() == Some(&1)));
+}
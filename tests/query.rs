@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+
+use casesmith::classify::{Taxonomy, TypeCatalogue};
+use casesmith::handle_generate;
+use casesmith::query::{Query, Target};
+use casesmith::{extract_cfgs_from_code, SimpleCfg};
+
+const CODE: &str = r#"
+export async function pushKey() {
+  const key = process.env.BUREAU_API_KEY;
+  await axios.post('https://bureau.example.com/keys', { key });
+}
+
+export async function pushSealed() {
+  const key = process.env.BUREAU_API_KEY;
+  const sealed = crypto.createCipheriv('aes-256-gcm', key, iv);
+  await axios.post('https://bureau.example.com/keys', { sealed });
+}
+
+export async function ping() {
+  await axios.get('https://bureau.example.com/health');
+}
+"#;
+
+fn funcs() -> BTreeMap<String, SimpleCfg> {
+    extract_cfgs_from_code(CODE).into_iter().map(|(f, cfg)| (format!("keys.ts::{}", f), cfg)).collect()
+}
+
+fn ids(q: &str) -> Vec<String> {
+    let hits = Query::parse(q).unwrap().run(&funcs(), &Taxonomy::default(), &TypeCatalogue::default());
+    hits.into_iter().map(|h| h.id).collect()
+}
+
+#[test]
+fn test_query_language_over_cfgs_and_edges() {
+    assert_eq!(ids("NET after SECRET without CRYPTO"), vec!["keys.ts::pushKey"]);
+    assert_eq!(ids("functions where NET after SECRET"), vec!["keys.ts::pushKey", "keys.ts::pushSealed"]);
+    assert_eq!(ids("SECRET before NET without CRYPTO|VALIDATE"), vec!["keys.ts::pushKey"]);
+    assert_eq!(ids("NET(\"health\") or (CRYPTO and not name(\"push\"))"), vec!["keys.ts::ping"]);
+    assert_eq!(ids("edges where kind(net) and class(credential)"), vec!["keys.ts::pushKey"]);
+
+    let hit = &Query::parse("NET after SECRET without CRYPTO").unwrap().run(&funcs(), &Taxonomy::default(), &TypeCatalogue::default())[0];
+    assert_eq!(hit.line, 4);
+    assert!(hit.detail.starts_with("SECRET: process.env"), "{}", hit.detail);
+    assert!(hit.detail.contains(" → NET: axios.post("), "{}", hit.detail);
+
+    assert_eq!(Query::parse("edges where src(\"env\")").unwrap().target, Target::Edges);
+    assert_eq!(Query::parse("functions where kind(net)").unwrap_err(), "kind() only applies to edge queries (`edges where ..`)");
+    assert!(Query::parse("NET after BOGUS").unwrap_err().starts_with("Unknown node tag 'bogus'"));
+    assert_eq!(Query::parse("NET and").unwrap_err(), "Expected a condition, found end of query");
+}
+
+#[test]
+fn test_named_queries_run_as_checks() {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    std::fs::write(tmp.path().join("keys.ts"), CODE).unwrap();
+    let config = r#"
+[queries.secret-sent-in-clear]
+query = "NET after SECRET without CRYPTO"
+severity = "high"
+message = "A secret reaches an outbound request unencrypted"
+"#;
+    handle_generate(Some(tmp.path().to_str().unwrap().to_string()), config);
+    let findings: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(tmp.path().join(".casesmithresults/findings.json")).unwrap()).unwrap();
+    let custom: Vec<&serde_json::Value> = findings.as_array().unwrap().iter().filter(|f| f["rule"] == "secret-sent-in-clear").collect();
+    assert_eq!(custom.len(), 1);
+    assert_eq!(custom[0]["func"], "pushKey");
+    assert_eq!(custom[0]["severity"], "high");
+    assert_eq!(custom[0]["line"], 4);
+}
@@ -0,0 +1,78 @@
+use casesmith::classify::Taxonomy;
+use casesmith::handle_generate;
+use casesmith::repl::Session;
+
+const CONTROLLER: &str = r#"
+@Controller('loans')
+export class LoanController {
+  constructor(private readonly loanService: LoanService) {}
+
+  @UseGuards(JwtAuthGuard)
+  @Post(':id/approve')
+  async approve(@Param('id') id: string) {
+    return this.loanService.approve(id);
+  }
+
+  @Get(':id')
+  view(@Param('id') id: string) {
+    return this.loanService.find(id);
+  }
+}
+"#;
+
+const SERVICE: &str = r#"
+export class LoanService {
+  async approve(id: string) {
+    const loan = await this.find(id);
+    await fetch('https://bureau.example.com/score');
+    return this.prisma.loan.update({ where: { id }, data: { status: 'approved' } });
+  }
+
+  async find(id: string) {
+    return this.prisma.loan.findUnique({ where: { id } });
+  }
+}
+"#;
+
+fn write_root() -> tempfile::TempDir {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+    std::fs::write(tmp.path().join("src/loan.controller.ts"), CONTROLLER).unwrap();
+    std::fs::write(tmp.path().join("src/loan.service.ts"), SERVICE).unwrap();
+    tmp
+}
+
+#[test]
+fn test_repl_queries_over_analyzed_root() {
+    let tmp = write_root();
+    let s = Session::load(tmp.path(), &Taxonomy::default()).unwrap();
+
+    assert_eq!(
+        s.eval("routes db-writes"),
+        "POST /loans/:id/approve  src/loan.controller.ts::LoanController.approve → src/loan.service.ts::LoanService.approve  \
+         DB: this.prisma.loan.update({ where: { id }, data: { status: 'approved' } })"
+    );
+    assert_eq!(s.eval("callers LoanService.find"), "src/loan.controller.ts::LoanController.view\nsrc/loan.service.ts::LoanService.approve");
+    assert!(s.eval("callees LoanService.approve").starts_with("this.find → src/loan.service.ts::LoanService.find\nfetch\n"));
+    assert_eq!(s.eval("edges net").lines().count(), 2);
+    assert!(s.eval("edges bogus").starts_with("Unknown edge kind 'bogus'"));
+    assert!(s.eval("cfg approve").contains("ambiguous"));
+
+    // IDs complete from their function name as well as their path
+    let (start, ids) = s.complete("callers LoanSer", 15);
+    assert_eq!(start, 8);
+    assert_eq!(ids, vec!["src/loan.service.ts::LoanService.approve", "src/loan.service.ts::LoanService.find"]);
+    assert_eq!(s.complete("ed", 2), (0, vec!["edges".to_string()]));
+}
+
+#[test]
+fn test_repl_loads_results_dir() {
+    let tmp = write_root();
+    handle_generate(Some(tmp.path().to_str().unwrap().to_string()), "");
+    let analyzed = Session::load(tmp.path(), &Taxonomy::default()).unwrap();
+    let loaded = Session::load(&tmp.path().join(".casesmithresults"), &Taxonomy::default()).unwrap();
+
+    assert_eq!(loaded.eval("functions"), analyzed.eval("functions"));
+    assert_eq!(loaded.eval("routes db-writes"), analyzed.eval("routes db-writes"));
+    assert_eq!(loaded.eval("callers LoanService.find"), analyzed.eval("callers LoanService.find"));
+}
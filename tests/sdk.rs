@@ -0,0 +1,77 @@
+use casesmith::{extract_cfgs_from_code, handle_generate};
+
+const FUNDING: &str = r#"
+import Stripe from 'stripe';
+import * as sgMail from '@sendgrid/mail';
+import { S3Client, PutObjectCommand } from '@aws-sdk/client-s3';
+import { ExperianService } from '../bureaus/experian.service';
+
+const s3 = new S3Client({ region: 'us-east-1' });
+
+@Injectable()
+export class FundingService {
+  private stripe = new Stripe(key, { apiVersion: '2024-06-20' });
+
+  constructor(private readonly plaid: PlaidApi, private readonly experian: ExperianService) {}
+
+  async fund(loan: Loan, borrower: BorrowerDto) {
+    const report = await this.experian.pullCreditReport(borrower.ssn);
+    const txns = await this.plaid.transactionsGet({ access_token: loan.plaidToken });
+    await this.stripe.paymentIntents.create({ amount: loan.amount, currency: 'usd' });
+    await s3.send(new PutObjectCommand({ Bucket: 'statements', Key: loan.id }));
+    await sgMail.send({ to: borrower.email, subject: 'Funded' });
+  }
+}
+"#;
+
+#[test]
+fn test_sdk_calls_tagged_with_vendor_and_operation() {
+    let cfg = &extract_cfgs_from_code(FUNDING)["FundingService.fund"];
+    let mut ops: Vec<String> = cfg.sdk_calls.iter().map(|c| format!("{}.{}", c.vendor, c.operation)).collect();
+    ops.sort();
+    assert_eq!(ops, vec![
+        "aws.s3.PutObject",
+        "experian.pullCreditReport",
+        "plaid.transactionsGet",
+        "sendgrid.send",
+        "stripe.paymentIntents.create",
+    ]);
+    assert!(cfg.nodes.iter().any(|n| n == "SDK: this.plaid.transactionsGet({ access_token: loan.plaidToken }) [plaid.transactionsGet]"));
+    // the S3 command itself is still a storage write
+    assert!(cfg.nodes.iter().any(|n| n.starts_with("STORAGE: new PutObjectCommand")));
+}
+
+#[test]
+fn test_aws_send_with_command_in_a_variable() {
+    let code = r#"
+import { S3Client, PutObjectCommand } from '@aws-sdk/client-s3';
+
+const s3 = new S3Client({ region: 'us-east-1' });
+
+export async function archive(loan: Loan, command: any) {
+  const cmd = new PutObjectCommand({ Bucket: 'statements', Key: loan.id });
+  await s3.send(cmd);
+  await s3.send(command);
+}
+"#;
+    let cfg = &extract_cfgs_from_code(code)["archive"];
+    let ops: Vec<String> = cfg.sdk_calls.iter().map(|c| format!("{}.{}", c.vendor, c.operation)).collect();
+    assert_eq!(ops, vec!["aws.s3.PutObject", "aws.s3.send"]);
+    assert!(cfg.nodes.iter().any(|n| n == "SDK: s3.send(cmd) [aws.s3.PutObject]"));
+}
+
+#[test]
+fn test_vendor_summary_in_security_flow() {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    std::fs::write(tmp.path().join("funding.service.ts"), FUNDING).unwrap();
+    handle_generate(Some(tmp.path().to_str().unwrap().to_string()), "");
+    let flow: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(tmp.path().join(".casesmithresults/security-flow.json")).unwrap(),
+    ).unwrap();
+    let vendors: Vec<&str> = flow["vendors"].as_array().unwrap().iter().map(|v| v["vendor"].as_str().unwrap()).collect();
+    assert_eq!(vendors, vec!["aws", "experian", "plaid", "sendgrid", "stripe"]);
+    let experian = &flow["vendors"][1];
+    assert_eq!(experian["operations"], serde_json::json!(["pullCreditReport"]));
+    assert_eq!(experian["classes"], serde_json::json!(["pii"]));
+    assert_eq!(flow["index"]["vendors"], 5);
+}
@@ -0,0 +1,194 @@
+use casesmith::{extract_cfgs_from_code, handle_generate};
+
+#[test]
+fn test_authz_tags_from_route_decorators_and_ownership_checks() {
+    let code = r#"
+@Controller('loans')
+export class LoanController {
+  @Post(':id/approve')
+  @UseGuards(AuthGuard('jwt'), RolesGuard)
+  @Roles('underwriter')
+  async approve(@Param('id') id: string) {
+    return this.prisma.loan.update({ where: { id } });
+  }
+
+  @Put(':id')
+  @UseGuards(JwtAuthGuard)
+  async edit(@Param('id') id: string, @Req() req) {
+    const loan = await this.prisma.loan.findUnique({ where: { id } });
+    if (loan.ownerId !== req.user.id) throw new ForbiddenException();
+    return this.prisma.loan.update({ where: { id } });
+  }
+}
+"#;
+    let cfgs = extract_cfgs_from_code(code);
+    let approve = &cfgs["LoanController.approve"];
+    assert!(approve.nodes.iter().any(|n| n == "USER ENTRY (Nest route)"));
+    assert!(approve.nodes.iter().any(|n| n.starts_with("AUTH: @UseGuards")));
+    assert!(approve.nodes.iter().any(|n| n == "AUTHZ: @Roles('underwriter')"));
+    let edit = &cfgs["LoanController.edit"];
+    assert!(edit.nodes.iter().any(|n| n.starts_with("AUTHZ: ownership check")));
+    assert!(!edit.nodes.iter().any(|n| n.starts_with("AUTHZ: @")));
+}
+
+#[test]
+fn test_security_flow_reports_authn_without_authz() {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    std::fs::write(tmp.path().join("loans.controller.ts"), r#"
+@Controller('loans')
+export class LoanController {
+  @Delete(':id')
+  @UseGuards(AuthGuard('jwt'))
+  async remove(@Param('id') id: string) {
+    await this.prisma.loan.delete({ where: { id } });
+  }
+
+  @Get(':id')
+  @UseGuards(AuthGuard('jwt'))
+  async get(@Param('id') id: string) {
+    return this.prisma.loan.findUnique({ where: { id } });
+  }
+}
+"#).expect("write ts file");
+    handle_generate(Some(tmp.path().to_str().unwrap().to_string()), "");
+    let flow: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(tmp.path().join(".casesmithresults/security-flow.json")).unwrap(),
+    ).unwrap();
+    assert_eq!(flow["index"]["authn_without_authz"], 1);
+    assert_eq!(flow["authz_gaps"][0]["func"], "LoanController.remove");
+}
+
+#[test]
+fn test_casl_ability_check_before_write_is_not_a_gap() {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    std::fs::write(tmp.path().join("loans.controller.ts"), r#"
+@Controller('loans')
+export class LoanController {
+  @Patch(':id')
+  @UseGuards(AuthGuard('jwt'))
+  async update(@Param('id') id: string, @Body() dto: UpdateLoanDto, @Req() req) {
+    const loan = await this.prisma.loan.findUnique({ where: { id } });
+    const ability = this.abilities.createForUser(req.user);
+    if (!ability.can('update', loan)) {
+      throw new ForbiddenException();
+    }
+    await this.prisma.loan.update({ where: { id }, data: dto });
+  }
+
+  @Put(':id/status')
+  @UseGuards(AuthGuard('jwt'))
+  async setStatus(@Param('id') id: string, @Body() dto: StatusDto, @Req() req) {
+    if (dto.force) {
+      await this.prisma.loan.update({ where: { id }, data: dto });
+    }
+    if (!req.ability.can('update', 'Loan')) {
+      throw new ForbiddenException();
+    }
+  }
+}
+"#).expect("write ts file");
+    handle_generate(Some(tmp.path().to_str().unwrap().to_string()), "");
+    let flow: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(tmp.path().join(".casesmithresults/security-flow.json")).unwrap(),
+    ).unwrap();
+    // the write after the ability check is guarded; the one that can happen first is not
+    assert_eq!(flow["index"]["authn_without_authz"], 1);
+    assert_eq!(flow["authz_gaps"][0]["func"], "LoanController.setStatus");
+}
+
+#[test]
+fn test_exec_and_code_eval_sinks() {
+    let code = r#"
+import { exec } from 'child_process';
+export function convert(file: string, expr: string, plugin: string) {
+    child_process.execSync(`convert ${file} out.pdf`);
+    exec('ls ' + file);
+    const f = new Function('x', expr);
+    vm.runInNewContext(expr, {});
+    const p = require(plugin);
+    const cfg = require('./config');
+    /\d+/.exec(file);
+    return eval(expr);
+}
+"#;
+    let cfg = &extract_cfgs_from_code(code)["convert"];
+    let count = |prefix: &str| cfg.nodes.iter().filter(|n| n.starts_with(prefix)).count();
+    assert_eq!(count("EXEC: "), 2);
+    assert_eq!(count("EVAL: "), 4);
+    assert!(cfg.nodes.iter().any(|n| n == "EVAL: new Function('x', expr)"));
+    assert!(!cfg.nodes.iter().any(|n| n.contains("./config")));
+}
+
+#[test]
+fn test_storage_sinks_capture_bucket_key_and_path() {
+    let code = r#"
+export async function storeDocument(loanId: string, body: Buffer, name: string) {
+    const p = path.join(UPLOAD_DIR, name);
+    await fs.promises.writeFile(p, body);
+    await s3.putObject({ Bucket: 'borrower-docs', Key: `loans/${loanId}.pdf`, Body: body });
+    await client.send(new PutObjectCommand({ Bucket: DOCS_BUCKET, Key: name }));
+    await storage.bucket('kyc').file(name).save(body);
+    const tpl = path.join(__dirname, 'templates');
+}
+"#;
+    let cfg = &extract_cfgs_from_code(code)["storeDocument"];
+    let storage: Vec<&String> = cfg.nodes.iter().filter(|n| n.starts_with("STORAGE: ")).collect();
+    assert!(storage.iter().any(|n| n.ends_with("[path=UPLOAD_DIR, name]")));
+    assert!(storage.iter().any(|n| n.starts_with("STORAGE: fs.promises.writeFile") && n.ends_with("[path=p]")));
+    assert!(storage.iter().any(|n| n.ends_with("[bucket='borrower-docs' key=`loans/${loanId}.pdf`]")));
+    assert!(storage.iter().any(|n| n.ends_with("[bucket=DOCS_BUCKET key=name]")));
+    assert!(storage.iter().any(|n| n.ends_with("[bucket='kyc' key=name]")));
+    assert!(!storage.iter().any(|n| n.contains("__dirname")));
+}
+
+#[test]
+fn test_queue_producers_and_consumers() {
+    let code = r#"
+@Controller()
+export class LoanEvents {
+  @EventPattern('loan.approved')
+  async onApproved(@Payload() data: LoanDto) {
+    await this.producer.send({ topic: 'loan.disbursement', messages: [{ value: data.id }] });
+    await this.channel.sendToQueue('notifications', Buffer.from(data.id));
+  }
+}
+
+export async function startConsumer() {
+  await consumer.subscribe({ topics: ['credit.pulled', 'credit.failed'] });
+  const w = new Worker('underwriting', processJob);
+}
+"#;
+    let cfgs = extract_cfgs_from_code(code);
+    let handler = &cfgs["LoanEvents.onApproved"];
+    assert!(handler.nodes.iter().any(|n| n == "QUEUE ENTRY (message handler) [topic=loan.approved]"));
+    assert!(handler.nodes.iter().any(|n| n.starts_with("QUEUE: this.producer.send") && n.ends_with("[topic=loan.disbursement]")));
+    assert!(handler.nodes.iter().any(|n| n.ends_with("[topic=notifications]")));
+    let consumer = &cfgs["startConsumer"];
+    assert!(consumer.nodes.iter().any(|n| n.starts_with("QUEUE ENTRY (consumer.subscribe") && n.ends_with("[topic=credit.pulled,credit.failed]")));
+    assert!(consumer.nodes.iter().any(|n| n.ends_with("[topic=underwriting]")));
+}
+
+#[test]
+fn test_browser_sinks_in_tsx() {
+    let code = r#"
+export function Statement({ html, next, token }: Props) {
+    document.getElementById('memo').innerHTML = html;
+    localStorage.setItem('access_token', token);
+    document.cookie = `session=${token}`;
+    if (next) { window.location.href = next; }
+    draft.location = next;
+    window.parent.postMessage({ token }, '*');
+    return <div dangerouslySetInnerHTML={{ __html: html }} />;
+}
+"#;
+    let cfg = &casesmith::extract_cfgs_from_tsx_code(code)["Statement"];
+    let has = |label: &str| cfg.nodes.iter().any(|n| n == label);
+    assert!(has("XSS: document.getElementById('memo').innerHTML = html"));
+    assert!(has("XSS: dangerouslySetInnerHTML={{ __html: html }}"));
+    assert!(has("CLIENT_STORAGE: localStorage.setItem('access_token', token)"));
+    assert!(has("CLIENT_STORAGE: document.cookie = `session=${token}`"));
+    assert!(has("REDIRECT: window.location.href = next"));
+    // a `location` field on an ordinary object is not navigation
+    assert_eq!(cfg.nodes.iter().filter(|n| n.starts_with("REDIRECT:")).count(), 1);
+    assert!(has("NET: window.parent.postMessage({ token }, '*')"));
+}
@@ -0,0 +1,132 @@
+use casesmith::handle_tests;
+
+const SERVICE: &str = r#"
+import axios from 'axios';
+
+@Injectable()
+export class LoanService {
+  constructor(private readonly prisma: PrismaService, private readonly audit: AuditService) {}
+
+  async approve(loan: Loan, officer: User) {
+    if (loan.amount > officer.limit) {
+      throw new ForbiddenException('over limit');
+    }
+    await this.prisma.loan.update({ where: { id: loan.id }, data: { status: 'APPROVED' } });
+    await axios.post('https://hooks.example.com/approved', { id: loan.id });
+    return loan;
+  }
+}
+"#;
+
+#[test]
+fn test_spec_skeleton_per_path_and_in_place_update() {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    let src = tmp.path().join("loan.service.ts");
+    let spec_path = tmp.path().join("loan.service.casesmith.spec.ts");
+    std::fs::write(&src, SERVICE).unwrap();
+    handle_tests(Some(tmp.path().to_str().unwrap().to_string()), None, "");
+
+    let spec = std::fs::read_to_string(&spec_path).unwrap();
+    assert!(spec.contains("jest.mock('axios');"));
+    assert!(spec.contains("import { LoanService } from './loan.service';"));
+    assert!(spec.contains("  const prisma = { loan: { update: jest.fn() } };"));
+    assert!(spec.contains("loanService = new LoanService(prisma as any, {} as any);"));
+    assert!(spec.contains("  it('loan.amount > officer.limit', async () => {"));
+    assert!(spec.contains(".rejects.toThrow();"));
+    assert!(spec.contains("  it('!(loan.amount > officer.limit)', async () => {"));
+    assert_eq!(spec.matches("expect(prisma.loan.update).toHaveBeenCalled();").count(), 1);
+    // boundary values from the condition fill in the arguments
+    assert!(spec.contains("    const officer: any = { limit: 100 }; // User\n    loan.amount = officer.limit + 1;"));
+
+    // hand edits survive a rerun; a new branch adds its tests next to them
+    let edited = spec.replacen("// TODO: assert on the result", "expect(prisma.loan.update.mock.calls[0][0].data.status).toBe('APPROVED');", 1);
+    std::fs::write(&spec_path, edited).unwrap();
+    std::fs::write(&src, SERVICE.replace("    return loan;", "    if (!officer.active) {\n      return null;\n    }\n    return loan;")).unwrap();
+    handle_tests(Some(tmp.path().to_str().unwrap().to_string()), Some("jest".to_string()), "");
+
+    let spec = std::fs::read_to_string(&spec_path).unwrap();
+    assert!(spec.contains("toBe('APPROVED');"));
+    assert!(spec.contains("  it('!(loan.amount > officer.limit), !officer.active', async () => {"));
+    assert!(spec.contains("  it('!(loan.amount > officer.limit), officer.active', async () => {"));
+    assert_eq!(spec.matches("// casesmith:begin header").count(), 1);
+    assert_eq!(spec.matches("describe('LoanService.approve'").count(), 1);
+}
+
+const SCHEMA: &str = r#"
+enum LoanStatus {
+  PENDING
+  APPROVED
+}
+
+model Loan {
+  id         Int        @id @default(autoincrement())
+  amount     Decimal
+  status     LoanStatus @default(PENDING)
+  borrower   User       @relation(fields: [borrowerId], references: [id])
+  borrowerId Int
+  note       String?
+}
+
+model User {
+  id    Int    @id @default(autoincrement())
+  email String @unique
+  loans Loan[]
+}
+"#;
+
+const FUNDING: &str = r#"
+import axios from 'axios';
+
+@Injectable()
+export class FundingService {
+  constructor(private readonly prisma: PrismaService) {}
+
+  async fund(id: number) {
+    const loan = await this.prisma.loan.findUnique({ where: { id } });
+    if (!loan) {
+      throw new NotFoundException();
+    }
+    if (loan.status !== 'PENDING') {
+      return loan;
+    }
+    if (loan.amount > 10000) {
+      throw new ForbiddenException('needs review');
+    }
+    const res = await axios.post('https://bureau.example.com/check', { id });
+    if (res.status !== 200) {
+      throw new Error('bureau down');
+    }
+    return loan;
+  }
+}
+"#;
+
+#[test]
+fn test_fixtures_from_prisma_schema_drive_branches() {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    std::fs::create_dir_all(tmp.path().join("prisma")).unwrap();
+    std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+    std::fs::write(tmp.path().join("prisma/schema.prisma"), SCHEMA).unwrap();
+    std::fs::write(tmp.path().join("src/funding.service.ts"), FUNDING).unwrap();
+    handle_tests(Some(tmp.path().to_str().unwrap().to_string()), None, "");
+
+    let fixtures = std::fs::read_to_string(tmp.path().join("casesmith.fixtures.ts")).unwrap();
+    assert!(fixtures.contains("export function loanFixture(overrides: Partial<Loan> = {}): Loan {"));
+    assert!(fixtures.contains("    amount: new Prisma.Decimal(1),\n    status: 'PENDING',\n    borrowerId: 1,\n    note: null,\n"));
+    // relations aren't part of the model type; untouched models get no factory
+    assert!(!fixtures.contains("borrower:") && !fixtures.contains("userFixture"));
+    assert!(fixtures.contains("export function axiosResponse<T>(data: T, status = 200): AxiosResponse<T> {"));
+
+    let spec = std::fs::read_to_string(tmp.path().join("src/funding.service.casesmith.spec.ts")).unwrap();
+    assert!(spec.contains("import { axiosResponse, loanFixture } from '../casesmith.fixtures';"));
+    assert!(spec.contains("    prisma.loan.findUnique.mockResolvedValue(loanFixture());"));
+    assert!(spec.contains("    jest.mocked(axios.post).mockResolvedValue(axiosResponse({}));"));
+    // each path's result values steer its branches
+    assert!(spec.contains("  it('!loan', async () => {\n    // arrange: !loan is true\n    const id: any = {}; // number\n    prisma.loan.findUnique.mockResolvedValueOnce(null);"));
+    assert!(spec.contains("prisma.loan.findUnique.mockResolvedValueOnce(loanFixture({ status: 'APPROVED' }));"));
+    assert!(spec.contains("jest.mocked(axios.post).mockResolvedValueOnce(axiosResponse({}, 201));"));
+    // Decimal overrides need the Prisma namespace in the spec, not just in the fixtures file
+    assert!(spec.contains("import { Prisma } from '@prisma/client';"));
+    assert!(spec.contains("loanFixture({ status: 'PENDING', amount: new Prisma.Decimal(10001) })"));
+    assert!(!tmp.path().join("casesmith.fixtures.casesmith.spec.ts").exists());
+}
@@ -0,0 +1,100 @@
+use casesmith::{extract_cfgs_from_code, handle_generate};
+
+#[test]
+fn test_validator_calls_become_validate_nodes() {
+    let code = r#"
+export function parseLoan(body: unknown) {
+  const input = LoanSchema.parse(body);
+  const cfg = JSON.parse(raw);
+  const addr = z.object({ zip: z.string() }).safeParse(body.address);
+  Joi.attempt(body.income, incomeSchema);
+  return input;
+}
+
+export async function checkDto(dto: CreateLoanDto) {
+  await validateOrReject(dto);
+}
+
+export const loanRules = () => [
+  body('email').isEmail().normalizeEmail(),
+  query('page').isInt({ min: 1 }),
+];
+"#;
+    let cfgs = extract_cfgs_from_code(code);
+    let validates = |f: &str| -> Vec<String> {
+        let mut v: Vec<String> = cfgs[f].nodes.iter().filter(|n| n.starts_with("VALIDATE: ")).cloned().collect();
+        v.sort();
+        v
+    };
+    assert_eq!(validates("parseLoan"), vec![
+        "VALIDATE: Joi.attempt(body.income, incomeSchema)",
+        "VALIDATE: LoanSchema.parse(body)",
+        "VALIDATE: z.object({ zip: z.string() }).safeParse(body.address)",
+    ]);
+    assert_eq!(validates("checkDto"), vec!["VALIDATE: validateOrReject(dto)"]);
+    // each express-validator chain is one node, not one per chained call
+    assert_eq!(validates("loanRules"), vec![
+        "VALIDATE: body('email').isEmail().normalizeEmail()",
+        "VALIDATE: query('page').isInt({ min: 1 })",
+    ]);
+}
+
+#[test]
+fn test_entrypoint_inputs_validated() {
+    let tmp = tempfile::tempdir().expect("create temp dir");
+    std::fs::write(tmp.path().join("main.ts"), r#"
+async function bootstrap() {
+  const app = await NestFactory.create(AppModule);
+  app.useGlobalPipes(new ValidationPipe({ whitelist: true }));
+}
+"#).unwrap();
+    std::fs::write(tmp.path().join("loan.dto.ts"), r#"
+export class CreateLoanDto {
+  @IsInt()
+  @Min(1000)
+  amount: number;
+
+  @IsOptional()
+  @IsString()
+  purpose?: string;
+}
+"#).unwrap();
+    std::fs::write(tmp.path().join("loan.controller.ts"), r#"
+@Controller('loans')
+export class LoanController {
+  @Post()
+  create(@Body() dto: CreateLoanDto) {
+    return this.loans.create(dto);
+  }
+
+  @Get(':id')
+  findOne(@Param('id', ParseIntPipe) id: number, @Query('expand') expand: string) {
+    return this.loans.find(id, expand);
+  }
+
+  @Post('notes')
+  addNote(@Body() note: NoteInput) {
+    const parsed = noteSchema.parse(note);
+    return this.notes.add(parsed);
+  }
+}
+"#).unwrap();
+    handle_generate(Some(tmp.path().to_str().unwrap().to_string()), "");
+    let results = tmp.path().join(".casesmithresults");
+    let flow: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(results.join("security-flow.json")).unwrap()).unwrap();
+    let entry = |f: &str| flow["entrypoints"].as_array().unwrap().iter().find(|e| e["func"] == f).unwrap().clone();
+
+    assert_eq!(entry("LoanController.create")["validated"], true);
+    assert_eq!(entry("LoanController.create")["via"], serde_json::json!(["global ValidationPipe (CreateLoanDto)"]));
+    assert_eq!(entry("LoanController.addNote")["via"], serde_json::json!(["noteSchema.parse(note)"]));
+    let find = entry("LoanController.findOne");
+    assert_eq!(find["validated"], false);
+    assert_eq!(find["unvalidated"], serde_json::json!(["expand"]));
+    assert_eq!(find["via"], serde_json::json!(["@Param('id', ParseIntPipe) id"]));
+    assert_eq!(flow["index"]["unvalidated_entrypoints"], 1);
+
+    let rules: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(results.join("validation.json")).unwrap()).unwrap();
+    let fields = &rules[0]["rules"];
+    assert_eq!(fields[0]["rules"], serde_json::json!(["@IsInt()", "@Min(1000)"]));
+    assert_eq!((fields[1]["field"].as_str(), fields[1]["optional"].as_bool()), (Some("purpose"), Some(true)));
+}